@@ -0,0 +1,33 @@
+//! Samples a loot table and prints the drop distribution.
+//!
+//! cargo run -p core_engine --example loot_sim -- <tables.loot.ron> <table> [samples] [seed]
+
+use core_engine::config::LootTables;
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (Some(path), Some(table)) = (args.first(), args.get(1)) else {
+        eprintln!("usage: loot_sim <tables.loot.ron> <table> [samples=1000000] [seed=0]");
+        std::process::exit(2);
+    };
+    let samples = args
+        .get(2)
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(1_000_000);
+    let seed = args.get(3).and_then(|s| s.parse().ok()).unwrap_or(0);
+
+    let text = std::fs::read_to_string(path).unwrap_or_else(|e| {
+        eprintln!("could not read {path}: {e}");
+        std::process::exit(1);
+    });
+    let tables: LootTables = ron::from_str(&text).unwrap_or_else(|e| {
+        eprintln!("could not parse {path}: {e}");
+        std::process::exit(1);
+    });
+    if tables.get(table).is_none() {
+        eprintln!("no table named {table:?} in {path}");
+        std::process::exit(1);
+    }
+
+    print!("{}", tables.sample_distribution(table, samples, seed));
+}
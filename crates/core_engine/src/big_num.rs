@@ -0,0 +1,555 @@
+use std::{
+    cmp::Ordering,
+    fmt,
+    iter::Sum,
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+
+// idle-game sized numbers. a plain f64 while it fits (exact integers up to 2^53),
+// mantissa in [1, 10) times 10^exponent past ±1e300, so it never overflows
+#[derive(Clone, Copy, Debug, Default, PartialEq, Reflect)]
+pub struct BigNum {
+    // the whole value when exponent == 0
+    mantissa: f64,
+    exponent: i64,
+}
+
+// below 1000 every notation prints the plain number
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Notation {
+    // 1.23K, 4.56M, ... 7.89Dc, then scientific
+    #[default]
+    Short,
+    // 4.56e12
+    Scientific,
+    // 1.23 million, ... 7.89 decillion, then scientific
+    Named,
+}
+
+const SHORT: [&str; 12] = [
+    "", "K", "M", "B", "T", "Qa", "Qi", "Sx", "Sp", "Oc", "No", "Dc",
+];
+const NAMED: [&str; 12] = [
+    "",
+    " thousand",
+    " million",
+    " billion",
+    " trillion",
+    " quadrillion",
+    " quintillion",
+    " sextillion",
+    " septillion",
+    " octillion",
+    " nonillion",
+    " decillion",
+];
+
+// plain f64 inside ±10^PLAIN
+const PLAIN: f64 = 300.0;
+// past this many digits apart, adding the smaller one changes nothing
+const MAX_DIGITS: i64 = 17;
+
+// split in two so 1e-200 * 10^450 doesn't overflow on the way
+fn scale10(m: f64, e: i64) -> f64 {
+    let e = e.clamp(-800, 800) as i32;
+    m * 10f64.powi(e / 2) * 10f64.powi(e - e / 2)
+}
+
+impl BigNum {
+    pub const ZERO: Self = Self {
+        mantissa: 0.0,
+        exponent: 0,
+    };
+    pub const ONE: Self = Self {
+        mantissa: 1.0,
+        exponent: 0,
+    };
+
+    // m * 10^e. NaN and infinities become zero so they never reach a save
+    pub fn new(mantissa: f64, exponent: i64) -> Self {
+        if mantissa == 0.0 || !mantissa.is_finite() {
+            return Self::ZERO;
+        }
+        let log = mantissa.abs().log10().floor();
+        let magnitude = log + exponent as f64;
+        if magnitude.abs() < PLAIN {
+            let plain = if exponent == 0 {
+                mantissa
+            } else {
+                scale10(mantissa, exponent)
+            };
+            return Self {
+                mantissa: plain,
+                exponent: 0,
+            };
+        }
+        let mut m = scale10(mantissa, -(log as i64));
+        let mut e = exponent.saturating_add(log as i64);
+        // log10 rounding at exact powers of ten
+        if m.abs() >= 10.0 {
+            m /= 10.0;
+            e += 1;
+        } else if m.abs() < 1.0 {
+            m *= 10.0;
+            e -= 1;
+        }
+        Self {
+            mantissa: m,
+            exponent: e,
+        }
+    }
+
+    // (mantissa in [1, 10), exponent), whatever the size
+    pub fn scientific(&self) -> (f64, i64) {
+        if self.exponent != 0 || self.is_zero() {
+            return (self.mantissa, self.exponent);
+        }
+        let mut e = self.mantissa.abs().log10().floor() as i64;
+        let mut m = scale10(self.mantissa, -e);
+        if m.abs() >= 10.0 {
+            m /= 10.0;
+            e += 1;
+        } else if m.abs() < 1.0 {
+            m *= 10.0;
+            e -= 1;
+        }
+        (m, e)
+    }
+
+    pub fn mantissa(&self) -> f64 {
+        self.scientific().0
+    }
+
+    pub fn exponent(&self) -> i64 {
+        self.scientific().1
+    }
+
+    fn is_plain(&self) -> bool {
+        self.exponent == 0
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mantissa == 0.0
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.mantissa < 0.0
+    }
+
+    // saturates to ±inf past f64 range
+    pub fn to_f64(&self) -> f64 {
+        if self.is_plain() {
+            return self.mantissa;
+        }
+        scale10(self.mantissa, self.exponent)
+    }
+
+    // whole units, clamped to 0..=u64::MAX. for item counts and the like
+    pub fn to_u64(&self) -> u64 {
+        if self.is_negative() {
+            return 0;
+        }
+        self.to_f64() as u64
+    }
+
+    pub fn abs(self) -> Self {
+        Self {
+            mantissa: self.mantissa.abs(),
+            ..self
+        }
+    }
+
+    pub fn floor(self) -> Self {
+        match (self.is_plain(), self.exponent > 0) {
+            (true, _) => Self::from(self.mantissa.floor()),
+            // no fraction left at this size
+            (false, true) => self,
+            (false, false) if self.is_negative() => Self::from(-1.0),
+            (false, false) => Self::ZERO,
+        }
+    }
+
+    // log10 of the absolute value, -inf for zero
+    pub fn log10(&self) -> f64 {
+        if self.is_zero() {
+            return f64::NEG_INFINITY;
+        }
+        self.mantissa.abs().log10() + self.exponent as f64
+    }
+
+    // self^power for self >= 0. cost curves: base * growth.pow(owned)
+    pub fn pow(self, power: f64) -> Self {
+        if self.is_plain() {
+            let plain = self.mantissa.powf(power);
+            if plain.is_finite() && plain.abs() < 10f64.powf(PLAIN) {
+                return Self::from(plain);
+            }
+        }
+        if self.is_zero() {
+            return if power == 0.0 { Self::ONE } else { Self::ZERO };
+        }
+        let log = self.log10() * power;
+        if !log.is_finite() {
+            return Self::ZERO;
+        }
+        let e = log.floor();
+        Self::new(10f64.powf(log - e), e as i64)
+    }
+
+    pub fn max(self, other: Self) -> Self {
+        if other > self { other } else { self }
+    }
+
+    pub fn min(self, other: Self) -> Self {
+        if other < self { other } else { self }
+    }
+
+    pub fn format(&self, notation: Notation, decimals: usize) -> String {
+        let sign = if self.is_negative() { "-" } else { "" };
+        let (m, e) = self.abs().scientific();
+        if e < 3 {
+            return format!("{sign}{}", small(self.abs().to_f64(), decimals));
+        }
+        let suffixes = match notation {
+            Notation::Short => &SHORT,
+            Notation::Named => &NAMED,
+            Notation::Scientific => return format!("{sign}{}", scientific(m, e, decimals)),
+        };
+        // 999.996K at 2 decimals rounds up into the next group, 1.00M
+        let mut group = e / 3;
+        let mut scaled = scale10(m, e - group * 3);
+        if round_to(scaled, decimals) >= 1000.0 {
+            group += 1;
+            scaled /= 1000.0;
+        }
+        match suffixes.get(group as usize) {
+            Some(suffix) => format!("{sign}{scaled:.decimals$}{suffix}"),
+            None => format!("{sign}{}", scientific(m, e, decimals)),
+        }
+    }
+}
+
+fn round_to(v: f64, decimals: usize) -> f64 {
+    let scale = 10f64.powi(decimals as i32);
+    (v * scale).round() / scale
+}
+
+// "100", "12.5", "0.33", trailing zeros trimmed
+fn small(v: f64, decimals: usize) -> String {
+    let s = format!("{v:.decimals$}");
+    if s.contains('.') {
+        s.trim_end_matches('0').trim_end_matches('.').to_string()
+    } else {
+        s
+    }
+}
+
+fn scientific(mut m: f64, mut e: i64, decimals: usize) -> String {
+    if round_to(m, decimals) >= 10.0 {
+        m /= 10.0;
+        e += 1;
+    }
+    format!("{m:.decimals$}e{e}")
+}
+
+// "{}" is Short with 2 decimals, "{:.0}" etc. picks the decimals
+impl fmt::Display for BigNum {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = self.format(Notation::Short, f.precision().unwrap_or(2));
+        // right-aligned like the other numbers, precision already went into `s`
+        match f.width() {
+            Some(w) => write!(f, "{s:>w$}"),
+            None => f.write_str(&s),
+        }
+    }
+}
+
+impl From<f64> for BigNum {
+    fn from(v: f64) -> Self {
+        Self::new(v, 0)
+    }
+}
+
+impl From<f32> for BigNum {
+    fn from(v: f32) -> Self {
+        Self::new(v as f64, 0)
+    }
+}
+
+impl From<u32> for BigNum {
+    fn from(v: u32) -> Self {
+        Self::new(v as f64, 0)
+    }
+}
+
+impl From<u64> for BigNum {
+    fn from(v: u64) -> Self {
+        Self::new(v as f64, 0)
+    }
+}
+
+impl From<i32> for BigNum {
+    fn from(v: i32) -> Self {
+        Self::new(v as f64, 0)
+    }
+}
+
+impl PartialOrd for BigNum {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.is_plain() && other.is_plain() {
+            return self.mantissa.partial_cmp(&other.mantissa);
+        }
+        let sign = |n: &Self| n.mantissa.partial_cmp(&0.0).unwrap_or(Ordering::Equal);
+        let by_sign = sign(self).cmp(&sign(other));
+        if by_sign != Ordering::Equal || self.is_zero() {
+            return Some(by_sign);
+        }
+        let ((ma, ea), (mb, eb)) = (self.scientific(), other.scientific());
+        let by_size = ea.cmp(&eb).then(ma.abs().total_cmp(&mb.abs()));
+        // bigger magnitude is smaller when negative
+        Some(if self.is_negative() {
+            by_size.reverse()
+        } else {
+            by_size
+        })
+    }
+}
+
+impl Neg for BigNum {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self {
+            mantissa: -self.mantissa,
+            ..self
+        }
+    }
+}
+
+impl<T: Into<BigNum>> Add<T> for BigNum {
+    type Output = Self;
+    fn add(self, rhs: T) -> Self {
+        let rhs = rhs.into();
+        if self.is_plain() && rhs.is_plain() {
+            return Self::from(self.mantissa + rhs.mantissa);
+        }
+        let ((ma, ea), (mb, eb)) = (self.scientific(), rhs.scientific());
+        if rhs.is_zero() {
+            return self;
+        }
+        if self.is_zero() {
+            return rhs;
+        }
+        let ((big_m, big_e), (small_m, small_e)) = if ea >= eb {
+            ((ma, ea), (mb, eb))
+        } else {
+            ((mb, eb), (ma, ea))
+        };
+        let gap = big_e - small_e;
+        if gap > MAX_DIGITS {
+            return Self::new(big_m, big_e);
+        }
+        Self::new(big_m + scale10(small_m, -gap), big_e)
+    }
+}
+
+impl<T: Into<BigNum>> Sub<T> for BigNum {
+    type Output = Self;
+    fn sub(self, rhs: T) -> Self {
+        self + -rhs.into()
+    }
+}
+
+impl<T: Into<BigNum>> Mul<T> for BigNum {
+    type Output = Self;
+    fn mul(self, rhs: T) -> Self {
+        let rhs = rhs.into();
+        if self.is_plain() && rhs.is_plain() {
+            let plain = self.mantissa * rhs.mantissa;
+            if plain.is_finite() {
+                return Self::from(plain);
+            }
+        }
+        let ((ma, ea), (mb, eb)) = (self.scientific(), rhs.scientific());
+        Self::new(ma * mb, ea.saturating_add(eb))
+    }
+}
+
+// dividing by zero gives zero rather than infinity, e.g. a cost over a rate of 0
+impl<T: Into<BigNum>> Div<T> for BigNum {
+    type Output = Self;
+    fn div(self, rhs: T) -> Self {
+        let rhs = rhs.into();
+        if rhs.is_zero() {
+            return Self::ZERO;
+        }
+        if self.is_plain() && rhs.is_plain() {
+            let plain = self.mantissa / rhs.mantissa;
+            if plain.is_finite() && (plain != 0.0 || self.is_zero()) {
+                return Self::from(plain);
+            }
+        }
+        let ((ma, ea), (mb, eb)) = (self.scientific(), rhs.scientific());
+        Self::new(ma / mb, ea.saturating_sub(eb))
+    }
+}
+
+impl<T: Into<BigNum>> AddAssign<T> for BigNum {
+    fn add_assign(&mut self, rhs: T) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: Into<BigNum>> SubAssign<T> for BigNum {
+    fn sub_assign(&mut self, rhs: T) {
+        *self = *self - rhs;
+    }
+}
+
+impl<T: Into<BigNum>> MulAssign<T> for BigNum {
+    fn mul_assign(&mut self, rhs: T) {
+        *self = *self * rhs;
+    }
+}
+
+impl<T: Into<BigNum>> DivAssign<T> for BigNum {
+    fn div_assign(&mut self, rhs: T) {
+        *self = *self / rhs;
+    }
+}
+
+impl Sum for BigNum {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |a, b| a + b)
+    }
+}
+
+// a plain float while it fits (so old `money: 494100.0` saves still load),
+// "1.5e400" past that. both forms are accepted when reading
+impl Serialize for BigNum {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.is_plain() {
+            serializer.serialize_f64(self.mantissa)
+        } else {
+            serializer.serialize_str(&format!("{}e{}", self.mantissa, self.exponent))
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for BigNum {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl de::Visitor<'_> for Visitor {
+            type Value = BigNum;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a number or a \"<mantissa>e<exponent>\" string")
+            }
+
+            fn visit_f64<E: de::Error>(self, v: f64) -> Result<BigNum, E> {
+                Ok(BigNum::from(v))
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<BigNum, E> {
+                Ok(BigNum::from(v as f64))
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<BigNum, E> {
+                Ok(BigNum::from(v))
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<BigNum, E> {
+                v.parse()
+                    .map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseBigNumError;
+
+impl fmt::Display for ParseBigNumError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("invalid big number")
+    }
+}
+
+impl std::error::Error for ParseBigNumError {}
+
+// "494100", "1.5e400", "-2.5E-3"
+impl std::str::FromStr for BigNum {
+    type Err = ParseBigNumError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (m, e) = match s.find(['e', 'E']) {
+            Some(i) => (&s[..i], s[i + 1..].parse().map_err(|_| ParseBigNumError)?),
+            None => (s, 0),
+        };
+        let m: f64 = m.parse().map_err(|_| ParseBigNumError)?;
+        if !m.is_finite() {
+            return Err(ParseBigNumError);
+        }
+        Ok(Self::new(m, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn n(s: &str) -> BigNum {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn arithmetic_past_f32_and_f64() {
+        // f32 can't hold this exactly, BigNum keeps every digit
+        let mut money = BigNum::from(16_777_216.0);
+        money += 1.0;
+        assert_eq!(money.to_f64(), 16_777_217.0);
+
+        let huge = n("1.5e400");
+        assert_eq!((huge * 2.0).exponent(), 400);
+        assert_eq!(huge + 1.0, huge);
+        assert!(huge - huge == BigNum::ZERO);
+        assert_eq!((huge / n("1.5e398")).to_f64(), 100.0);
+        assert_eq!(BigNum::from(1.5).pow(1000.0).exponent(), 176);
+        assert!(n("-1e10") < n("-1e9") && n("-1") < n("1e-5") && n("1e9") < n("1e10"));
+        assert_eq!(
+            [n("1"), n("2.5"), n("1e3")].into_iter().sum::<BigNum>(),
+            n("1003.5")
+        );
+    }
+
+    #[test]
+    fn formats() {
+        assert_eq!(n("494100").to_string(), "494.10K");
+        assert_eq!(format!("{:.1}", n("1234567")), "1.2M");
+        assert_eq!(format!("{:.0}", n("100")), "100");
+        assert_eq!(n("12.5").to_string(), "12.5");
+        assert_eq!(n("999999").to_string(), "1.00M");
+        assert_eq!(n("-2500").to_string(), "-2.50K");
+        assert_eq!(n("4.56e12").format(Notation::Scientific, 2), "4.56e12");
+        assert_eq!(n("1.23e6").format(Notation::Named, 2), "1.23 million");
+        // out of suffixes
+        assert_eq!(n("1.5e400").to_string(), "1.50e400");
+    }
+
+    #[test]
+    fn ron_round_trip() {
+        for v in [n("494100"), n("1.5e400"), n("-3.25e-7"), BigNum::ZERO] {
+            let text = ron::to_string(&v).unwrap();
+            assert_eq!(ron::from_str::<BigNum>(&text).unwrap(), v, "{text}");
+        }
+        assert_eq!(ron::from_str::<BigNum>("494100.0").unwrap(), n("494100"));
+        assert_eq!(ron::from_str::<BigNum>("50").unwrap(), n("50"));
+        assert!(ron::from_str::<BigNum>("\"lots\"").is_err());
+    }
+}
@@ -0,0 +1,55 @@
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use crate::config::{AnimState, AnimationSet};
+
+// plays clips from an AnimationSet on this entity's Sprite, switching them with the
+// set's state machine. the Sprite keeps its own image, the set only slices it
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+#[require(Sprite)]
+pub struct SpriteAnimator {
+    pub set: Handle<AnimationSet>,
+    pub state: AnimState,
+    // playback speed, 0 freezes the current frame
+    pub speed: f32,
+    pub(crate) frame: usize,
+    pub(crate) timer: f32,
+    pub(crate) finished: bool,
+    // to spot health going down between frames
+    pub(crate) last_health: Option<f32>,
+}
+
+impl SpriteAnimator {
+    pub fn new(set: Handle<AnimationSet>) -> Self {
+        Self {
+            set,
+            state: AnimState::Idle,
+            speed: 1.0,
+            frame: 0,
+            timer: 0.0,
+            finished: false,
+            last_health: None,
+        }
+    }
+
+    // index into the current clip's frames, not the atlas
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+
+    // jumps straight to a state, the state machine takes over again next frame
+    pub fn play(&mut self, state: AnimState) {
+        self.state = state;
+        self.restart();
+    }
+
+    pub fn restart(&mut self) {
+        self.frame = 0;
+        self.timer = 0.0;
+        self.finished = false;
+    }
+}
+
+// atlas layout per animation set, rebuilt when the RON changes
+#[derive(Resource, Debug, Default)]
+pub struct AnimationAtlases(pub HashMap<AssetId<AnimationSet>, Handle<TextureAtlasLayout>>);
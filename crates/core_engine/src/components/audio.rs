@@ -0,0 +1,36 @@
+use bevy::prelude::*;
+
+use crate::config::{AudioBus, SoundCues};
+
+// one playing instance of a cue, counted for max_concurrent
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct PlayingCue {
+    pub cue: String,
+    pub bus: AudioBus,
+    // the cue's own volume, the bus volume is applied on top
+    pub volume: f32,
+    // Time::elapsed_secs when it started, oldest goes first with StealOldest
+    pub started: f32,
+}
+
+// what actually makes noise. Null plays nothing, for tests and headless servers
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SoundBackend {
+    #[default]
+    Bevy,
+    Null,
+}
+
+// stands in for the audio clip on the null backend, so concurrency limits still apply
+#[derive(Component, Debug, Clone, Copy)]
+pub struct NullSound {
+    pub remaining: f32,
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct SoundCuesHandle(pub Handle<SoundCues>);
+
+// where AudioSettings are written when they change
+#[derive(Resource, Debug, Clone)]
+pub struct AudioSettingsFile(pub String);
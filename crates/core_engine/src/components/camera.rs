@@ -0,0 +1,175 @@
+use bevy::prelude::*;
+
+// the playable area, centered on the origin. games used to keep their own Bounds / ARENA_HALF_*
+#[derive(Resource, Debug, Clone, Copy, Reflect)]
+#[reflect(Resource)]
+pub struct ArenaBounds {
+    pub half_w: f32,
+    pub half_h: f32,
+}
+
+impl Default for ArenaBounds {
+    fn default() -> Self {
+        Self::new(480.0, 270.0)
+    }
+}
+
+impl ArenaBounds {
+    pub fn new(half_w: f32, half_h: f32) -> Self {
+        Self { half_w, half_h }
+    }
+
+    pub fn half_size(&self) -> Vec2 {
+        Vec2::new(self.half_w, self.half_h)
+    }
+
+    pub fn contains(&self, p: Vec2) -> bool {
+        p.x.abs() <= self.half_w && p.y.abs() <= self.half_h
+    }
+
+    // keeps a point `margin` away from the edges (e.g. a collider radius)
+    pub fn clamp(&self, p: Vec2, margin: f32) -> Vec2 {
+        let half = (self.half_size() - Vec2::splat(margin)).max(Vec2::ZERO);
+        p.clamp(-half, half)
+    }
+}
+
+// where the camera wants to be, before shake. camera systems write focus/zoom,
+// apply_camera_rig turns it into the Transform (+ projection scale)
+#[derive(Component, Debug, Clone, Copy, Reflect)]
+#[reflect(Component)]
+pub struct CameraRig {
+    pub focus: Vec2,
+    pub zoom: f32, // orthographic scale, 1 = pixel perfect
+    // used when the camera has no render target yet (headless, first frame)
+    pub fallback_view: Vec2,
+}
+
+impl Default for CameraRig {
+    fn default() -> Self {
+        Self {
+            focus: Vec2::ZERO,
+            zoom: 1.0,
+            fallback_view: Vec2::new(960.0, 540.0),
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy, Reflect)]
+#[reflect(Component)]
+#[require(CameraRig)]
+pub struct CameraFollow {
+    pub target: Entity,
+    // how fast the camera catches up, per second. 0 -> snap
+    pub smoothing: f32,
+    // half extents of the box the target can move in without moving the camera
+    pub dead_zone: Vec2,
+    pub offset: Vec2,
+}
+
+impl CameraFollow {
+    pub fn new(target: Entity) -> Self {
+        Self {
+            target,
+            smoothing: 6.0,
+            dead_zone: Vec2::ZERO,
+            offset: Vec2::ZERO,
+        }
+    }
+
+    pub fn with_dead_zone(mut self, half_extents: Vec2) -> Self {
+        self.dead_zone = half_extents;
+        self
+    }
+
+    pub fn with_smoothing(mut self, smoothing: f32) -> Self {
+        self.smoothing = smoothing;
+        self
+    }
+}
+
+// keep the visible area inside ArenaBounds (centered if the arena is smaller than the view)
+#[derive(Component, Debug, Clone, Copy, Default, Reflect)]
+#[reflect(Component)]
+#[require(CameraRig)]
+pub struct ClampToArena;
+
+// trauma based shake: trauma 0..1 decays over time, shake strength is trauma^2
+#[derive(Component, Debug, Clone, Copy, Reflect)]
+#[reflect(Component)]
+#[require(CameraRig)]
+pub struct CameraShake {
+    pub trauma: f32,
+    pub decay: f32, // trauma lost per second
+    pub max_offset: f32,
+    pub max_roll: f32, // radians
+    pub frequency: f32,
+    pub(crate) t: f32,
+}
+
+impl Default for CameraShake {
+    fn default() -> Self {
+        Self {
+            trauma: 0.0,
+            decay: 1.5,
+            max_offset: 18.0,
+            max_roll: 0.06,
+            frequency: 22.0,
+            t: 0.0,
+        }
+    }
+}
+
+impl CameraShake {
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+
+    pub fn strength(&self) -> f32 {
+        self.trauma * self.trauma
+    }
+}
+
+// put on things whose DamageEvents should shake every CameraShake camera
+#[derive(Component, Debug, Clone, Copy, Reflect)]
+#[reflect(Component)]
+pub struct ShakeOnDamage {
+    pub trauma_per_damage: f32,
+    pub max_per_hit: f32,
+}
+
+impl Default for ShakeOnDamage {
+    fn default() -> Self {
+        Self {
+            trauma_per_damage: 0.05,
+            max_per_hit: 0.6,
+        }
+    }
+}
+
+// entities the ZoomToFit camera keeps in view
+#[derive(Component, Debug, Clone, Copy, Default, Reflect)]
+#[reflect(Component)]
+pub struct CameraTarget;
+
+// frames every CameraTarget, overrides CameraFollow's focus when both are present
+#[derive(Component, Debug, Clone, Copy, Reflect)]
+#[reflect(Component)]
+#[require(CameraRig)]
+pub struct ZoomToFit {
+    pub padding: f32,
+    pub min_zoom: f32,
+    pub max_zoom: f32,
+    pub smoothing: f32,
+}
+
+impl Default for ZoomToFit {
+    fn default() -> Self {
+        Self {
+            padding: 80.0,
+            min_zoom: 0.5,
+            max_zoom: 3.0,
+            smoothing: 4.0,
+        }
+    }
+}
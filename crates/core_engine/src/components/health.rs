@@ -48,3 +48,32 @@ impl Health {
         }
     }
 }
+
+// soaks up damage before Health does, shown as an overlay on health bars
+#[derive(Component, Debug, Clone, Copy, Reflect)]
+#[reflect(Component)]
+pub struct Shield {
+    pub current: f32,
+    pub max: f32,
+}
+
+impl Shield {
+    pub fn new(max: f32) -> Self {
+        Self { current: max, max }
+    }
+
+    pub fn ratio(&self) -> f32 {
+        if self.max > 0.0 {
+            (self.current / self.max).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+
+    // takes what it can, returns the damage left for Health
+    pub fn absorb(&mut self, amount: f32) -> f32 {
+        let soaked = amount.min(self.current).max(0.0);
+        self.current -= soaked;
+        amount - soaked
+    }
+}
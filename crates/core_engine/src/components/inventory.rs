@@ -0,0 +1,208 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{ItemId, ItemRegistry};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+pub struct ItemStack {
+    pub item: ItemId,
+    pub count: u32,
+}
+
+// slot based bag of stacks. works as a Component (chests, players) or a Resource (global stock)
+#[derive(Component, Resource, Debug, Clone, Default, Reflect)]
+#[reflect(Component)]
+pub struct Inventory {
+    pub slots: Vec<ItemStack>,
+    // max number of slots, None -> unlimited
+    pub capacity: Option<usize>,
+}
+
+impl Inventory {
+    pub fn with_capacity(slots: usize) -> Self {
+        Self {
+            slots: Vec::new(),
+            capacity: Some(slots),
+        }
+    }
+
+    pub fn count(&self, item: ItemId) -> u64 {
+        self.slots
+            .iter()
+            .filter(|s| s.item == item)
+            .map(|s| s.count as u64)
+            .sum()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.capacity.is_some_and(|cap| self.slots.len() >= cap)
+    }
+
+    // how many of `item` would still fit
+    pub fn space_for(&self, item: ItemId, reg: &ItemRegistry) -> u64 {
+        let stack = reg.stack_size(item) as u64;
+        let partial: u64 = self
+            .slots
+            .iter()
+            .filter(|s| s.item == item)
+            .map(|s| stack.saturating_sub(s.count as u64))
+            .sum();
+        match self.capacity {
+            Some(cap) => partial + cap.saturating_sub(self.slots.len()) as u64 * stack,
+            None => u64::MAX,
+        }
+    }
+
+    // tops up existing stacks, then opens new slots. returns what didn't fit
+    pub fn add(&mut self, item: ItemId, mut count: u32, reg: &ItemRegistry) -> u32 {
+        let stack = reg.stack_size(item);
+        for slot in self.slots.iter_mut().filter(|s| s.item == item) {
+            let room = stack.saturating_sub(slot.count);
+            let put = room.min(count);
+            slot.count += put;
+            count -= put;
+            if count == 0 {
+                return 0;
+            }
+        }
+        while count > 0 && !self.is_full() {
+            let put = stack.min(count);
+            self.slots.push(ItemStack { item, count: put });
+            count -= put;
+        }
+        count
+    }
+
+    // takes up to `count`, emptiest stacks first. returns how many were taken
+    pub fn take(&mut self, item: ItemId, count: u32) -> u32 {
+        let mut left = count;
+        let mut idx: Vec<usize> = (0..self.slots.len())
+            .filter(|&i| self.slots[i].item == item)
+            .collect();
+        idx.sort_by_key(|&i| self.slots[i].count);
+        for i in idx {
+            let slot = &mut self.slots[i];
+            let took = slot.count.min(left);
+            slot.count -= took;
+            left -= took;
+            if left == 0 {
+                break;
+            }
+        }
+        self.slots.retain(|s| s.count > 0);
+        count - left
+    }
+
+    // all or nothing
+    pub fn remove(&mut self, item: ItemId, count: u32) -> bool {
+        if self.count(item) < count as u64 {
+            return false;
+        }
+        self.take(item, count);
+        true
+    }
+
+    // summed per item, sorted by id
+    pub fn totals(&self) -> Vec<(ItemId, u64)> {
+        let mut out: Vec<(ItemId, u64)> = Vec::new();
+        for s in &self.slots {
+            match out.iter_mut().find(|(id, _)| *id == s.item) {
+                Some((_, n)) => *n += s.count as u64,
+                None => out.push((s.item, s.count as u64)),
+            }
+        }
+        out.sort_by_key(|(id, _)| *id);
+        out
+    }
+
+    pub fn clear(&mut self) {
+        self.slots.clear();
+    }
+}
+
+// where a transfer reads from / writes to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InventoryRef {
+    Global, // the Inventory resource
+    Entity(Entity),
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct ItemCatalogHandle(pub Handle<crate::config::ItemCatalog>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ItemCatalog;
+
+    const YARN: ItemId = ItemId(1);
+    const GEM: ItemId = ItemId(2);
+
+    fn registry() -> ItemRegistry {
+        let catalog: ItemCatalog = ron::from_str(
+            r#"(items: [
+                (id: (1), key: "yarn", name: "Yarn", stack_size: 10),
+                (id: (2), key: "gem", name: "Gem", stack_size: 3),
+            ])"#,
+        )
+        .unwrap();
+        ItemRegistry::from_catalog(&catalog)
+    }
+
+    fn counts(inv: &Inventory) -> Vec<(ItemId, u32)> {
+        inv.slots.iter().map(|s| (s.item, s.count)).collect()
+    }
+
+    #[test]
+    fn add_tops_up_then_opens_slots_until_full() {
+        let reg = registry();
+        let mut inv = Inventory::with_capacity(3);
+        assert_eq!(inv.add(YARN, 15, &reg), 0);
+        assert_eq!(counts(&inv), vec![(YARN, 10), (YARN, 5)]);
+        assert_eq!(inv.space_for(YARN, &reg), 15);
+        assert_eq!(inv.space_for(GEM, &reg), 3);
+
+        // the half stack first, then the last slot, the rest comes back
+        assert_eq!(inv.add(YARN, 20, &reg), 5);
+        assert_eq!(counts(&inv), vec![(YARN, 10), (YARN, 10), (YARN, 10)]);
+        assert!(inv.is_full());
+        assert_eq!(inv.space_for(YARN, &reg), 0);
+        assert_eq!(inv.add(GEM, 1, &reg), 1);
+        assert_eq!(inv.count(YARN), 30);
+        assert_eq!(inv.count(GEM), 0);
+    }
+
+    #[test]
+    fn unlimited_bags_never_fill() {
+        let reg = registry();
+        let mut inv = Inventory::default();
+        assert_eq!(inv.space_for(GEM, &reg), u64::MAX);
+        assert_eq!(inv.add(GEM, 100, &reg), 0);
+        assert_eq!(inv.slots.len(), 34);
+        assert!(!inv.is_full());
+        assert_eq!(inv.totals(), vec![(GEM, 100)]);
+    }
+
+    #[test]
+    fn take_drains_the_smallest_stacks_first() {
+        let mut inv = Inventory {
+            slots: [(YARN, 10), (YARN, 3), (GEM, 2), (YARN, 7)]
+                .map(|(item, count)| ItemStack { item, count })
+                .to_vec(),
+            ..default()
+        };
+        assert_eq!(inv.take(YARN, 8), 8);
+        assert_eq!(counts(&inv), vec![(YARN, 10), (GEM, 2), (YARN, 2)]);
+        assert_eq!(inv.totals(), vec![(YARN, 12), (GEM, 2)]);
+
+        // asking for more than there is takes what's there
+        assert_eq!(inv.take(YARN, 50), 12);
+        assert_eq!(counts(&inv), vec![(GEM, 2)]);
+
+        // remove is all or nothing
+        assert!(!inv.remove(GEM, 3));
+        assert_eq!(inv.count(GEM), 2);
+        assert!(inv.remove(GEM, 2));
+        assert!(inv.slots.is_empty());
+    }
+}
@@ -0,0 +1,19 @@
+use bevy::prelude::*;
+
+// rolls this table from the LootPlugin's tables when the entity dies
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct LootDropper {
+    pub table: String,
+}
+
+impl LootDropper {
+    pub fn new(table: impl Into<String>) -> Self {
+        Self {
+            table: table.into(),
+        }
+    }
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct LootTablesHandle(pub Handle<crate::config::LootTables>);
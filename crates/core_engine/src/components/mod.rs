@@ -1,11 +1,31 @@
+pub mod animation;
+pub mod audio;
+pub mod camera;
 pub mod collider;
 pub mod health;
+pub mod inventory;
 pub mod lifetime;
+pub mod loot;
+pub mod particles;
+pub mod pause;
+pub mod stats;
 pub mod tags;
+pub mod tween;
 pub mod velocity;
+pub mod xp;
 
+pub use animation::*;
+pub use audio::*;
+pub use camera::*;
 pub use collider::*;
 pub use health::*;
+pub use inventory::*;
 pub use lifetime::*;
+pub use loot::*;
+pub use particles::*;
+pub use pause::*;
+pub use stats::*;
 pub use tags::*;
+pub use tween::*;
 pub use velocity::*;
+pub use xp::*;
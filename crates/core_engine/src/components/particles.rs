@@ -0,0 +1,218 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::config::{EmitterDef, ParticleEffects};
+
+// one particle, simulated in a batch by its emitter instead of being an entity
+#[derive(Debug, Clone, Copy, Reflect)]
+pub struct Particle {
+    pub position: Vec2, // world space, so a moving emitter leaves a trail
+    pub velocity: Vec2,
+    pub age: f32,
+    pub lifetime: f32,
+}
+
+// plays an effect from the ParticlesPlugin's file at this entity's position
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct ParticleEmitter {
+    pub effect: String,
+    // stop emitting (live particles still finish), e.g. a trail when the player stops
+    pub paused: bool,
+    // despawns the entity once the effect is over, for fire-and-forget bursts
+    pub despawn_when_done: bool,
+    pub particles: Vec<Particle>,
+    elapsed: f32,
+    // fractional particles owed by `rate`, carried between frames
+    debt: f32,
+    started: bool,
+}
+
+impl ParticleEmitter {
+    pub fn new(effect: impl Into<String>) -> Self {
+        Self {
+            effect: effect.into(),
+            paused: false,
+            despawn_when_done: false,
+            particles: Vec::new(),
+            elapsed: 0.0,
+            debt: 0.0,
+            started: false,
+        }
+    }
+
+    pub fn once(effect: impl Into<String>) -> Self {
+        Self {
+            despawn_when_done: true,
+            ..Self::new(effect)
+        }
+    }
+
+    // starts the effect over, burst included
+    pub fn restart(&mut self) {
+        self.elapsed = 0.0;
+        self.debt = 0.0;
+        self.started = false;
+    }
+
+    pub fn is_emitting(&self, def: &EmitterDef) -> bool {
+        if !self.started {
+            return true;
+        }
+        !def.is_one_shot() && def.duration.is_none_or(|d| self.elapsed < d)
+    }
+
+    // nothing left to emit and every particle is gone
+    pub fn is_done(&self, def: &EmitterDef) -> bool {
+        !self.is_emitting(def) && self.particles.is_empty()
+    }
+
+    // moves and ages the live particles, then emits new ones at `origin`
+    pub fn update(&mut self, def: &EmitterDef, origin: Vec2, dt: f32, rng: &mut impl Rng) {
+        let gravity = Vec2::from(def.gravity);
+        let damping = if def.drag > 0.0 {
+            (1.0 - def.drag).powf(dt.max(0.0)).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        for p in self.particles.iter_mut() {
+            p.age += dt;
+            p.velocity += gravity * dt;
+            p.position += p.velocity * dt;
+            p.velocity *= damping;
+        }
+        self.particles.retain(|p| p.age < p.lifetime);
+
+        if self.paused {
+            return;
+        }
+
+        let mut count = 0;
+        if !self.started {
+            self.started = true;
+            count += def.burst;
+        } else if self.is_emitting(def) {
+            // don't emit past the end of the duration on a long frame
+            let window = def
+                .duration
+                .map_or(dt, |d| dt.min((d - self.elapsed).max(0.0)));
+            self.elapsed += dt;
+            self.debt += def.rate * window;
+            count += self.debt as u32;
+            self.debt = self.debt.fract();
+        }
+
+        for _ in 0..count {
+            self.spawn(def, origin, rng);
+        }
+    }
+
+    fn spawn(&mut self, def: &EmitterDef, origin: Vec2, rng: &mut impl Rng) {
+        if def.max_particles == 0 {
+            return;
+        }
+        if self.particles.len() >= def.max_particles {
+            self.particles.remove(0);
+        }
+        let angle = def.direction + def.spread * rng.random_range(-1.0..=1.0);
+        let speed = range(def.speed, rng);
+        self.particles.push(Particle {
+            position: origin,
+            velocity: Vec2::from_angle(angle.to_radians()) * speed,
+            age: 0.0,
+            lifetime: range(def.lifetime, rng).max(0.0),
+        });
+    }
+}
+
+fn range((min, max): (f32, f32), rng: &mut impl Rng) -> f32 {
+    if max > min {
+        rng.random_range(min..=max)
+    } else {
+        min
+    }
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct ParticleEffectsHandle(pub Handle<ParticleEffects>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, rngs::StdRng};
+
+    fn def() -> EmitterDef {
+        ron::from_str(
+            "(burst: 10, speed: (100.0, 100.0), lifetime: (1.0, 1.0), gravity: (0.0, -100.0))",
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn burst_falls_and_expires() {
+        let def = def();
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut emitter = ParticleEmitter::new("hit");
+
+        emitter.update(&def, Vec2::ZERO, 0.0, &mut rng);
+        assert_eq!(emitter.particles.len(), 10);
+        assert!(!emitter.is_emitting(&def));
+
+        for p in emitter.particles.iter_mut() {
+            p.velocity = Vec2::ZERO;
+        }
+        emitter.update(&def, Vec2::ZERO, 0.5, &mut rng);
+        assert!(emitter.particles.iter().all(|p| p.position.y < 0.0));
+
+        emitter.update(&def, Vec2::ZERO, 0.6, &mut rng);
+        assert!(emitter.is_done(&def));
+    }
+
+    #[test]
+    fn rate_carries_fractions_and_stops_after_duration() {
+        let def = EmitterDef {
+            rate: 4.0,
+            duration: Some(1.0),
+            lifetime: (5.0, 5.0),
+            ..default()
+        };
+        let mut rng = StdRng::seed_from_u64(2);
+        let mut emitter = ParticleEmitter::new("trail");
+
+        emitter.update(&def, Vec2::ZERO, 0.0, &mut rng);
+        // 0.125s frames -> half a particle each, one every other frame
+        for _ in 0..4 {
+            emitter.update(&def, Vec2::ZERO, 0.125, &mut rng);
+        }
+        assert_eq!(emitter.particles.len(), 2);
+
+        // one huge frame only pays out the rest of the duration
+        emitter.update(&def, Vec2::ZERO, 3.0, &mut rng);
+        assert_eq!(emitter.particles.len(), 4);
+        assert!(!emitter.is_emitting(&def));
+    }
+
+    #[test]
+    fn max_particles_drops_the_oldest() {
+        let def = EmitterDef {
+            burst: 50,
+            max_particles: 20,
+            ..default()
+        };
+        let mut emitter = ParticleEmitter::new("big");
+        emitter.update(&def, Vec2::ZERO, 0.0, &mut StdRng::seed_from_u64(3));
+        assert_eq!(emitter.particles.len(), 20);
+    }
+
+    #[test]
+    fn color_and_size_over_life() {
+        let def = EmitterDef {
+            size: (8.0, 0.0),
+            color: ([1.0, 0.0, 0.0, 1.0], [1.0, 0.0, 0.0, 0.0]),
+            ..default()
+        };
+        let (size, color) = def.sample(0.5);
+        assert_eq!(size, 4.0);
+        assert!((color.alpha() - 0.5).abs() < 1e-4);
+    }
+}
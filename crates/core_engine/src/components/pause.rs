@@ -0,0 +1,90 @@
+use bevy::{ecs::system::SystemParam, platform::collections::HashSet, prelude::*};
+
+// who is holding the game clock. Time<Virtual> runs again once the last hold is let go,
+// and a pause that was already there before the first hold is left alone
+#[derive(Resource, Debug, Default)]
+pub struct GamePause {
+    holds: HashSet<&'static str>,
+    owns_clock: bool,
+}
+
+impl GamePause {
+    pub const LEVEL_UP: &'static str = "level_up";
+    pub const MENU: &'static str = "menu";
+
+    pub fn hold(&mut self, reason: &'static str, time: &mut Time<Virtual>) {
+        if self.holds.is_empty() && !time.is_paused() {
+            time.pause();
+            self.owns_clock = true;
+        }
+        self.holds.insert(reason);
+    }
+
+    pub fn release(&mut self, reason: &'static str, time: &mut Time<Virtual>) {
+        if !self.holds.remove(reason) || !self.holds.is_empty() {
+            return;
+        }
+        if std::mem::take(&mut self.owns_clock) {
+            time.unpause();
+        }
+    }
+
+    pub fn is_held_by(&self, reason: &str) -> bool {
+        self.holds.contains(reason)
+    }
+
+    pub fn is_held(&self) -> bool {
+        !self.holds.is_empty()
+    }
+}
+
+// GamePause together with the clock it drives, for systems
+#[derive(SystemParam)]
+pub struct PauseClock<'w> {
+    pause: ResMut<'w, GamePause>,
+    time: ResMut<'w, Time<Virtual>>,
+}
+
+impl PauseClock<'_> {
+    pub fn hold(&mut self, reason: &'static str) {
+        self.pause.hold(reason, &mut self.time);
+    }
+
+    pub fn release(&mut self, reason: &'static str) {
+        self.pause.release(reason, &mut self.time);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clock_runs_once_every_hold_is_gone() {
+        let mut time = Time::<Virtual>::default();
+        let mut pause = GamePause::default();
+        pause.hold(GamePause::LEVEL_UP, &mut time);
+        pause.hold(GamePause::MENU, &mut time);
+        pause.release(GamePause::MENU, &mut time);
+        assert!(time.is_paused());
+        assert!(pause.is_held_by(GamePause::LEVEL_UP));
+        pause.release(GamePause::LEVEL_UP, &mut time);
+        assert!(!time.is_paused());
+        assert!(!pause.is_held());
+    }
+
+    #[test]
+    fn a_pause_from_elsewhere_is_left_alone() {
+        let mut time = Time::<Virtual>::default();
+        time.pause();
+        let mut pause = GamePause::default();
+        pause.hold(GamePause::MENU, &mut time);
+        pause.release(GamePause::MENU, &mut time);
+        assert!(time.is_paused());
+
+        // releasing what was never held does nothing
+        time.unpause();
+        pause.release(GamePause::LEVEL_UP, &mut time);
+        assert!(!time.is_paused());
+    }
+}
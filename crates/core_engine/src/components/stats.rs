@@ -0,0 +1,283 @@
+use bevy::{platform::collections::HashMap, prelude::*};
+use serde::{Deserialize, Serialize};
+
+// well-known stats, games can use Custom(n) for their own
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+pub enum StatId {
+    MaxHealth,
+    MoveSpeed,
+    FireRate,
+    Damage,
+    ProductionRate,
+    MagnetRadius,
+    Custom(u16),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect, Serialize, Deserialize)]
+pub enum ModifierKind {
+    Flat,       // added to base
+    AddPercent, // summed, then applied once: 0.1 + 0.1 -> +20%
+    Mult,       // multiplied one by one: 1.5 * 1.5 -> x2.25
+}
+
+// who applied the modifier, so it can be removed again (e.g. "upgrade:boots", "buff:haste")
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Reflect)]
+pub struct SourceId(pub String);
+
+impl From<&str> for SourceId {
+    fn from(s: &str) -> Self {
+        Self(s.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Reflect)]
+pub struct StatModifier {
+    pub source: SourceId,
+    pub kind: ModifierKind,
+    pub value: f32,
+    // seconds left, None -> permanent
+    pub remaining: Option<f32>,
+}
+
+impl StatModifier {
+    pub fn new(source: impl Into<SourceId>, kind: ModifierKind, value: f32) -> Self {
+        Self {
+            source: source.into(),
+            kind,
+            value,
+            remaining: None,
+        }
+    }
+
+    pub fn flat(source: impl Into<SourceId>, value: f32) -> Self {
+        Self::new(source, ModifierKind::Flat, value)
+    }
+
+    pub fn percent(source: impl Into<SourceId>, value: f32) -> Self {
+        Self::new(source, ModifierKind::AddPercent, value)
+    }
+
+    pub fn mult(source: impl Into<SourceId>, value: f32) -> Self {
+        Self::new(source, ModifierKind::Mult, value)
+    }
+
+    pub fn for_seconds(mut self, seconds: f32) -> Self {
+        self.remaining = Some(seconds.max(0.0));
+        self
+    }
+}
+
+#[derive(Debug, Clone, Reflect)]
+pub struct Stat {
+    pub base: f32,
+    modifiers: Vec<StatModifier>,
+    // cached final value, recomputed whenever base or modifiers change
+    value: f32,
+}
+
+impl Stat {
+    pub fn new(base: f32) -> Self {
+        Self {
+            base,
+            modifiers: Vec::new(),
+            value: base,
+        }
+    }
+
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    pub fn modifiers(&self) -> &[StatModifier] {
+        &self.modifiers
+    }
+
+    // (base + flat) * (1 + sum of percents) * product of mults
+    fn recompute(&mut self) {
+        let mut flat = 0.0;
+        let mut percent = 0.0;
+        let mut mult = 1.0;
+        for m in &self.modifiers {
+            match m.kind {
+                ModifierKind::Flat => flat += m.value,
+                ModifierKind::AddPercent => percent += m.value,
+                ModifierKind::Mult => mult *= m.value,
+            }
+        }
+        self.value = (self.base + flat) * (1.0 + percent).max(0.0) * mult;
+    }
+}
+
+#[derive(Component, Debug, Clone, Default, Reflect)]
+#[reflect(Component)]
+pub struct Stats {
+    stats: HashMap<StatId, Stat>,
+}
+
+impl Stats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // builder style: Stats::new().with(StatId::MoveSpeed, 260.0)
+    pub fn with(mut self, id: StatId, base: f32) -> Self {
+        self.set_base(id, base);
+        self
+    }
+
+    pub fn set_base(&mut self, id: StatId, base: f32) {
+        let stat = self.stats.entry(id).or_insert_with(|| Stat::new(base));
+        stat.base = base;
+        stat.recompute();
+    }
+
+    pub fn stat(&self, id: StatId) -> Option<&Stat> {
+        self.stats.get(&id)
+    }
+
+    pub fn get(&self, id: StatId) -> Option<f32> {
+        self.stats.get(&id).map(Stat::value)
+    }
+
+    pub fn get_or(&self, id: StatId, default: f32) -> f32 {
+        self.get(id).unwrap_or(default)
+    }
+
+    // stacks with anything already there, even from the same source.
+    // a stat that was never set starts from base 0
+    pub fn add_modifier(&mut self, id: StatId, modifier: StatModifier) {
+        let stat = self.stats.entry(id).or_insert_with(|| Stat::new(0.0));
+        stat.modifiers.push(modifier);
+        stat.recompute();
+    }
+
+    // replaces whatever this source had on the stat (refreshing a buff instead of stacking it)
+    pub fn set_modifier(&mut self, id: StatId, modifier: StatModifier) {
+        let stat = self.stats.entry(id).or_insert_with(|| Stat::new(0.0));
+        stat.modifiers.retain(|m| m.source != modifier.source);
+        stat.modifiers.push(modifier);
+        stat.recompute();
+    }
+
+    // removes every modifier from this source on every stat, returns how many went
+    pub fn remove_source(&mut self, source: &SourceId) -> usize {
+        let mut removed = 0;
+        for stat in self.stats.values_mut() {
+            let before = stat.modifiers.len();
+            stat.modifiers.retain(|m| &m.source != source);
+            if stat.modifiers.len() != before {
+                removed += before - stat.modifiers.len();
+                stat.recompute();
+            }
+        }
+        removed
+    }
+
+    pub fn count_source(&self, source: &SourceId) -> usize {
+        self.stats
+            .values()
+            .flat_map(|s| s.modifiers.iter())
+            .filter(|m| &m.source == source)
+            .count()
+    }
+
+    pub fn has_timed_modifiers(&self) -> bool {
+        self.stats
+            .values()
+            .any(|s| s.modifiers.iter().any(|m| m.remaining.is_some()))
+    }
+
+    // counts down timed modifiers, returns true if any expired
+    pub fn tick(&mut self, dt: f32) -> bool {
+        let mut expired = false;
+        for stat in self.stats.values_mut() {
+            let before = stat.modifiers.len();
+            for m in stat.modifiers.iter_mut() {
+                if let Some(left) = m.remaining.as_mut() {
+                    *left -= dt;
+                }
+            }
+            stat.modifiers
+                .retain(|m| m.remaining.is_none_or(|left| left > 0.0));
+            if stat.modifiers.len() != before {
+                stat.recompute();
+                expired = true;
+            }
+        }
+        expired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flats_then_summed_percents_then_mults() {
+        let mut stats = Stats::new().with(StatId::Damage, 100.0);
+        stats.add_modifier(StatId::Damage, StatModifier::flat("ring", 20.0));
+        stats.add_modifier(StatId::Damage, StatModifier::flat("ring", 10.0));
+        stats.add_modifier(StatId::Damage, StatModifier::percent("rage", 0.5));
+        stats.add_modifier(StatId::Damage, StatModifier::percent("rage", 0.25));
+        // 130 * 1.75
+        assert_eq!(stats.get(StatId::Damage), Some(227.5));
+
+        stats.add_modifier(StatId::Damage, StatModifier::mult("crit", 2.0));
+        stats.add_modifier(StatId::Damage, StatModifier::mult("crit", 1.5));
+        assert_eq!(stats.get(StatId::Damage), Some(682.5));
+
+        // the base moves under the modifiers
+        stats.set_base(StatId::Damage, 10.0);
+        assert_eq!(stats.get(StatId::Damage), Some(210.0));
+
+        // never set: base 0, but still there
+        stats.add_modifier(StatId::MoveSpeed, StatModifier::flat("boots", 5.0));
+        assert_eq!(stats.get(StatId::MoveSpeed), Some(5.0));
+        assert_eq!(stats.get(StatId::FireRate), None);
+        assert_eq!(stats.get_or(StatId::FireRate, 1.0), 1.0);
+    }
+
+    #[test]
+    fn percents_bottom_out_at_zero() {
+        let mut stats = Stats::new().with(StatId::MoveSpeed, 200.0);
+        stats.add_modifier(StatId::MoveSpeed, StatModifier::percent("slow", -0.5));
+        assert_eq!(stats.get(StatId::MoveSpeed), Some(100.0));
+
+        stats.add_modifier(StatId::MoveSpeed, StatModifier::percent("root", -1.0));
+        stats.add_modifier(StatId::MoveSpeed, StatModifier::mult("haste", 2.0));
+        assert_eq!(stats.get(StatId::MoveSpeed), Some(0.0));
+    }
+
+    #[test]
+    fn set_replaces_per_source_and_remove_clears_every_stat() {
+        let mut stats = Stats::new()
+            .with(StatId::MoveSpeed, 100.0)
+            .with(StatId::FireRate, 2.0);
+        stats.set_modifier(StatId::MoveSpeed, StatModifier::percent("buff:haste", 0.2));
+        stats.set_modifier(StatId::MoveSpeed, StatModifier::percent("buff:haste", 0.5));
+        stats.set_modifier(StatId::MoveSpeed, StatModifier::flat("boots", 10.0));
+        stats.add_modifier(StatId::FireRate, StatModifier::mult("buff:haste", 1.5));
+        assert_eq!(stats.count_source(&"buff:haste".into()), 2);
+        assert_eq!(stats.get(StatId::MoveSpeed), Some(165.0));
+
+        assert_eq!(stats.remove_source(&"buff:haste".into()), 2);
+        assert_eq!(stats.remove_source(&"buff:haste".into()), 0);
+        assert_eq!(stats.get(StatId::MoveSpeed), Some(110.0));
+        assert_eq!(stats.get(StatId::FireRate), Some(2.0));
+    }
+
+    #[test]
+    fn timed_modifiers_expire() {
+        let mut stats = Stats::new().with(StatId::Damage, 10.0);
+        stats.add_modifier(
+            StatId::Damage,
+            StatModifier::flat("potion", 5.0).for_seconds(1.0),
+        );
+        assert!(stats.has_timed_modifiers());
+        assert!(!stats.tick(0.6));
+        assert_eq!(stats.get(StatId::Damage), Some(15.0));
+        assert!(stats.tick(0.6));
+        assert_eq!(stats.get(StatId::Damage), Some(10.0));
+        assert!(!stats.has_timed_modifiers());
+    }
+}
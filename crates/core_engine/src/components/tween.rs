@@ -0,0 +1,256 @@
+use bevy::prelude::*;
+
+// what a tween writes to. from/to are absolute values, eased progress lerps between them
+#[derive(Debug, Clone, Reflect)]
+pub enum TweenLens {
+    Translation {
+        from: Vec3,
+        to: Vec3,
+    },
+    Scale {
+        from: Vec3,
+        to: Vec3,
+    },
+    Rotation {
+        from: Quat,
+        to: Quat,
+    },
+    SpriteColor {
+        from: Color,
+        to: Color,
+    },
+    // Node width/height in px
+    NodeSize {
+        from: Vec2,
+        to: Vec2,
+    },
+    // Node left/top in px
+    NodeOffset {
+        from: Vec2,
+        to: Vec2,
+    },
+    BackgroundColor {
+        from: Color,
+        to: Color,
+    },
+    TextColor {
+        from: Color,
+        to: Color,
+    },
+    // any registered, reflected component with an f32 at `path` ("0", "offset.y", ...)
+    Field {
+        component: String,
+        path: String,
+        from: f32,
+        to: f32,
+    },
+}
+
+impl TweenLens {
+    // TweenLens::field::<ReelOffset>("0", 0.0, 12.0), the component needs register_type
+    pub fn field<C: Component + TypePath>(path: impl Into<String>, from: f32, to: f32) -> Self {
+        Self::Field {
+            component: C::type_path().to_string(),
+            path: path.into(),
+            from,
+            to,
+        }
+    }
+}
+
+// a tween is a tree of steps, played on the entity that holds the Tween component
+#[derive(Debug, Clone, Reflect)]
+pub enum TweenStep {
+    Lerp {
+        lens: TweenLens,
+        duration: f32,
+        ease: EaseFunction,
+    },
+    Delay(f32),
+    // one after the other
+    Sequence(Vec<TweenStep>),
+    // all at once, done when the longest one is
+    Parallel(Vec<TweenStep>),
+}
+
+impl TweenStep {
+    pub fn lerp(lens: TweenLens, seconds: f32, ease: EaseFunction) -> Self {
+        Self::Lerp {
+            lens,
+            duration: seconds.max(0.0),
+            ease,
+        }
+    }
+
+    pub fn delay(seconds: f32) -> Self {
+        Self::Delay(seconds.max(0.0))
+    }
+
+    pub fn sequence(steps: impl IntoIterator<Item = TweenStep>) -> Self {
+        Self::Sequence(steps.into_iter().collect())
+    }
+
+    pub fn parallel(steps: impl IntoIterator<Item = TweenStep>) -> Self {
+        Self::Parallel(steps.into_iter().collect())
+    }
+
+    // a.then(b) -> Sequence[a, b], flattened so chains don't nest
+    pub fn then(self, next: TweenStep) -> Self {
+        match self {
+            Self::Sequence(mut steps) => {
+                steps.push(next);
+                Self::Sequence(steps)
+            }
+            step => Self::Sequence(vec![step, next]),
+        }
+    }
+
+    pub fn duration(&self) -> f32 {
+        match self {
+            Self::Lerp { duration, .. } => *duration,
+            Self::Delay(d) => *d,
+            Self::Sequence(steps) => steps.iter().map(Self::duration).sum(),
+            Self::Parallel(steps) => steps.iter().map(Self::duration).fold(0.0, f32::max),
+        }
+    }
+
+    // every lerp that was active somewhere in (prev, now], with its progress at `now`.
+    // lerps that finished inside the window still get their final value, so a big
+    // time step never skips the end of a step
+    pub(crate) fn visit(&self, start: f32, prev: f32, now: f32, out: &mut Vec<(TweenLens, f32)>) {
+        match self {
+            Self::Lerp {
+                lens,
+                duration,
+                ease,
+            } => {
+                // prev starts below 0, so zero-length lerps at 0 still apply on the first tick
+                if now < start || prev >= start + duration {
+                    return;
+                }
+                let t = if *duration <= 0.0 {
+                    1.0
+                } else {
+                    ((now - start) / duration).clamp(0.0, 1.0)
+                };
+                out.push((lens.clone(), ease.sample_clamped(t)));
+            }
+            Self::Delay(_) => {}
+            Self::Sequence(steps) => {
+                let mut at = start;
+                for step in steps {
+                    if at > now {
+                        break;
+                    }
+                    step.visit(at, prev, now, out);
+                    at += step.duration();
+                }
+            }
+            Self::Parallel(steps) => {
+                for step in steps {
+                    step.visit(start, prev, now, out);
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+pub enum TweenRepeat {
+    #[default]
+    Once,
+    Times(u32), // plays this many times in total
+    Forever,
+}
+
+// plays `step` on this entity. removed (or the entity despawned) once it's done,
+// and a TweenCompletedEvent goes out
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct Tween {
+    pub step: TweenStep,
+    pub repeat: TweenRepeat,
+    // shows up in TweenCompletedEvent, so one entity can tell its tweens apart
+    pub tag: Option<String>,
+    pub despawn_on_complete: bool,
+    // keeps going while Time<Virtual> is paused (menus, level up popups)
+    pub real_time: bool,
+    pub(crate) elapsed: f32,
+    pub(crate) played: u32,
+    // -1 until the first tick, see TweenStep::visit
+    pub(crate) prev: f32,
+}
+
+impl Tween {
+    pub fn new(step: TweenStep) -> Self {
+        Self {
+            step,
+            repeat: TweenRepeat::Once,
+            tag: None,
+            despawn_on_complete: false,
+            real_time: false,
+            elapsed: 0.0,
+            played: 0,
+            prev: -1.0,
+        }
+    }
+
+    pub fn translation(from: Vec3, to: Vec3, seconds: f32, ease: EaseFunction) -> Self {
+        Self::new(TweenStep::lerp(
+            TweenLens::Translation { from, to },
+            seconds,
+            ease,
+        ))
+    }
+
+    pub fn scale(from: Vec3, to: Vec3, seconds: f32, ease: EaseFunction) -> Self {
+        Self::new(TweenStep::lerp(
+            TweenLens::Scale { from, to },
+            seconds,
+            ease,
+        ))
+    }
+
+    pub fn sprite_color(from: Color, to: Color, seconds: f32, ease: EaseFunction) -> Self {
+        Self::new(TweenStep::lerp(
+            TweenLens::SpriteColor { from, to },
+            seconds,
+            ease,
+        ))
+    }
+
+    // sprite snaps to `flash` and fades back to `base`, for hits
+    pub fn flash(base: Color, flash: Color, seconds: f32) -> Self {
+        Self::sprite_color(flash, base, seconds, EaseFunction::QuadraticOut).with_tag("flash")
+    }
+
+    pub fn with_repeat(mut self, repeat: TweenRepeat) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        self.tag = Some(tag.into());
+        self
+    }
+
+    pub fn despawn_on_complete(mut self) -> Self {
+        self.despawn_on_complete = true;
+        self
+    }
+
+    pub fn in_real_time(mut self) -> Self {
+        self.real_time = true;
+        self
+    }
+
+    // 0..1 over the current play
+    pub fn progress(&self) -> f32 {
+        let total = self.step.duration();
+        if total <= 0.0 {
+            1.0
+        } else {
+            (self.elapsed / total).clamp(0.0, 1.0)
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::components::{CircleCollider, Velocity};
+
+#[derive(Component, Debug, Clone, Copy, Reflect)]
+#[reflect(Component)]
+pub struct Experience {
+    pub level: u32,
+    pub xp: u32,    // progress inside the current level
+    pub total: u64, // lifetime xp, handy for scoring
+}
+
+impl Default for Experience {
+    fn default() -> Self {
+        Self {
+            level: 1,
+            xp: 0,
+            total: 0,
+        }
+    }
+}
+
+// dropped xp orb, flies to the nearest collector once inside its magnet radius
+#[derive(Component, Debug, Clone, Copy, Reflect)]
+#[reflect(Component)]
+#[require(Transform, Velocity, CircleCollider = CircleCollider::new(6.0))]
+pub struct XpPickup {
+    pub amount: u32,
+}
+
+impl XpPickup {
+    pub fn new(amount: u32) -> Self {
+        Self { amount }
+    }
+}
+
+// who can pick up xp. StatId::MagnetRadius on Stats overrides magnet_radius
+#[derive(Component, Debug, Clone, Copy, Reflect)]
+#[reflect(Component)]
+pub struct XpCollector {
+    pub magnet_radius: f32,
+    pub pull_speed: f32,
+}
+
+impl Default for XpCollector {
+    fn default() -> Self {
+        Self {
+            magnet_radius: 120.0,
+            pull_speed: 420.0,
+        }
+    }
+}
+
+// level-up flow settings + which data files to use
+#[derive(Resource, Debug, Clone)]
+pub struct LevelUpConfig {
+    pub choices: usize,
+    pub pause_on_level_up: bool,
+    pub curve: Option<Handle<crate::config::LevelCurve>>,
+    pub pool: Option<Handle<crate::config::UpgradePool>>,
+}
+
+impl Default for LevelUpConfig {
+    fn default() -> Self {
+        Self {
+            choices: 3,
+            pause_on_level_up: true,
+            curve: None,
+            pool: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LevelUpOffer {
+    pub entity: Entity,
+    pub level: u32,
+    pub choices: Vec<String>, // upgrade ids
+}
+
+// offers waiting for a pick, front() is the one to show. gameplay stays paused while non-empty
+#[derive(Resource, Debug, Default)]
+pub struct PendingLevelUps {
+    pub queue: VecDeque<LevelUpOffer>,
+}
+
+impl PendingLevelUps {
+    pub fn current(&self) -> Option<&LevelUpOffer> {
+        self.queue.front()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+}
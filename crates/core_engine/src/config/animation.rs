@@ -0,0 +1,277 @@
+use bevy::{platform::collections::HashMap, prelude::*};
+use serde::Deserialize;
+
+// one sprite sheet: how to slice it, its clips, and which clip each state plays.
+// (atlas: (tile: (32, 32), columns: 4, rows: 2), clips: { "walk": (frames: [4, 5, 6, 7]) }, states: { Move: "walk" })
+#[derive(Asset, TypePath, Deserialize, Debug, Clone)]
+pub struct AnimationSet {
+    pub atlas: AtlasGrid,
+    pub clips: HashMap<String, SpriteClip>,
+    // states without a clip are never entered
+    pub states: HashMap<AnimState, String>,
+    // checked in order, the first one that fires wins
+    #[serde(default = "default_transitions")]
+    pub transitions: Vec<AnimTransition>,
+    // px/s before the sprite counts as moving
+    #[serde(default = "default_move_threshold")]
+    pub move_threshold: f32,
+    // mirror the sprite when moving left
+    #[serde(default)]
+    pub flip_with_velocity: bool,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct AtlasGrid {
+    pub tile: (u32, u32),
+    pub columns: u32,
+    pub rows: u32,
+    #[serde(default)]
+    pub padding: (u32, u32),
+    #[serde(default)]
+    pub offset: (u32, u32),
+}
+
+impl AtlasGrid {
+    pub fn layout(&self) -> TextureAtlasLayout {
+        TextureAtlasLayout::from_grid(
+            UVec2::from(self.tile),
+            self.columns,
+            self.rows,
+            Some(UVec2::from(self.padding)),
+            Some(UVec2::from(self.offset)),
+        )
+    }
+
+    pub fn len(&self) -> usize {
+        (self.columns * self.rows) as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SpriteClip {
+    // atlas indices, in play order
+    pub frames: Vec<usize>,
+    #[serde(default = "default_fps")]
+    pub fps: f32,
+    // seconds per frame, overrides fps for the frames it covers
+    #[serde(default)]
+    pub durations: Vec<f32>,
+    #[serde(default = "default_looping")]
+    pub looping: bool,
+}
+
+impl SpriteClip {
+    pub fn frame_duration(&self, frame: usize) -> f32 {
+        self.durations
+            .get(frame)
+            .copied()
+            .unwrap_or(1.0 / self.fps.max(0.001))
+    }
+
+    // moves `frame` / `timer` on by dt, returns true once a non-looping clip has shown
+    // its last frame for that frame's whole duration
+    pub fn advance(&self, frame: &mut usize, timer: &mut f32, dt: f32) -> bool {
+        if self.frames.is_empty() {
+            return true;
+        }
+        *timer += dt;
+        loop {
+            let last = *frame + 1 >= self.frames.len();
+            let d = self.frame_duration(*frame);
+            if last && !self.looping {
+                *timer = timer.min(d.max(0.0));
+                return *timer >= d;
+            }
+            if *timer < d || d <= 0.0 {
+                return false;
+            }
+            *timer -= d;
+            *frame = if last { 0 } else { *frame + 1 };
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Deserialize, Reflect)]
+pub enum AnimState {
+    #[default]
+    Idle,
+    Move,
+    Hurt,
+    Die,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct AnimTransition {
+    // empty -> from any state
+    #[serde(default)]
+    pub from: Vec<AnimState>,
+    pub to: AnimState,
+    pub when: AnimCondition,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnimCondition {
+    Moving,
+    Still,
+    Hurt, // health went down this frame
+    Dead,
+    ClipFinished, // the current clip is non-looping and done
+}
+
+// what the state machine looks at, filled from Velocity / Health each frame
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AnimInputs {
+    pub speed: f32,
+    pub hurt: bool,
+    pub dead: bool,
+    pub clip_finished: bool,
+}
+
+fn default_transitions() -> Vec<AnimTransition> {
+    use AnimCondition as C;
+    use AnimState as S;
+    let t = |from: &[AnimState], to, when| AnimTransition {
+        from: from.to_vec(),
+        to,
+        when,
+    };
+    vec![
+        t(&[S::Idle, S::Move, S::Hurt], S::Die, C::Dead),
+        t(&[S::Idle, S::Move], S::Hurt, C::Hurt),
+        t(&[S::Hurt], S::Idle, C::ClipFinished),
+        t(&[S::Idle], S::Move, C::Moving),
+        t(&[S::Move], S::Idle, C::Still),
+    ]
+}
+
+fn default_move_threshold() -> f32 {
+    5.0
+}
+
+fn default_fps() -> f32 {
+    8.0
+}
+
+fn default_looping() -> bool {
+    true
+}
+
+impl AnimationSet {
+    pub fn clip(&self, state: AnimState) -> Option<&SpriteClip> {
+        self.states
+            .get(&state)
+            .and_then(|name| self.clips.get(name))
+    }
+
+    pub fn next_state(&self, current: AnimState, inputs: &AnimInputs) -> AnimState {
+        let holds = |c: AnimCondition| match c {
+            AnimCondition::Moving => inputs.speed > self.move_threshold,
+            AnimCondition::Still => inputs.speed <= self.move_threshold,
+            AnimCondition::Hurt => inputs.hurt,
+            AnimCondition::Dead => inputs.dead,
+            AnimCondition::ClipFinished => inputs.clip_finished,
+        };
+        self.transitions
+            .iter()
+            .find(|t| {
+                t.to != current
+                    && (t.from.is_empty() || t.from.contains(&current))
+                    && self.clip(t.to).is_some()
+                    && holds(t.when)
+            })
+            .map_or(current, |t| t.to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SET: &str = r#"(
+        atlas: (tile: (16, 16), columns: 4, rows: 2),
+        clips: {
+            "idle": (frames: [0, 1], fps: 2.0),
+            "walk": (frames: [4, 5, 6], durations: [0.1, 0.3]),
+            "hurt": (frames: [2, 3], fps: 10.0, looping: false),
+        },
+        states: { Idle: "idle", Move: "walk", Hurt: "hurt" },
+    )"#;
+
+    fn set() -> AnimationSet {
+        ron::from_str(SET).unwrap()
+    }
+
+    #[test]
+    fn clips_use_frame_timings() {
+        let set = set();
+        let walk = &set.clips["walk"];
+        let (mut frame, mut timer) = (0, 0.0);
+        walk.advance(&mut frame, &mut timer, 0.15);
+        assert_eq!(frame, 1);
+        // 0.3 for frame 1, then fps 8 for frame 2, then wraps
+        walk.advance(&mut frame, &mut timer, 0.3);
+        assert_eq!(frame, 2);
+        walk.advance(&mut frame, &mut timer, 0.125);
+        assert_eq!(frame, 0);
+
+        // the last frame of a one-shot still gets its 0.1s before it counts as done
+        let hurt = &set.clips["hurt"];
+        let (mut frame, mut timer) = (0, 0.0);
+        assert!(!hurt.advance(&mut frame, &mut timer, 0.05));
+        assert!(!hurt.advance(&mut frame, &mut timer, 0.1));
+        assert_eq!(frame, 1);
+        assert!(!hurt.advance(&mut frame, &mut timer, 0.02));
+        assert!(hurt.advance(&mut frame, &mut timer, 0.05));
+        assert!(hurt.advance(&mut frame, &mut timer, 1.0));
+        assert_eq!(frame, 1);
+
+        // a single-frame one-shot like square_chaser's hurt lasts its duration
+        let flash = SpriteClip {
+            frames: vec![3],
+            durations: vec![0.3],
+            ..hurt.clone()
+        };
+        let (mut frame, mut timer) = (0, 0.0);
+        assert!(!flash.advance(&mut frame, &mut timer, 1.0 / 60.0));
+        assert!(!flash.advance(&mut frame, &mut timer, 0.2));
+        assert!(flash.advance(&mut frame, &mut timer, 0.1));
+    }
+
+    #[test]
+    fn default_state_machine() {
+        let set = set();
+        let moving = AnimInputs {
+            speed: 100.0,
+            ..default()
+        };
+        assert_eq!(set.next_state(AnimState::Idle, &moving), AnimState::Move);
+        assert_eq!(
+            set.next_state(AnimState::Move, &AnimInputs::default()),
+            AnimState::Idle
+        );
+
+        let hit = AnimInputs {
+            hurt: true,
+            ..moving
+        };
+        assert_eq!(set.next_state(AnimState::Move, &hit), AnimState::Hurt);
+        // hurt holds until the clip is done
+        assert_eq!(set.next_state(AnimState::Hurt, &moving), AnimState::Hurt);
+        let done = AnimInputs {
+            clip_finished: true,
+            ..default()
+        };
+        assert_eq!(set.next_state(AnimState::Hurt, &done), AnimState::Idle);
+
+        // no "die" clip in this set, so dying never leaves the current state
+        let dead = AnimInputs {
+            dead: true,
+            ..default()
+        };
+        assert_eq!(set.next_state(AnimState::Idle, &dead), AnimState::Idle);
+    }
+}
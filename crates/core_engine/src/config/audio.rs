@@ -0,0 +1,149 @@
+use std::{fs, io, path::Path};
+
+use bevy::{platform::collections::HashMap, prelude::*};
+use serde::{Deserialize, Serialize};
+
+// every sound cue of a game in one RON file, plus which gameplay message plays which cue:
+// (cues: { "hit": (files: ["audio/hit1.ogg", "audio/hit2.ogg"], pitch: (0.9, 1.1)) },
+//  triggers: [(on: Damage, cue: "hit")])
+#[derive(Asset, TypePath, Deserialize, Debug, Clone, Default)]
+pub struct SoundCues {
+    pub cues: HashMap<String, SoundCue>,
+    #[serde(default)]
+    pub triggers: Vec<SoundTrigger>,
+}
+
+impl SoundCues {
+    pub fn get(&self, name: &str) -> Option<&SoundCue> {
+        self.cues.get(name)
+    }
+
+    // cue names hooked up to this trigger, in file order
+    pub fn cues_for<'a>(&'a self, on: &'a CueTrigger) -> impl Iterator<Item = &'a str> + 'a {
+        self.triggers
+            .iter()
+            .filter(move |t| &t.on == on)
+            .map(|t| t.cue.as_str())
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SoundTrigger {
+    pub on: CueTrigger,
+    pub cue: String,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum CueTrigger {
+    Damage,
+    Death,
+    Pickup,
+    LevelUp,
+    // a Tween with this tag finished, e.g. TweenCompleted("reel_stop")
+    TweenCompleted(String),
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SoundCue {
+    // one is picked at random per play
+    pub files: Vec<String>,
+    #[serde(default)]
+    pub bus: AudioBus,
+    #[serde(default = "default_volume")]
+    pub volume: f32,
+    // playback speed range, (0.9, 1.1) keeps repeated hits from sounding identical
+    #[serde(default = "default_pitch")]
+    pub pitch: (f32, f32),
+    // None -> no limit
+    #[serde(default)]
+    pub max_concurrent: Option<u32>,
+    #[serde(default)]
+    pub on_limit: LimitPolicy,
+    #[serde(default)]
+    pub looping: bool,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Reflect)]
+pub enum AudioBus {
+    Music,
+    #[default]
+    Sfx,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LimitPolicy {
+    // drop the new play
+    #[default]
+    Skip,
+    // stop the oldest instance to make room
+    StealOldest,
+}
+
+fn default_volume() -> f32 {
+    1.0
+}
+
+fn default_pitch() -> (f32, f32) {
+    (1.0, 1.0)
+}
+
+// bus volumes, linear 0..1. master scales both buses
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, PartialEq, Reflect)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct AudioSettings {
+    pub master: f32,
+    pub music: f32,
+    pub sfx: f32,
+    pub muted: bool,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            master: 1.0,
+            music: 0.7,
+            sfx: 1.0,
+            muted: false,
+        }
+    }
+}
+
+impl AudioSettings {
+    pub fn bus_volume(&self, bus: AudioBus) -> f32 {
+        if self.muted {
+            return 0.0;
+        }
+        let bus = match bus {
+            AudioBus::Music => self.music,
+            AudioBus::Sfx => self.sfx,
+        };
+        (self.master * bus).clamp(0.0, 1.0)
+    }
+
+    // missing or broken file -> None, callers fall back to defaults
+    pub fn load(path: impl AsRef<Path>) -> Option<Self> {
+        let text = fs::read_to_string(path.as_ref()).ok()?;
+        match ron::from_str(&text) {
+            Ok(settings) => Some(settings),
+            Err(e) => {
+                warn!("ignoring audio settings {:?}: {e}", path.as_ref());
+                None
+            }
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(io::Error::other)?;
+        // written aside and renamed over, a crash mid-write can't truncate the old file
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        fs::write(&tmp, text)?;
+        fs::rename(&tmp, path)
+    }
+}
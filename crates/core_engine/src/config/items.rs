@@ -0,0 +1,87 @@
+use bevy::{platform::collections::HashMap, prelude::*};
+use serde::{Deserialize, Serialize};
+
+#[derive(
+    Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Reflect,
+)]
+pub struct ItemId(pub u16);
+
+// one entry of the catalog. `key` is what loot tables and configs refer to
+#[derive(Deserialize, Debug, Clone)]
+pub struct ItemDef {
+    pub id: ItemId,
+    pub key: String,
+    pub name: String,
+    #[serde(default)]
+    pub icon: Option<String>, // asset path
+    #[serde(default = "default_stack_size")]
+    pub stack_size: u32,
+    #[serde(default)]
+    pub base_value: f32,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+fn default_stack_size() -> u32 {
+    99
+}
+
+impl ItemDef {
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+}
+
+// the RON file: (items: [(id: (1), key: "yarn", name: "Yarn Ball", stack_size: 999)])
+#[derive(Asset, TypePath, Deserialize, Debug, Clone, Default)]
+pub struct ItemCatalog {
+    pub items: Vec<ItemDef>,
+}
+
+// lookup built from the loaded catalog, rebuilt on hot reload
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ItemRegistry {
+    defs: HashMap<ItemId, ItemDef>,
+    by_key: HashMap<String, ItemId>,
+}
+
+impl ItemRegistry {
+    pub fn from_catalog(catalog: &ItemCatalog) -> Self {
+        let mut reg = Self::default();
+        for def in &catalog.items {
+            if reg.defs.contains_key(&def.id) {
+                warn!("item id {:?} defined twice, keeping the last", def.id);
+            }
+            reg.by_key.insert(def.key.clone(), def.id);
+            reg.defs.insert(def.id, def.clone());
+        }
+        reg
+    }
+
+    pub fn get(&self, id: ItemId) -> Option<&ItemDef> {
+        self.defs.get(&id)
+    }
+
+    pub fn by_key(&self, key: &str) -> Option<&ItemDef> {
+        self.by_key.get(key).and_then(|id| self.defs.get(id))
+    }
+
+    // unknown items still display as something
+    pub fn name(&self, id: ItemId) -> String {
+        self.get(id)
+            .map_or_else(|| format!("Item #{}", id.0), |d| d.name.clone())
+    }
+
+    // unknown items don't stack-limit, so nothing is lost before the catalog loads
+    pub fn stack_size(&self, id: ItemId) -> u32 {
+        self.get(id).map_or(u32::MAX, |d| d.stack_size.max(1))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ItemDef> {
+        self.defs.values()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.defs.is_empty()
+    }
+}
@@ -0,0 +1,347 @@
+use std::collections::BTreeMap;
+
+use bevy::{platform::collections::HashMap, prelude::*};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use serde::Deserialize;
+
+// nested tables can reference each other, this stops a cycle from looping forever
+const MAX_DEPTH: u32 = 8;
+
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Reflect,
+)]
+pub enum Rarity {
+    #[default]
+    Common,
+    Uncommon,
+    Rare,
+    Epic,
+    Legendary,
+}
+
+// every table of a game in one RON file, nested tables are referenced by name:
+// (tables: { "slime": (entries: [(weight: 5.0, drop: Item(id: "goo")), (drop: Table("gems"))]) })
+#[derive(Asset, TypePath, Deserialize, Debug, Clone, Default)]
+pub struct LootTables {
+    pub tables: HashMap<String, LootTable>,
+}
+
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct LootTable {
+    // weighted picks per evaluation
+    #[serde(default = "default_rolls")]
+    pub rolls: u32,
+    // dropped every time, on top of the rolls
+    #[serde(default)]
+    pub guaranteed: Vec<LootEntry>,
+    #[serde(default)]
+    pub entries: Vec<LootEntry>,
+    #[serde(default)]
+    pub pity: Option<Pity>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct LootEntry {
+    #[serde(default = "default_weight")]
+    pub weight: f32,
+    pub drop: LootKind,
+    #[serde(default)]
+    pub rarity: Rarity,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub enum LootKind {
+    Item {
+        id: String,
+        #[serde(default = "default_count")]
+        min: u32,
+        #[serde(default = "default_count")]
+        max: u32,
+    },
+    Table(String),
+    Nothing,
+}
+
+// after `after` evaluations without anything of `rarity` or better,
+// the next roll only picks from entries of at least that rarity
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct Pity {
+    pub rarity: Rarity,
+    pub after: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LootDrop {
+    pub id: String,
+    pub count: u32,
+    pub rarity: Rarity,
+}
+
+// dry streaks per table name, lives as a resource so pity survives between kills
+#[derive(Resource, Debug, Clone, Default)]
+pub struct PityCounters(pub HashMap<String, u32>);
+
+fn default_rolls() -> u32 {
+    1
+}
+
+fn default_weight() -> f32 {
+    1.0
+}
+
+fn default_count() -> u32 {
+    1
+}
+
+impl LootTables {
+    pub fn get(&self, name: &str) -> Option<&LootTable> {
+        self.tables.get(name)
+    }
+
+    // evaluates a table, unknown names drop nothing
+    pub fn roll(&self, name: &str, rng: &mut impl Rng, pity: &mut PityCounters) -> Vec<LootDrop> {
+        let mut out = Vec::new();
+        self.roll_into(name, rng, pity, 0, &mut out);
+        out
+    }
+
+    fn roll_into(
+        &self,
+        name: &str,
+        rng: &mut impl Rng,
+        pity: &mut PityCounters,
+        depth: u32,
+        out: &mut Vec<LootDrop>,
+    ) {
+        if depth > MAX_DEPTH {
+            warn!("loot table {name:?} nested deeper than {MAX_DEPTH}, cycle?");
+            return;
+        }
+        let Some(table) = self.tables.get(name) else {
+            warn!("unknown loot table {name:?}");
+            return;
+        };
+
+        for entry in &table.guaranteed {
+            self.drop_entry(entry, rng, pity, depth, out);
+        }
+
+        for _ in 0..table.rolls {
+            let forced = table
+                .pity
+                .filter(|p| p.after > 0 && pity.0.get(name).copied().unwrap_or(0) >= p.after);
+            let min_rarity = forced.map_or(Rarity::Common, |p| p.rarity);
+
+            let Some(entry) = pick_weighted(&table.entries, min_rarity, rng)
+                // nothing qualifies for pity, fall back to a normal roll
+                .or_else(|| pick_weighted(&table.entries, Rarity::Common, rng))
+            else {
+                continue;
+            };
+
+            let start = out.len();
+            self.drop_entry(entry, rng, pity, depth, out);
+
+            if let Some(p) = table.pity {
+                let hit =
+                    entry.rarity >= p.rarity || out[start..].iter().any(|d| d.rarity >= p.rarity);
+                let streak = pity.0.entry(name.to_string()).or_default();
+                *streak = if hit { 0 } else { *streak + 1 };
+            }
+        }
+    }
+
+    fn drop_entry(
+        &self,
+        entry: &LootEntry,
+        rng: &mut impl Rng,
+        pity: &mut PityCounters,
+        depth: u32,
+        out: &mut Vec<LootDrop>,
+    ) {
+        match &entry.drop {
+            LootKind::Item { id, min, max } => {
+                let (lo, hi) = ((*min).min(*max), (*min).max(*max));
+                let count = rng.random_range(lo..=hi);
+                if count > 0 {
+                    out.push(LootDrop {
+                        id: id.clone(),
+                        count,
+                        rarity: entry.rarity,
+                    });
+                }
+            }
+            LootKind::Table(name) => self.roll_into(name, rng, pity, depth + 1, out),
+            LootKind::Nothing => {}
+        }
+    }
+
+    // samples a table `samples` times with a fixed seed, for balancing
+    pub fn sample_distribution(&self, name: &str, samples: u32, seed: u64) -> LootDistribution {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut pity = PityCounters::default();
+        let mut dist = LootDistribution {
+            samples,
+            ..default()
+        };
+        for _ in 0..samples {
+            let drops = self.roll(name, &mut rng, &mut pity);
+            if drops.is_empty() {
+                dist.empty += 1;
+            }
+            for d in drops {
+                let stat = dist.items.entry(d.id).or_default();
+                stat.drops += 1;
+                stat.total += d.count as u64;
+                *dist.rarities.entry(d.rarity).or_default() += 1;
+            }
+        }
+        dist
+    }
+}
+
+fn pick_weighted<'a>(
+    entries: &'a [LootEntry],
+    min_rarity: Rarity,
+    rng: &mut impl Rng,
+) -> Option<&'a LootEntry> {
+    let eligible = || {
+        entries
+            .iter()
+            .filter(move |e| e.weight > 0.0 && e.rarity >= min_rarity)
+    };
+    let total: f32 = eligible().map(|e| e.weight).sum();
+    if total <= 0.0 {
+        return None;
+    }
+    let mut pick = rng.random_range(0.0..total);
+    for e in eligible() {
+        if pick < e.weight {
+            return Some(e);
+        }
+        pick -= e.weight;
+    }
+    // float rounding, land on the last one
+    eligible().next_back()
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ItemStat {
+    pub drops: u64, // how many times it dropped
+    pub total: u64, // summed counts
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LootDistribution {
+    pub samples: u32,
+    pub empty: u64,
+    pub items: BTreeMap<String, ItemStat>,
+    pub rarities: BTreeMap<Rarity, u64>,
+}
+
+impl LootDistribution {
+    // drops per evaluation, can go above 1.0 for tables with several rolls
+    pub fn rate(&self, id: &str) -> f64 {
+        let drops = self.items.get(id).map_or(0, |s| s.drops);
+        drops as f64 / self.samples.max(1) as f64
+    }
+}
+
+impl std::fmt::Display for LootDistribution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let n = self.samples.max(1) as f64;
+        writeln!(
+            f,
+            "{} samples, {} empty ({:.3}%)",
+            self.samples,
+            self.empty,
+            self.empty as f64 / n * 100.0
+        )?;
+        writeln!(
+            f,
+            "{:<20} {:>10} {:>9} {:>10}",
+            "item", "drops", "rate %", "avg count"
+        )?;
+        for (id, s) in &self.items {
+            writeln!(
+                f,
+                "{:<20} {:>10} {:>9.4} {:>10.2}",
+                id,
+                s.drops,
+                s.drops as f64 / n * 100.0,
+                s.total as f64 / s.drops.max(1) as f64
+            )?;
+        }
+        for (rarity, count) in &self.rarities {
+            writeln!(
+                f,
+                "{:<20} {:>10} {:>9.4}",
+                format!("{rarity:?}"),
+                count,
+                *count as f64 / n * 100.0
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TABLES: &str = r#"(
+        tables: {
+            "slime": (
+                guaranteed: [(drop: Item(id: "goo", min: 1, max: 2))],
+                entries: [
+                    (weight: 3.0, drop: Nothing),
+                    (weight: 1.0, drop: Table("gems"), rarity: Rare),
+                ],
+            ),
+            "gems": (
+                entries: [
+                    (weight: 9.0, drop: Item(id: "ruby"), rarity: Rare),
+                    (weight: 1.0, drop: Item(id: "diamond"), rarity: Legendary),
+                ],
+                pity: Some((rarity: Legendary, after: 20)),
+            ),
+            "loop": (entries: [(drop: Table("loop"))]),
+        },
+    )"#;
+
+    fn tables() -> LootTables {
+        ron::from_str(TABLES).unwrap()
+    }
+
+    #[test]
+    fn guaranteed_always_drops() {
+        let dist = tables().sample_distribution("slime", 10_000, 1);
+        assert_eq!(dist.rate("goo"), 1.0);
+        // 1 in 4 goes into the gem table
+        assert!((dist.rate("ruby") + dist.rate("diamond") - 0.25).abs() < 0.02);
+    }
+
+    #[test]
+    fn pity_forces_rarity() {
+        let t = tables();
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut pity = PityCounters::default();
+        let mut dry = 0;
+        for _ in 0..5_000 {
+            let drops = t.roll("gems", &mut rng, &mut pity);
+            if drops.iter().any(|d| d.id == "diamond") {
+                dry = 0;
+            } else {
+                dry += 1;
+            }
+            assert!(dry <= 20, "pity should cap the dry streak at 20");
+        }
+    }
+
+    #[test]
+    fn cycles_terminate() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let drops = tables().roll("loop", &mut rng, &mut PityCounters::default());
+        assert!(drops.is_empty());
+    }
+}
@@ -0,0 +1,15 @@
+pub mod animation;
+pub mod audio;
+pub mod items;
+pub mod loot;
+pub mod particles;
+pub mod ron_loader;
+pub mod xp;
+
+pub use animation::*;
+pub use audio::*;
+pub use items::*;
+pub use loot::*;
+pub use particles::*;
+pub use ron_loader::*;
+pub use xp::*;
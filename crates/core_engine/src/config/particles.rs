@@ -0,0 +1,127 @@
+use bevy::{platform::collections::HashMap, prelude::*};
+use serde::Deserialize;
+
+// every effect of a game in one RON file, plus which effect plays on which gameplay message:
+// (effects: { "hit": (burst: 12, speed: (60.0, 160.0), spread: 180.0) }, triggers: (damage: Some("hit")))
+#[derive(Asset, TypePath, Deserialize, Debug, Clone, Default)]
+pub struct ParticleEffects {
+    pub effects: HashMap<String, EmitterDef>,
+    #[serde(default)]
+    pub triggers: ParticleTriggers,
+}
+
+impl ParticleEffects {
+    pub fn get(&self, name: &str) -> Option<&EmitterDef> {
+        self.effects.get(name)
+    }
+}
+
+// effect names played at the entity's position, None -> nothing
+#[derive(Deserialize, Debug, Clone, Default)]
+pub struct ParticleTriggers {
+    #[serde(default)]
+    pub damage: Option<String>,
+    #[serde(default)]
+    pub death: Option<String>,
+    #[serde(default)]
+    pub pickup: Option<String>,
+}
+
+// ranges are (min, max), picked uniformly per particle
+#[derive(Deserialize, Debug, Clone)]
+pub struct EmitterDef {
+    // spawned all at once when the emitter starts
+    #[serde(default)]
+    pub burst: u32,
+    // particles per second while the emitter runs
+    #[serde(default)]
+    pub rate: f32,
+    // how long `rate` keeps emitting, None -> forever (or just the burst if rate is 0)
+    #[serde(default)]
+    pub duration: Option<f32>,
+    #[serde(default = "default_lifetime")]
+    pub lifetime: (f32, f32),
+    #[serde(default)]
+    pub speed: (f32, f32),
+    // degrees, 0 = right, 90 = up
+    #[serde(default = "default_direction")]
+    pub direction: f32,
+    // degrees to either side of `direction`, 180 = all around
+    #[serde(default = "default_spread")]
+    pub spread: f32,
+    // px/s², e.g. (0.0, -400.0)
+    #[serde(default)]
+    pub gravity: (f32, f32),
+    // same meaning as Velocity::drag, 0..1 fraction per sec
+    #[serde(default)]
+    pub drag: f32,
+    // start -> end over the particle's life
+    #[serde(default = "default_size")]
+    pub size: (f32, f32),
+    // srgba start -> end over the particle's life, written ((r, g, b, a), (r, g, b, a))
+    #[serde(default = "default_color")]
+    pub color: ([f32; 4], [f32; 4]),
+    // oldest particles make room when full
+    #[serde(default = "default_max_particles")]
+    pub max_particles: usize,
+}
+
+impl Default for EmitterDef {
+    fn default() -> Self {
+        Self {
+            burst: 0,
+            rate: 0.0,
+            duration: None,
+            lifetime: default_lifetime(),
+            speed: (0.0, 0.0),
+            direction: default_direction(),
+            spread: default_spread(),
+            gravity: (0.0, 0.0),
+            drag: 0.0,
+            size: default_size(),
+            color: default_color(),
+            max_particles: default_max_particles(),
+        }
+    }
+}
+
+fn default_lifetime() -> (f32, f32) {
+    (0.5, 0.5)
+}
+
+fn default_direction() -> f32 {
+    90.0
+}
+
+fn default_spread() -> f32 {
+    180.0
+}
+
+fn default_size() -> (f32, f32) {
+    (4.0, 0.0)
+}
+
+fn default_color() -> ([f32; 4], [f32; 4]) {
+    ([1.0; 4], [1.0, 1.0, 1.0, 0.0])
+}
+
+fn default_max_particles() -> usize {
+    256
+}
+
+impl EmitterDef {
+    // only a burst, nothing left to emit after the first frame
+    pub fn is_one_shot(&self) -> bool {
+        self.rate <= 0.0 || self.duration.is_some_and(|d| d <= 0.0)
+    }
+
+    // size and color at `t` = age / lifetime
+    pub fn sample(&self, t: f32) -> (f32, Color) {
+        let t = t.clamp(0.0, 1.0);
+        let size = self.size.0 + (self.size.1 - self.size.0) * t;
+        let [r0, g0, b0, a0] = self.color.0;
+        let [r1, g1, b1, a1] = self.color.1;
+        let color = Srgba::new(r0, g0, b0, a0).mix(&Srgba::new(r1, g1, b1, a1), t);
+        (size, color.into())
+    }
+}
@@ -0,0 +1,75 @@
+use std::marker::PhantomData;
+
+use bevy::{asset::AssetLoader, prelude::*};
+use serde::de::DeserializeOwned;
+
+// generic RON -> Asset loader, so each data file is just a Deserialize struct.
+// load with a typed handle: server.load::<LevelCurve>("config/levels.xp.ron")
+pub struct RonAssetPlugin<A> {
+    extensions: &'static [&'static str],
+    _marker: PhantomData<fn() -> A>,
+}
+
+impl<A> RonAssetPlugin<A> {
+    pub fn new(extensions: &'static [&'static str]) -> Self {
+        Self {
+            extensions,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<A: Asset + DeserializeOwned> Plugin for RonAssetPlugin<A> {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<A>().register_asset_loader(RonLoader::<A> {
+            extensions: self.extensions,
+            _marker: PhantomData,
+        });
+    }
+}
+
+struct RonLoader<A> {
+    extensions: &'static [&'static str],
+    _marker: PhantomData<fn() -> A>,
+}
+
+#[derive(Debug)]
+pub enum RonLoadError {
+    Io(std::io::Error),
+    Ron(ron::error::SpannedError),
+}
+
+impl std::fmt::Display for RonLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RonLoadError::Io(e) => write!(f, "could not read RON asset: {e}"),
+            RonLoadError::Ron(e) => write!(f, "could not parse RON asset: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for RonLoadError {}
+
+impl<A: Asset + DeserializeOwned> AssetLoader for RonLoader<A> {
+    type Asset = A;
+    type Settings = ();
+    type Error = RonLoadError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn bevy::asset::io::Reader,
+        _settings: &(),
+        _load_context: &mut bevy::asset::LoadContext<'_>,
+    ) -> Result<A, RonLoadError> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(RonLoadError::Io)?;
+        ron::de::from_bytes(&bytes).map_err(RonLoadError::Ron)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        self.extensions
+    }
+}
@@ -0,0 +1,90 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::components::{ModifierKind, StatId};
+
+// xp needed per level, loaded from RON:
+// (base: 5, growth: 1.25, flat: 2, max_level: Some(50), table: [3, 5])
+#[derive(Asset, TypePath, Deserialize, Debug, Clone)]
+pub struct LevelCurve {
+    pub base: f32,   // xp for level 1 -> 2
+    pub growth: f32, // multiplier per level
+    #[serde(default)]
+    pub flat: f32, // added per level on top of growth
+    #[serde(default)]
+    pub max_level: Option<u32>,
+    // explicit overrides for the first levels, table[0] is level 1 -> 2
+    #[serde(default)]
+    pub table: Vec<u32>,
+}
+
+impl Default for LevelCurve {
+    fn default() -> Self {
+        Self {
+            base: 5.0,
+            growth: 1.2,
+            flat: 0.0,
+            max_level: None,
+            table: Vec::new(),
+        }
+    }
+}
+
+impl LevelCurve {
+    // xp needed to go from `level` to `level + 1`, None at the cap
+    pub fn xp_to_next(&self, level: u32) -> Option<u32> {
+        if self.max_level.is_some_and(|max| level >= max) {
+            return None;
+        }
+        let idx = level.saturating_sub(1);
+        if let Some(&xp) = self.table.get(idx as usize) {
+            return Some(xp.max(1));
+        }
+        let n = idx as f32;
+        let xp = self.base * self.growth.powf(n) + self.flat * n;
+        Some(xp.round().max(1.0) as u32)
+    }
+}
+
+// pool of level-up upgrades, each one is a bundle of stat modifiers
+#[derive(Asset, TypePath, Deserialize, Debug, Clone, Default)]
+pub struct UpgradePool {
+    pub upgrades: Vec<UpgradeDef>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct UpgradeDef {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default = "default_weight")]
+    pub weight: f32,
+    #[serde(default)]
+    pub max_stacks: Option<u32>,
+    pub modifiers: Vec<UpgradeModifier>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct UpgradeModifier {
+    pub stat: StatId,
+    pub kind: ModifierKind,
+    pub value: f32,
+}
+
+fn default_weight() -> f32 {
+    1.0
+}
+
+impl UpgradePool {
+    pub fn get(&self, id: &str) -> Option<&UpgradeDef> {
+        self.upgrades.iter().find(|u| u.id == id)
+    }
+}
+
+impl UpgradeDef {
+    // modifiers from this upgrade are tagged with this source on Stats
+    pub fn source(&self) -> String {
+        format!("upgrade:{}", self.id)
+    }
+}
@@ -1,5 +1,10 @@
 use bevy::prelude::*;
 
+use crate::{
+    components::InventoryRef,
+    config::{ItemId, LootDrop},
+};
+
 #[derive(Message, Debug, Clone, Copy)]
 pub struct DamageEvent {
     pub target: Entity,
@@ -17,4 +22,87 @@ pub struct DeathEvent {
     pub entity: Entity,
 }
 
-// TODO add SpawnEvent, DeathEvent, PickupEvent, etc
+#[derive(Message, Debug, Clone, Copy)]
+pub struct PickupEvent {
+    pub collector: Entity,
+    pub position: Vec2,
+}
+
+#[derive(Message, Debug, Clone, Copy)]
+pub struct XpGainEvent {
+    pub entity: Entity,
+    pub amount: u32,
+}
+
+// one per level gained, gameplay is paused until an UpgradeChosenEvent answers it
+#[derive(Message, Debug, Clone)]
+pub struct LevelUpEvent {
+    pub entity: Entity,
+    pub level: u32,
+    pub choices: Vec<String>, // upgrade ids from the UpgradePool
+}
+
+#[derive(Message, Debug, Clone)]
+pub struct UpgradeChosenEvent {
+    pub entity: Entity,
+    pub upgrade: String,
+}
+
+// rolled from a LootDropper when it died, the game decides what to spawn
+#[derive(Message, Debug, Clone)]
+pub struct LootDropEvent {
+    pub source: Entity,
+    pub position: Vec2,
+    pub drops: Vec<LootDrop>,
+}
+
+// move items between inventories, moves as many as fit
+#[derive(Message, Debug, Clone, Copy)]
+pub struct ItemTransferEvent {
+    pub from: InventoryRef,
+    pub to: InventoryRef,
+    pub item: ItemId,
+    pub count: u32,
+}
+
+// result of an ItemTransferEvent, `moved` can be less than asked
+#[derive(Message, Debug, Clone, Copy)]
+pub struct ItemTransferredEvent {
+    pub from: InventoryRef,
+    pub to: InventoryRef,
+    pub item: ItemId,
+    pub requested: u32,
+    pub moved: u32,
+}
+
+// a Tween finished (every repeat included), sent before it's removed / despawned
+#[derive(Message, Debug, Clone)]
+pub struct TweenCompletedEvent {
+    pub entity: Entity,
+    pub tag: Option<String>,
+}
+
+// plays a particle effect once at `position`, from the ParticlesPlugin's file
+#[derive(Message, Debug, Clone)]
+pub struct SpawnParticlesEvent {
+    pub effect: String,
+    pub position: Vec2,
+}
+
+// plays a cue from the SoundPlugin's file, respecting its concurrency limit
+#[derive(Message, Debug, Clone)]
+pub struct PlayCueEvent {
+    pub cue: String,
+}
+
+// a cue actually started, after limits and randomization
+#[derive(Message, Debug, Clone)]
+pub struct CuePlayedEvent {
+    pub entity: Entity,
+    pub cue: String,
+    pub file: String,
+    pub volume: f32, // cue * bus * master
+    pub speed: f32,
+}
+
+// TODO add SpawnEvent, etc
@@ -1,5 +1,8 @@
+pub mod big_num;
 pub mod components;
+pub mod config;
 pub mod events;
 pub mod plugins;
 pub mod prelude;
+pub mod rng;
 pub mod systems;
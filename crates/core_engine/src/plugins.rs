@@ -1,6 +1,18 @@
 use crate::{
+    config::{
+        AnimationSet, AudioSettings, ItemCatalog, ItemRegistry, LevelCurve, LootTables,
+        ParticleEffects, PityCounters, RonAssetPlugin, SoundCues, UpgradePool,
+    },
     events::*,
-    prelude::{CircleCollider, Health, Lifetime, Velocity},
+    prelude::{
+        AnimationAtlases, ArenaBounds, AudioSettingsFile, CameraFollow, CameraRig, CameraShake,
+        CameraTarget, CircleCollider, ClampToArena, Experience, GamePause, Health, Inventory,
+        ItemCatalogHandle, LevelUpConfig, Lifetime, LootDropper, LootTablesHandle,
+        ParticleEffectsHandle, ParticleEmitter, PendingLevelUps, PlayingCue, ShakeOnDamage, Shield,
+        SoundBackend, SoundCuesHandle, SpriteAnimator, Stats, Tween, Velocity, XpCollector,
+        XpPickup, ZoomToFit,
+    },
+    rng::GameRng,
     systems::*,
 };
 use bevy::prelude::*;
@@ -18,12 +30,16 @@ pub struct CorePlugin;
 impl Plugin for CorePlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<Health>()
+            .register_type::<Shield>()
             .register_type::<Velocity>()
             .register_type::<CircleCollider>()
             .register_type::<Lifetime>()
+            .register_type::<Stats>()
             .add_message::<DamageEvent>()
             .add_message::<HealEvent>()
             .add_message::<DeathEvent>()
+            .add_message::<PickupEvent>()
+            .init_resource::<GameRng>()
             // system sets for organization
             .configure_sets(
                 Update,
@@ -35,6 +51,14 @@ impl Plugin for CorePlugin {
             )
             // movement & kinematics
             .add_systems(Update, apply_velocity.in_set(CoreSet::Simulation))
+            // stats -> derived values (Health.max), before the health pipeline sees them
+            .add_systems(
+                Update,
+                (tick_stat_modifiers, sync_health_from_stats)
+                    .chain()
+                    .in_set(CoreSet::Simulation)
+                    .before(tick_health),
+            )
             // health pipeline
             .add_systems(
                 Update,
@@ -47,3 +71,281 @@ impl Plugin for CorePlugin {
             );
     }
 }
+
+// xp, levels and the level-up choice flow. paths are RON files under the game's assets/
+pub struct XpPlugin {
+    pub curve: Option<&'static str>,    // e.g. "config/survivor.xp.ron"
+    pub upgrades: Option<&'static str>, // e.g. "config/survivor.upgrades.ron"
+    pub choices: usize,
+}
+
+impl Default for XpPlugin {
+    fn default() -> Self {
+        Self {
+            curve: None,
+            upgrades: None,
+            choices: 3,
+        }
+    }
+}
+
+impl Plugin for XpPlugin {
+    fn build(&self, app: &mut App) {
+        let curve = self.curve;
+        let upgrades = self.upgrades;
+        let choices = self.choices;
+
+        app.add_plugins((
+            RonAssetPlugin::<LevelCurve>::new(&["xp.ron"]),
+            RonAssetPlugin::<UpgradePool>::new(&["upgrades.ron"]),
+        ))
+        .register_type::<Experience>()
+        .register_type::<XpPickup>()
+        .register_type::<XpCollector>()
+        .add_message::<XpGainEvent>()
+        .add_message::<LevelUpEvent>()
+        .add_message::<UpgradeChosenEvent>()
+        .init_resource::<GameRng>()
+        .init_resource::<PendingLevelUps>()
+        .init_resource::<GamePause>()
+        .add_systems(
+            Startup,
+            move |mut commands: Commands, server: Res<AssetServer>| {
+                commands.insert_resource(LevelUpConfig {
+                    choices,
+                    curve: curve.map(|p| server.load(p)),
+                    pool: upgrades.map(|p| server.load(p)),
+                    ..default()
+                });
+            },
+        )
+        .add_systems(
+            Update,
+            (
+                magnet_xp_pickups.before(apply_velocity),
+                (collect_xp_pickups, apply_xp_gain, apply_chosen_upgrades)
+                    .chain()
+                    .after(apply_velocity),
+            )
+                .in_set(CoreSet::Simulation)
+                .run_if(resource_exists::<LevelUpConfig>),
+        );
+    }
+}
+
+// weighted loot tables from one RON file, rolled for LootDropper entities on death
+pub struct LootPlugin {
+    pub tables: &'static str, // e.g. "config/enemies.loot.ron"
+}
+
+impl Plugin for LootPlugin {
+    fn build(&self, app: &mut App) {
+        let path = self.tables;
+        app.add_plugins(RonAssetPlugin::<LootTables>::new(&["loot.ron"]))
+            .register_type::<LootDropper>()
+            .add_message::<LootDropEvent>()
+            .init_resource::<GameRng>()
+            .init_resource::<PityCounters>()
+            .add_systems(
+                Startup,
+                move |mut commands: Commands, server: Res<AssetServer>| {
+                    commands.insert_resource(LootTablesHandle(server.load(path)));
+                },
+            )
+            .add_systems(
+                Update,
+                drop_loot_on_death
+                    .in_set(CoreSet::Simulation)
+                    .after(apply_damage_events)
+                    .run_if(resource_exists::<LootTablesHandle>),
+            );
+    }
+}
+
+// shared item catalog (RON) + inventory transfers
+pub struct ItemsPlugin {
+    pub catalog: &'static str, // e.g. "config/tycoon.items.ron"
+}
+
+impl Plugin for ItemsPlugin {
+    fn build(&self, app: &mut App) {
+        let path = self.catalog;
+        app.add_plugins(RonAssetPlugin::<ItemCatalog>::new(&["items.ron"]))
+            .register_type::<Inventory>()
+            .init_resource::<ItemRegistry>()
+            .add_message::<ItemTransferEvent>()
+            .add_message::<ItemTransferredEvent>()
+            .add_systems(
+                Startup,
+                move |mut commands: Commands, server: Res<AssetServer>| {
+                    commands.insert_resource(ItemCatalogHandle(server.load(path)));
+                },
+            )
+            .add_systems(
+                Update,
+                (
+                    sync_item_registry.run_if(resource_exists::<ItemCatalogHandle>),
+                    apply_item_transfers,
+                )
+                    .in_set(CoreSet::PrePhysics),
+            );
+    }
+}
+
+// follow / dead-zone / arena clamp / zoom-to-fit / trauma shake for 2d cameras
+pub struct CameraPlugin;
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<ArenaBounds>()
+            .register_type::<CameraRig>()
+            .register_type::<CameraFollow>()
+            .register_type::<ClampToArena>()
+            .register_type::<CameraShake>()
+            .register_type::<ShakeOnDamage>()
+            .register_type::<CameraTarget>()
+            .register_type::<ZoomToFit>()
+            // after gameplay moved things, before transforms propagate
+            .add_systems(
+                PostUpdate,
+                (
+                    follow_camera_target,
+                    zoom_to_fit_targets,
+                    clamp_camera_to_arena,
+                    shake_on_damage,
+                    apply_camera_rig,
+                )
+                    .chain()
+                    .before(TransformSystems::Propagate),
+            );
+    }
+}
+
+// easing tweens for Transform, sprite / ui colors, ui nodes and reflected f32 fields
+pub struct TweenPlugin;
+
+impl Plugin for TweenPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Tween>()
+            .add_message::<TweenCompletedEvent>()
+            // after gameplay, so a tween wins over whatever Update wrote this frame
+            .add_systems(
+                PostUpdate,
+                advance_tweens.before(TransformSystems::Propagate),
+            );
+    }
+}
+
+// CPU particles from one RON file, played by ParticleEmitter entities or on gameplay messages
+pub struct ParticlesPlugin {
+    pub effects: &'static str, // e.g. "config/survivor.particles.ron"
+}
+
+impl Plugin for ParticlesPlugin {
+    fn build(&self, app: &mut App) {
+        let path = self.effects;
+        app.add_plugins(RonAssetPlugin::<ParticleEffects>::new(&["particles.ron"]))
+            .register_type::<ParticleEmitter>()
+            .add_message::<DamageEvent>()
+            .add_message::<DeathEvent>()
+            .add_message::<PickupEvent>()
+            .add_message::<SpawnParticlesEvent>()
+            .init_resource::<GameRng>()
+            .add_systems(
+                Startup,
+                move |mut commands: Commands, server: Res<AssetServer>| {
+                    commands.insert_resource(ParticleEffectsHandle(server.load(path)));
+                },
+            )
+            .add_systems(
+                Update,
+                (
+                    particles_from_messages,
+                    spawn_particle_bursts,
+                    simulate_particles,
+                    draw_particles.run_if(can_draw_particles),
+                )
+                    .chain()
+                    .in_set(CoreSet::Post)
+                    // dying entities still have their transform
+                    .before(despawn_on_death)
+                    .run_if(resource_exists::<ParticleEffectsHandle>),
+            );
+    }
+}
+
+// sprite-sheet clips + idle/move/hurt/die state machine, from "*.anim.ron" files.
+// edits to a loaded file re-slice the atlas and restart its clips
+pub struct SpriteAnimationPlugin;
+
+impl Plugin for SpriteAnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(RonAssetPlugin::<AnimationSet>::new(&["anim.ron"]))
+            .register_type::<SpriteAnimator>()
+            .init_resource::<AnimationAtlases>()
+            .add_systems(
+                Update,
+                (build_animation_atlases, animate_sprites)
+                    .chain()
+                    .in_set(CoreSet::Post),
+            );
+    }
+}
+
+// sound cues from one RON file on master/music/sfx buses, played on gameplay messages
+// or PlayCueEvent. bus volumes are read from / written to `settings` if set
+pub struct SoundPlugin {
+    pub cues: &'static str,             // e.g. "config/slots.sounds.ron"
+    pub settings: Option<&'static str>, // e.g. "settings/audio.ron", relative to the working dir
+    pub backend: SoundBackend,
+}
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        let path = self.cues;
+        let settings = self
+            .settings
+            .and_then(AudioSettings::load)
+            .unwrap_or_default();
+
+        app.add_plugins(RonAssetPlugin::<SoundCues>::new(&["sounds.ron"]))
+            .register_type::<AudioSettings>()
+            .register_type::<PlayingCue>()
+            .insert_resource(settings)
+            .insert_resource(self.backend)
+            .init_resource::<GameRng>()
+            .add_message::<DamageEvent>()
+            .add_message::<DeathEvent>()
+            .add_message::<PickupEvent>()
+            .add_message::<LevelUpEvent>()
+            .add_message::<TweenCompletedEvent>()
+            .add_message::<PlayCueEvent>()
+            .add_message::<CuePlayedEvent>()
+            .add_systems(
+                Startup,
+                move |mut commands: Commands, server: Res<AssetServer>| {
+                    commands.insert_resource(SoundCuesHandle(server.load(path)));
+                },
+            )
+            .add_systems(
+                Update,
+                (cues_from_messages, play_cues)
+                    .chain()
+                    .in_set(CoreSet::Post)
+                    .run_if(resource_exists::<SoundCuesHandle>),
+            );
+
+        match self.backend {
+            SoundBackend::Bevy => {
+                app.add_systems(Update, (drop_failed_sounds, apply_bus_volumes));
+            }
+            SoundBackend::Null => {
+                app.add_systems(Update, tick_null_sounds);
+            }
+        }
+        if let Some(file) = self.settings {
+            app.insert_resource(AudioSettingsFile(file.to_string()))
+                .add_systems(Last, persist_audio_settings);
+        }
+    }
+}
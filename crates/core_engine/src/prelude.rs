@@ -1,6 +1,15 @@
 // a convinientce re-exporting for you fuckduckfuck
+pub use crate::big_num::{BigNum, Notation};
 pub use crate::components::*;
+pub use crate::config::{
+    AnimState, AnimationSet, AudioBus, AudioSettings, EmitterDef, ItemDef, ItemId, ItemRegistry,
+    ParticleEffects,
+};
 pub use crate::events::*;
-pub use crate::plugins::CorePlugin;
+pub use crate::plugins::{
+    CameraPlugin, CorePlugin, ItemsPlugin, LootPlugin, ParticlesPlugin, SoundPlugin,
+    SpriteAnimationPlugin, TweenPlugin, XpPlugin,
+};
+pub use crate::rng::GameRng;
 pub use crate::systems::*;
 pub use bevy::prelude::*;
@@ -0,0 +1,18 @@
+use bevy::prelude::*;
+use rand::{SeedableRng, rngs::StdRng};
+
+// shared, seedable rng so drops/choices can be replayed with a fixed seed
+#[derive(Resource, Deref, DerefMut)]
+pub struct GameRng(pub StdRng);
+
+impl GameRng {
+    pub fn seeded(seed: u64) -> Self {
+        Self(StdRng::seed_from_u64(seed))
+    }
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self(StdRng::from_os_rng())
+    }
+}
@@ -0,0 +1,139 @@
+use bevy::prelude::*;
+
+use crate::{components::*, events::DamageEvent};
+
+// exponential smoothing that doesn't depend on frame rate
+fn approach(rate: f32, dt: f32) -> f32 {
+    if rate <= 0.0 {
+        1.0
+    } else {
+        1.0 - (-rate * dt).exp()
+    }
+}
+
+fn view_size(rig: &CameraRig, camera: Option<&Camera>) -> Vec2 {
+    camera
+        .and_then(Camera::logical_viewport_size)
+        .unwrap_or(rig.fallback_view)
+}
+
+pub fn follow_camera_target(
+    time: Res<Time>,
+    mut cams: Query<(&CameraFollow, &mut CameraRig)>,
+    targets: Query<&Transform, Without<CameraRig>>,
+) {
+    let dt = time.delta_secs();
+    for (follow, mut rig) in cams.iter_mut() {
+        let Ok(target) = targets.get(follow.target) else {
+            continue;
+        };
+        let wanted = target.translation.truncate() + follow.offset;
+
+        // only move by how far the target left the dead zone
+        let delta = wanted - rig.focus;
+        let outside = Vec2::new(
+            delta.x.signum() * (delta.x.abs() - follow.dead_zone.x).max(0.0),
+            delta.y.signum() * (delta.y.abs() - follow.dead_zone.y).max(0.0),
+        );
+        rig.focus += outside * approach(follow.smoothing, dt);
+    }
+}
+
+pub fn zoom_to_fit_targets(
+    time: Res<Time>,
+    mut cams: Query<(&ZoomToFit, &mut CameraRig, Option<&Camera>)>,
+    targets: Query<&Transform, (With<CameraTarget>, Without<CameraRig>)>,
+) {
+    let mut points = targets.iter().map(|t| t.translation.truncate());
+    let Some(first) = points.next() else {
+        return;
+    };
+    let (min, max) = points.fold((first, first), |(lo, hi), p| (lo.min(p), hi.max(p)));
+
+    let dt = time.delta_secs();
+    for (fit, mut rig, camera) in cams.iter_mut() {
+        let view = view_size(&rig, camera);
+        let needed = (max - min) + Vec2::splat(fit.padding * 2.0);
+        let zoom = (needed / view)
+            .max_element()
+            .clamp(fit.min_zoom, fit.max_zoom);
+
+        let k = approach(fit.smoothing, dt);
+        rig.focus = rig.focus.lerp((min + max) * 0.5, k);
+        rig.zoom += (zoom - rig.zoom) * k;
+    }
+}
+
+pub fn clamp_camera_to_arena(
+    arena: Option<Res<ArenaBounds>>,
+    mut cams: Query<(&mut CameraRig, Option<&Camera>), With<ClampToArena>>,
+) {
+    let Some(arena) = arena else {
+        return;
+    };
+    for (mut rig, camera) in cams.iter_mut() {
+        let half_view = view_size(&rig, camera) * 0.5 * rig.zoom;
+        let room = (arena.half_size() - half_view).max(Vec2::ZERO);
+        rig.focus = rig.focus.clamp(-room, room);
+    }
+}
+
+pub fn shake_on_damage(
+    mut reader: MessageReader<DamageEvent>,
+    victims: Query<&ShakeOnDamage>,
+    mut cams: Query<&mut CameraShake>,
+) {
+    for ev in reader.read() {
+        let Ok(shake) = victims.get(ev.target) else {
+            continue;
+        };
+        let trauma = (ev.amount.max(0.0) * shake.trauma_per_damage).min(shake.max_per_hit);
+        for mut cam in cams.iter_mut() {
+            cam.add_trauma(trauma);
+        }
+    }
+}
+
+// smooth pseudo-noise in -1..1, good enough for shake and needs no rng
+fn wobble(t: f32, seed: f32) -> f32 {
+    (t + seed).sin() * 0.6 + (t * 2.3 + seed * 1.7).sin() * 0.4
+}
+
+// writes rig focus/zoom (+ shake) into the actual camera transform and projection
+pub fn apply_camera_rig(
+    time: Res<Time>,
+    mut cams: Query<(
+        &CameraRig,
+        &mut Transform,
+        Option<&mut CameraShake>,
+        Option<&mut Projection>,
+    )>,
+) {
+    let dt = time.delta_secs();
+    for (rig, mut tf, shake, projection) in cams.iter_mut() {
+        let mut offset = Vec2::ZERO;
+        let mut roll = 0.0;
+        if let Some(mut shake) = shake {
+            shake.t += dt * shake.frequency;
+            let s = shake.strength();
+            if s > 0.0 {
+                offset =
+                    Vec2::new(wobble(shake.t, 0.0), wobble(shake.t, 10.0)) * shake.max_offset * s;
+                roll = wobble(shake.t, 20.0) * shake.max_roll * s;
+            }
+            shake.trauma = (shake.trauma - shake.decay * dt).max(0.0);
+        }
+
+        let pos = rig.focus + offset;
+        tf.translation.x = pos.x;
+        tf.translation.y = pos.y;
+        tf.rotation = Quat::from_rotation_z(roll);
+
+        if let Some(mut projection) = projection
+            && let Projection::Orthographic(ortho) = projection.as_mut()
+            && ortho.scale != rig.zoom
+        {
+            ortho.scale = rig.zoom;
+        }
+    }
+}
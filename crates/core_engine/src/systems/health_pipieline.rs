@@ -1,4 +1,7 @@
-use crate::{components::Health, events::*};
+use crate::{
+    components::{Health, Shield},
+    events::*,
+};
 use bevy::prelude::*;
 
 // ticks invulnerability frames
@@ -14,18 +17,22 @@ pub fn tick_health(mut q: Query<&mut Health>, time: Res<Time>) {
         }
     }
 }
-// consumes DamageEvent -> mutates health, DeathEvent emitted, if needed
+// consumes DamageEvent -> mutates health (after any Shield), DeathEvent emitted, if needed
 pub fn apply_damage_events(
     mut reader: MessageReader<DamageEvent>,
     mut writer_death: MessageWriter<DeathEvent>,
-    mut q: Query<&mut Health>,
+    mut q: Query<(&mut Health, Option<&mut Shield>)>,
 ) {
     for ev in reader.read() {
-        if let Ok(mut h) = q.get_mut(ev.target) {
+        if let Ok((mut h, shield)) = q.get_mut(ev.target) {
             if h.i_frames > 0.0 || h.is_dead() {
                 continue;
             }
-            h.current = (h.current - ev.amount.max(0.0)).max(0.0);
+            let amount = match shield {
+                Some(mut shield) => shield.absorb(ev.amount.max(0.0)),
+                None => ev.amount.max(0.0),
+            };
+            h.current = (h.current - amount).max(0.0);
             if h.is_dead() {
                 writer_death.write(DeathEvent { entity: ev.target });
             }
@@ -0,0 +1,79 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::{Inventory, InventoryRef, ItemCatalogHandle},
+    config::{ItemCatalog, ItemRegistry},
+    events::{ItemTransferEvent, ItemTransferredEvent},
+};
+
+// (re)builds the registry whenever the catalog loads or is edited on disk
+pub fn sync_item_registry(
+    mut events: MessageReader<AssetEvent<ItemCatalog>>,
+    handle: Res<ItemCatalogHandle>,
+    catalogs: Res<Assets<ItemCatalog>>,
+    mut registry: ResMut<ItemRegistry>,
+) {
+    for ev in events.read() {
+        if (ev.is_loaded_with_dependencies(&handle.0) || ev.is_modified(&handle.0))
+            && let Some(catalog) = catalogs.get(&handle.0)
+        {
+            *registry = ItemRegistry::from_catalog(catalog);
+            info!("item registry: {} items", catalog.items.len());
+        }
+    }
+}
+
+pub fn apply_item_transfers(
+    mut reader: MessageReader<ItemTransferEvent>,
+    mut writer: MessageWriter<ItemTransferredEvent>,
+    mut global: Option<ResMut<Inventory>>,
+    mut q: Query<&mut Inventory>,
+    registry: Res<ItemRegistry>,
+) {
+    for ev in reader.read() {
+        if ev.from == ev.to {
+            continue;
+        }
+        // take from the source first, then hand back whatever the target can't hold
+        let taken = match ev.from {
+            InventoryRef::Global => global.as_mut().map_or(0, |inv| inv.take(ev.item, ev.count)),
+            InventoryRef::Entity(e) => q
+                .get_mut(e)
+                .map_or(0, |mut inv| inv.take(ev.item, ev.count)),
+        };
+        let leftover = match ev.to {
+            InventoryRef::Global => match global.as_mut() {
+                Some(inv) => inv.add(ev.item, taken, &registry),
+                None => taken,
+            },
+            InventoryRef::Entity(e) => match q.get_mut(e) {
+                Ok(mut inv) => inv.add(ev.item, taken, &registry),
+                Err(_) => taken,
+            },
+        };
+        if leftover > 0 {
+            let back = match ev.from {
+                InventoryRef::Global => global
+                    .as_mut()
+                    .map(|inv| inv.add(ev.item, leftover, &registry)),
+                InventoryRef::Entity(e) => q
+                    .get_mut(e)
+                    .ok()
+                    .map(|mut inv| inv.add(ev.item, leftover, &registry)),
+            };
+            if let Some(lost) = back
+                && lost > 0
+            {
+                warn!("item transfer lost {} of {:?}", lost, ev.item);
+            }
+        }
+
+        writer.write(ItemTransferredEvent {
+            from: ev.from,
+            to: ev.to,
+            item: ev.item,
+            requested: ev.count,
+            moved: taken - leftover,
+        });
+    }
+}
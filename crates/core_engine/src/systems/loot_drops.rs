@@ -0,0 +1,36 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::{LootDropper, LootTablesHandle},
+    config::{LootTables, PityCounters},
+    events::{DeathEvent, LootDropEvent},
+    rng::GameRng,
+};
+
+// listens to DeathEvent before despawn_on_death removes the entity
+pub fn drop_loot_on_death(
+    mut reader: MessageReader<DeathEvent>,
+    mut writer: MessageWriter<LootDropEvent>,
+    q: Query<(&LootDropper, &GlobalTransform)>,
+    handle: Res<LootTablesHandle>,
+    tables: Res<Assets<LootTables>>,
+    mut rng: ResMut<GameRng>,
+    mut pity: ResMut<PityCounters>,
+) {
+    let Some(tables) = tables.get(&handle.0) else {
+        return;
+    };
+    for ev in reader.read() {
+        let Ok((dropper, tf)) = q.get(ev.entity) else {
+            continue;
+        };
+        let drops = tables.roll(&dropper.table, &mut rng.0, &mut pity);
+        if !drops.is_empty() {
+            writer.write(LootDropEvent {
+                source: ev.entity,
+                position: tf.translation().truncate(),
+                drops,
+            });
+        }
+    }
+}
@@ -1,11 +1,29 @@
+pub mod camera_rig;
 pub mod damage;
 pub mod despawn;
 pub mod health_pipieline;
+pub mod inventory_sync;
 pub mod lifetime;
+pub mod loot_drops;
 pub mod movement;
+pub mod particle_sim;
+pub mod progression;
+pub mod sound_cues;
+pub mod sprite_animation;
+pub mod stat_modifiers;
+pub mod tweening;
 
+pub use camera_rig::*;
 pub use damage::*;
 pub use despawn::*;
 pub use health_pipieline::*;
+pub use inventory_sync::*;
 pub use lifetime::*;
+pub use loot_drops::*;
 pub use movement::*;
+pub use particle_sim::*;
+pub use progression::*;
+pub use sound_cues::*;
+pub use sprite_animation::*;
+pub use stat_modifiers::*;
+pub use tweening::*;
@@ -0,0 +1,124 @@
+use bevy::{gizmos::config::GizmoConfigStore, prelude::*};
+
+use crate::{
+    components::{ParticleEffectsHandle, ParticleEmitter},
+    config::ParticleEffects,
+    events::*,
+    rng::GameRng,
+};
+
+// damage / death / pickup -> SpawnParticlesEvent, using the file's triggers
+pub fn particles_from_messages(
+    handle: Res<ParticleEffectsHandle>,
+    effects: Res<Assets<ParticleEffects>>,
+    mut damage: MessageReader<DamageEvent>,
+    mut death: MessageReader<DeathEvent>,
+    mut pickup: MessageReader<PickupEvent>,
+    q: Query<&GlobalTransform>,
+    mut writer: MessageWriter<SpawnParticlesEvent>,
+) {
+    let Some(triggers) = effects.get(&handle.0).map(|e| &e.triggers) else {
+        damage.clear();
+        death.clear();
+        pickup.clear();
+        return;
+    };
+    let at = |e: Entity| q.get(e).ok().map(|tf| tf.translation().truncate());
+
+    if let Some(effect) = &triggers.damage {
+        for position in damage.read().filter_map(|ev| at(ev.target)) {
+            writer.write(SpawnParticlesEvent {
+                effect: effect.clone(),
+                position,
+            });
+        }
+    }
+    if let Some(effect) = &triggers.death {
+        for position in death.read().filter_map(|ev| at(ev.entity)) {
+            writer.write(SpawnParticlesEvent {
+                effect: effect.clone(),
+                position,
+            });
+        }
+    }
+    if let Some(effect) = &triggers.pickup {
+        for ev in pickup.read() {
+            writer.write(SpawnParticlesEvent {
+                effect: effect.clone(),
+                position: ev.position,
+            });
+        }
+    }
+}
+
+// every SpawnParticlesEvent becomes a short-lived emitter entity. it starts in this
+// frame's simulate_particles, before transform propagation, so the GlobalTransform
+// is set here too
+pub fn spawn_particle_bursts(
+    mut commands: Commands,
+    mut reader: MessageReader<SpawnParticlesEvent>,
+) {
+    for ev in reader.read() {
+        let at = ev.position.extend(0.0);
+        commands.spawn((
+            ParticleEmitter::once(ev.effect.clone()),
+            Transform::from_translation(at),
+            GlobalTransform::from_translation(at),
+        ));
+    }
+}
+
+pub fn simulate_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    handle: Res<ParticleEffectsHandle>,
+    effects: Res<Assets<ParticleEffects>>,
+    mut rng: ResMut<GameRng>,
+    mut q: Query<(Entity, &mut ParticleEmitter, &GlobalTransform)>,
+) {
+    let Some(effects) = effects.get(&handle.0) else {
+        return;
+    };
+    let dt = time.delta_secs();
+    for (entity, mut emitter, tf) in q.iter_mut() {
+        let Some(def) = effects.get(&emitter.effect) else {
+            warn_once!("unknown particle effect {:?}", emitter.effect);
+            if emitter.despawn_when_done {
+                commands.entity(entity).despawn();
+            }
+            continue;
+        };
+        emitter.update(def, tf.translation().truncate(), dt, &mut rng.0);
+        if emitter.despawn_when_done && emitter.is_done(def) {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+// immediate-mode quads, no entity per particle
+pub fn draw_particles(
+    mut gizmos: Gizmos,
+    handle: Res<ParticleEffectsHandle>,
+    effects: Res<Assets<ParticleEffects>>,
+    q: Query<&ParticleEmitter>,
+) {
+    let Some(effects) = effects.get(&handle.0) else {
+        return;
+    };
+    for emitter in q.iter() {
+        let Some(def) = effects.get(&emitter.effect) else {
+            continue;
+        };
+        for p in &emitter.particles {
+            let (size, color) = def.sample(p.age / p.lifetime.max(f32::EPSILON));
+            if size > 0.0 {
+                gizmos.rect_2d(p.position, Vec2::splat(size), color);
+            }
+        }
+    }
+}
+
+// gizmos need the render side, headless apps and tests skip drawing
+pub fn can_draw_particles(store: Option<Res<GizmoConfigStore>>) -> bool {
+    store.is_some()
+}
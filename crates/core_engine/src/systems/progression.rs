@@ -0,0 +1,221 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use rand::seq::IndexedRandom;
+
+use crate::{
+    components::*,
+    config::{LevelCurve, UpgradePool},
+    events::*,
+    rng::GameRng,
+};
+
+type PickupFilter = (With<XpPickup>, Without<XpCollector>);
+
+// level curve + upgrade pool, resolved from the handles in LevelUpConfig
+#[derive(SystemParam)]
+pub struct LevelUpData<'w> {
+    cfg: Res<'w, LevelUpConfig>,
+    curves: Res<'w, Assets<LevelCurve>>,
+    pools: Res<'w, Assets<UpgradePool>>,
+}
+
+impl LevelUpData<'_> {
+    pub fn curve(&self) -> Option<&LevelCurve> {
+        self.cfg.curve.as_ref().and_then(|h| self.curves.get(h))
+    }
+
+    pub fn pool(&self) -> Option<&UpgradePool> {
+        self.cfg.pool.as_ref().and_then(|h| self.pools.get(h))
+    }
+}
+
+// pulls xp orbs that are inside a collector's magnet radius
+pub fn magnet_xp_pickups(
+    collectors: Query<(&Transform, &XpCollector, Option<&Stats>)>,
+    mut pickups: Query<(&Transform, &mut Velocity), PickupFilter>,
+) {
+    for (tf, mut vel) in pickups.iter_mut() {
+        let pos = tf.translation.truncate();
+        // nearest collector that has us in range
+        let target = collectors
+            .iter()
+            .filter_map(|(ctf, c, stats)| {
+                let radius = stats.map_or(c.magnet_radius, |s| {
+                    s.get_or(StatId::MagnetRadius, c.magnet_radius)
+                });
+                let to = ctf.translation.truncate() - pos;
+                (to.length_squared() <= radius * radius).then_some((to, c.pull_speed))
+            })
+            .min_by(|a, b| a.0.length_squared().total_cmp(&b.0.length_squared()));
+
+        if let Some((to, speed)) = target {
+            vel.lin_vel = to.normalize_or_zero() * speed;
+        }
+    }
+}
+
+// touching orbs are consumed -> XpGainEvent + PickupEvent
+pub fn collect_xp_pickups(
+    mut commands: Commands,
+    collectors: Query<(Entity, &Transform, &CircleCollider), With<XpCollector>>,
+    pickups: Query<(Entity, &Transform, &CircleCollider, &XpPickup)>,
+    mut writer_xp: MessageWriter<XpGainEvent>,
+    mut writer_pickup: MessageWriter<PickupEvent>,
+) {
+    for (pickup, ptf, pc, xp) in pickups.iter() {
+        let p = ptf.translation.truncate();
+        for (collector, ctf, cc) in collectors.iter() {
+            let r = pc.radius + cc.radius;
+            if p.distance_squared(ctf.translation.truncate()) <= r * r {
+                writer_xp.write(XpGainEvent {
+                    entity: collector,
+                    amount: xp.amount,
+                });
+                writer_pickup.write(PickupEvent {
+                    collector,
+                    position: p,
+                });
+                commands.entity(pickup).despawn();
+                break;
+            }
+        }
+    }
+}
+
+// adds xp, rolls upgrade choices for every level gained and pauses the game
+pub fn apply_xp_gain(
+    mut reader: MessageReader<XpGainEvent>,
+    mut writer: MessageWriter<LevelUpEvent>,
+    mut q: Query<(&mut Experience, Option<&Stats>)>,
+    data: LevelUpData,
+    mut pending: ResMut<PendingLevelUps>,
+    mut rng: ResMut<GameRng>,
+    mut clock: PauseClock,
+) {
+    let fallback = LevelCurve::default();
+    let curve = data.curve().unwrap_or(&fallback);
+    let pool = data.pool();
+    let cfg = &data.cfg;
+
+    for ev in reader.read() {
+        let Ok((mut exp, stats)) = q.get_mut(ev.entity) else {
+            continue;
+        };
+        exp.total += ev.amount as u64;
+        exp.xp += ev.amount;
+
+        while let Some(need) = curve.xp_to_next(exp.level) {
+            if exp.xp < need {
+                break;
+            }
+            exp.xp -= need;
+            exp.level += 1;
+
+            let choices = pool
+                .map(|p| roll_upgrade_choices(p, stats, cfg.choices, &mut rng))
+                .unwrap_or_default();
+
+            writer.write(LevelUpEvent {
+                entity: ev.entity,
+                level: exp.level,
+                choices: choices.clone(),
+            });
+            // nothing to choose from -> no reason to stop the game
+            if !choices.is_empty() {
+                pending.queue.push_back(LevelUpOffer {
+                    entity: ev.entity,
+                    level: exp.level,
+                    choices,
+                });
+            }
+        }
+        // capped: don't keep piling xp into a level that never comes
+        if curve.xp_to_next(exp.level).is_none() {
+            exp.xp = 0;
+        }
+    }
+
+    if cfg.pause_on_level_up && !pending.is_empty() {
+        clock.hold(GamePause::LEVEL_UP);
+    }
+}
+
+// picks `amount` distinct upgrades by weight, skipping the ones already at max stacks
+pub fn roll_upgrade_choices(
+    pool: &UpgradePool,
+    stats: Option<&Stats>,
+    amount: usize,
+    rng: &mut GameRng,
+) -> Vec<String> {
+    let eligible: Vec<_> = pool
+        .upgrades
+        .iter()
+        .filter(|u| u.weight > 0.0)
+        .filter(|u| match (u.max_stacks, stats) {
+            (Some(max), Some(stats)) => upgrade_stacks(stats, &u.source(), u.modifiers.len()) < max,
+            (Some(0), None) => false,
+            _ => true,
+        })
+        .collect();
+
+    eligible
+        .choose_multiple_weighted(&mut rng.0, amount, |u| u.weight)
+        .map(|it| it.map(|u| u.id.clone()).collect())
+        .unwrap_or_default()
+}
+
+fn upgrade_stacks(stats: &Stats, source: &str, modifiers_per_stack: usize) -> u32 {
+    (stats.count_source(&source.into()) / modifiers_per_stack.max(1)) as u32
+}
+
+// applies the picked upgrade to Stats, resumes the game once every offer is answered
+pub fn apply_chosen_upgrades(
+    mut commands: Commands,
+    mut reader: MessageReader<UpgradeChosenEvent>,
+    mut q: Query<Option<&mut Stats>>,
+    data: LevelUpData,
+    mut pending: ResMut<PendingLevelUps>,
+    mut clock: PauseClock,
+) {
+    let pool = data.pool();
+
+    for ev in reader.read() {
+        // answer the matching offer (normally the front one), anything else wasn't offered
+        let Some(idx) = pending
+            .queue
+            .iter()
+            .position(|o| o.entity == ev.entity && o.choices.contains(&ev.upgrade))
+        else {
+            warn!("{:?} wasn't offered {:?}", ev.entity, ev.upgrade);
+            continue;
+        };
+        pending.queue.remove(idx);
+
+        let Some(def) = pool.and_then(|p| p.get(&ev.upgrade)) else {
+            warn!("unknown upgrade {:?}", ev.upgrade);
+            continue;
+        };
+        let Ok(stats) = q.get_mut(ev.entity) else {
+            continue;
+        };
+
+        let source = def.source();
+        let apply = |stats: &mut Stats| {
+            for m in &def.modifiers {
+                stats.add_modifier(m.stat, StatModifier::new(source.as_str(), m.kind, m.value));
+            }
+        };
+        match stats {
+            Some(mut stats) => apply(&mut stats),
+            None => {
+                let mut stats = Stats::new();
+                apply(&mut stats);
+                commands.entity(ev.entity).insert(stats);
+            }
+        }
+    }
+
+    // only lets go of our own hold, a menu that's open keeps the game paused
+    if pending.is_empty() {
+        clock.release(GamePause::LEVEL_UP);
+    }
+}
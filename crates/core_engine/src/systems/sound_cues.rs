@@ -0,0 +1,240 @@
+use bevy::{
+    audio::{AudioSinkPlayback, Volume},
+    ecs::system::SystemParam,
+    platform::collections::HashMap,
+    prelude::*,
+};
+use rand::{Rng, seq::IndexedRandom};
+
+use crate::{
+    components::{AudioSettingsFile, NullSound, PlayingCue, SoundBackend, SoundCuesHandle},
+    config::{AudioSettings, CueTrigger, LimitPolicy, SoundCues},
+    events::*,
+    rng::GameRng,
+};
+
+// how long a null-backend sound "plays"
+const NULL_SOUND_SECS: f32 = 0.5;
+
+// the cue table + bus volumes + backend, resolved together
+#[derive(SystemParam)]
+pub struct SoundCueData<'w> {
+    handle: Res<'w, SoundCuesHandle>,
+    cues: Res<'w, Assets<SoundCues>>,
+    settings: Res<'w, AudioSettings>,
+    backend: Res<'w, SoundBackend>,
+    // missing in headless apps, those always play through the null backend
+    server: Option<Res<'w, AssetServer>>,
+}
+
+impl SoundCueData<'_> {
+    pub fn cues(&self) -> Option<&SoundCues> {
+        self.cues.get(&self.handle.0)
+    }
+}
+
+// damage / death / pickup / level up / finished tweens -> PlayCueEvent, using the file's triggers
+pub fn cues_from_messages(
+    data: SoundCueData,
+    mut damage: MessageReader<DamageEvent>,
+    mut death: MessageReader<DeathEvent>,
+    mut pickup: MessageReader<PickupEvent>,
+    mut level_up: MessageReader<LevelUpEvent>,
+    mut tweens: MessageReader<TweenCompletedEvent>,
+    mut writer: MessageWriter<PlayCueEvent>,
+) {
+    let Some(cues) = data.cues() else {
+        damage.clear();
+        death.clear();
+        pickup.clear();
+        level_up.clear();
+        tweens.clear();
+        return;
+    };
+
+    let mut fired = Vec::new();
+    fired.extend(damage.read().map(|_| CueTrigger::Damage));
+    fired.extend(death.read().map(|_| CueTrigger::Death));
+    fired.extend(pickup.read().map(|_| CueTrigger::Pickup));
+    fired.extend(level_up.read().map(|_| CueTrigger::LevelUp));
+    fired.extend(
+        tweens
+            .read()
+            .filter_map(|ev| ev.tag.clone().map(CueTrigger::TweenCompleted)),
+    );
+
+    for on in &fired {
+        for cue in cues.cues_for(on) {
+            writer.write(PlayCueEvent {
+                cue: cue.to_string(),
+            });
+        }
+    }
+}
+
+// starts cues: concurrency limit, random file and pitch, bus volume
+pub fn play_cues(
+    mut commands: Commands,
+    mut reader: MessageReader<PlayCueEvent>,
+    mut writer: MessageWriter<CuePlayedEvent>,
+    data: SoundCueData,
+    time: Res<Time>,
+    mut rng: ResMut<GameRng>,
+    playing: Query<(Entity, &PlayingCue)>,
+) {
+    let Some(cues) = data.cues() else {
+        reader.clear();
+        return;
+    };
+
+    // running instances per cue, oldest first
+    let mut running: HashMap<&str, Vec<(f32, Entity)>> = HashMap::default();
+    for (e, p) in playing.iter() {
+        running
+            .entry(p.cue.as_str())
+            .or_default()
+            .push((p.started, e));
+    }
+    for list in running.values_mut() {
+        list.sort_by(|a, b| a.0.total_cmp(&b.0));
+    }
+
+    let now = time.elapsed_secs();
+    for ev in reader.read() {
+        let Some((name, cue)) = cues.cues.get_key_value(ev.cue.as_str()) else {
+            warn_once!("unknown sound cue {:?}", ev.cue);
+            continue;
+        };
+        let Some(file) = cue.files.choose(&mut rng.0) else {
+            continue;
+        };
+
+        let list = running.entry(name.as_str()).or_default();
+        if let Some(max) = cue.max_concurrent
+            && list.len() >= max as usize
+        {
+            match cue.on_limit {
+                LimitPolicy::Skip => continue,
+                LimitPolicy::StealOldest => {
+                    if list.is_empty() {
+                        continue; // max_concurrent: 0
+                    }
+                    let (_, oldest) = list.remove(0);
+                    commands.entity(oldest).despawn();
+                }
+            }
+        }
+
+        let (lo, hi) = cue.pitch;
+        let speed = if hi > lo {
+            rng.random_range(lo..=hi)
+        } else {
+            lo
+        };
+        let volume = cue.volume * data.settings.bus_volume(cue.bus);
+
+        let mut entity = commands.spawn(PlayingCue {
+            cue: name.clone(),
+            bus: cue.bus,
+            volume: cue.volume,
+            started: now,
+        });
+        match (*data.backend, data.server.as_ref()) {
+            (SoundBackend::Bevy, Some(server)) => {
+                let settings = if cue.looping {
+                    PlaybackSettings::LOOP
+                } else {
+                    PlaybackSettings::DESPAWN
+                };
+                entity.insert((
+                    AudioPlayer::<AudioSource>(server.load(file)),
+                    settings
+                        .with_volume(Volume::Linear(volume))
+                        .with_speed(speed),
+                ));
+            }
+            _ => {
+                let remaining = if cue.looping {
+                    f32::INFINITY
+                } else {
+                    NULL_SOUND_SECS
+                };
+                entity.insert(NullSound { remaining });
+            }
+        }
+        let id = entity.id();
+        list.push((now, id));
+        writer.write(CuePlayedEvent {
+            entity: id,
+            cue: name.clone(),
+            file: file.clone(),
+            volume,
+            speed,
+        });
+    }
+}
+
+pub fn tick_null_sounds(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut q: Query<(Entity, &mut NullSound)>,
+) {
+    let dt = time.delta_secs();
+    for (e, mut sound) in q.iter_mut() {
+        sound.remaining -= dt;
+        if sound.remaining <= 0.0 {
+            commands.entity(e).despawn();
+        }
+    }
+}
+
+// a missing / broken file never starts playing, free its concurrency slot
+pub fn drop_failed_sounds(
+    mut commands: Commands,
+    server: Res<AssetServer>,
+    q: Query<(Entity, &AudioPlayer), With<PlayingCue>>,
+) {
+    for (e, player) in q.iter() {
+        if server.load_state(player.0.id()).is_failed() {
+            commands.entity(e).despawn();
+        }
+    }
+}
+
+// volume sliders move sounds that are already playing too
+pub fn apply_bus_volumes(
+    settings: Res<AudioSettings>,
+    mut q: Query<(&PlayingCue, &mut AudioSink)>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+    for (cue, mut sink) in q.iter_mut() {
+        sink.set_volume(Volume::Linear(cue.volume * settings.bus_volume(cue.bus)));
+    }
+}
+
+// a dragged slider changes the settings every frame, so the file is only written once
+// they've been still for a moment (real time, the menu pauses the game clock), or on exit
+pub fn persist_audio_settings(
+    settings: Res<AudioSettings>,
+    file: Res<AudioSettingsFile>,
+    time: Res<Time<Real>>,
+    exit: MessageReader<AppExit>,
+    mut pending: Local<Option<Timer>>,
+) {
+    if settings.is_changed() && !settings.is_added() {
+        *pending = Some(Timer::from_seconds(0.5, TimerMode::Once));
+    }
+    let Some(timer) = pending.as_mut() else {
+        return;
+    };
+    timer.tick(time.delta());
+    if !timer.is_finished() && exit.is_empty() {
+        return;
+    }
+    *pending = None;
+    if let Err(e) = settings.save(&file.0) {
+        warn!("could not save audio settings to {:?}: {e}", file.0);
+    }
+}
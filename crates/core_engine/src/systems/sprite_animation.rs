@@ -0,0 +1,108 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::{AnimationAtlases, Health, SpriteAnimator, Velocity},
+    config::{AnimInputs, AnimationSet},
+};
+
+// slices the atlas once a set loads, and again whenever its file is edited
+pub fn build_animation_atlases(
+    mut events: MessageReader<AssetEvent<AnimationSet>>,
+    sets: Res<Assets<AnimationSet>>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut atlases: ResMut<AnimationAtlases>,
+    mut animators: Query<&mut SpriteAnimator>,
+) {
+    for ev in events.read() {
+        let (id, reloaded) = match ev {
+            AssetEvent::LoadedWithDependencies { id } => (*id, false),
+            AssetEvent::Modified { id } => (*id, true),
+            AssetEvent::Removed { id } | AssetEvent::Unused { id } => {
+                if let Some(layout) = atlases.0.remove(id) {
+                    layouts.remove(&layout);
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        let Some(set) = sets.get(id) else {
+            continue;
+        };
+        match atlases.0.get(&id) {
+            Some(layout) => {
+                layouts.insert(layout, set.atlas.layout()).ok();
+            }
+            None => {
+                atlases.0.insert(id, layouts.add(set.atlas.layout()));
+            }
+        }
+        // clips may have changed length, start them over
+        if reloaded {
+            for mut animator in animators.iter_mut().filter(|a| a.set.id() == id) {
+                animator.restart();
+            }
+        }
+    }
+}
+
+// runs the state machine from Velocity / Health, then advances the clip and shows its frame
+pub fn animate_sprites(
+    time: Res<Time>,
+    sets: Res<Assets<AnimationSet>>,
+    atlases: Res<AnimationAtlases>,
+    mut q: Query<(
+        &mut SpriteAnimator,
+        &mut Sprite,
+        Option<&Velocity>,
+        Option<&Health>,
+    )>,
+) {
+    let dt = time.delta_secs();
+    for (mut animator, mut sprite, vel, health) in q.iter_mut() {
+        let (Some(set), Some(layout)) =
+            (sets.get(&animator.set), atlases.0.get(&animator.set.id()))
+        else {
+            continue;
+        };
+
+        let velocity = vel.map_or(Vec2::ZERO, |v| v.lin_vel);
+        let current = health.map(|h| h.current);
+        let inputs = AnimInputs {
+            speed: velocity.length(),
+            hurt: matches!((animator.last_health, current), (Some(before), Some(now)) if now < before),
+            dead: current.is_some_and(|c| c <= 0.0),
+            clip_finished: animator.finished,
+        };
+        animator.last_health = current;
+
+        let next = set.next_state(animator.state, &inputs);
+        if next != animator.state {
+            animator.play(next);
+        }
+
+        let Some(clip) = set.clip(animator.state) else {
+            continue;
+        };
+        let a = &mut *animator;
+        if !a.finished {
+            a.finished = clip.advance(&mut a.frame, &mut a.timer, dt * a.speed);
+        }
+        let Some(&index) = clip.frames.get(animator.frame) else {
+            continue;
+        };
+        let index = index.min(set.atlas.len().saturating_sub(1));
+
+        match sprite.texture_atlas.as_mut() {
+            Some(atlas) if atlas.layout == *layout => atlas.index = index,
+            _ => {
+                sprite.texture_atlas = Some(TextureAtlas {
+                    layout: layout.clone(),
+                    index,
+                })
+            }
+        }
+        if set.flip_with_velocity && velocity.x.abs() > set.move_threshold {
+            sprite.flip_x = velocity.x < 0.0;
+        }
+    }
+}
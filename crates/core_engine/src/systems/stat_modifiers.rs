@@ -0,0 +1,26 @@
+use crate::components::{Health, StatId, Stats};
+use bevy::prelude::*;
+
+// counts down timed modifiers (buffs), only marks Stats changed when one expires
+pub fn tick_stat_modifiers(mut q: Query<&mut Stats>, time: Res<Time>) {
+    let dt = time.delta_secs();
+    for mut stats in q.iter_mut() {
+        if !stats.has_timed_modifiers() {
+            continue;
+        }
+        if stats.bypass_change_detection().tick(dt) {
+            stats.set_changed();
+        }
+    }
+}
+
+// Health.max follows StatId::MaxHealth, keeping the current ratio
+pub fn sync_health_from_stats(mut q: Query<(&Stats, &mut Health), Changed<Stats>>) {
+    for (stats, mut h) in q.iter_mut() {
+        if let Some(max) = stats.get(StatId::MaxHealth)
+            && max != h.max
+        {
+            h.set_max(max, true);
+        }
+    }
+}
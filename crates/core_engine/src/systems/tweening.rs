@@ -0,0 +1,173 @@
+use bevy::prelude::*;
+
+use crate::{components::*, events::TweenCompletedEvent};
+
+type TweenTargets<'a> = (
+    Option<&'a mut Transform>,
+    Option<&'a mut Sprite>,
+    Option<&'a mut Node>,
+    Option<&'a mut BackgroundColor>,
+    Option<&'a mut TextColor>,
+);
+
+type TweenTargetsMut<'a> = (
+    Option<Mut<'a, Transform>>,
+    Option<Mut<'a, Sprite>>,
+    Option<Mut<'a, Node>>,
+    Option<Mut<'a, BackgroundColor>>,
+    Option<Mut<'a, TextColor>>,
+);
+
+// advances every Tween and writes the eased values, finished ones send TweenCompletedEvent
+pub fn advance_tweens(
+    mut commands: Commands,
+    time: Res<Time>,
+    real: Res<Time<Real>>,
+    mut q: Query<(Entity, &mut Tween, TweenTargets)>,
+    mut writer: MessageWriter<TweenCompletedEvent>,
+    mut active: Local<Vec<(TweenLens, f32)>>,
+) {
+    for (entity, mut tween, mut targets) in q.iter_mut() {
+        let dt = if tween.real_time {
+            real.delta_secs()
+        } else {
+            time.delta_secs()
+        };
+        let total = tween.step.duration();
+        let mut done = false;
+
+        active.clear();
+        tween.elapsed += dt;
+        // a long frame can wrap a short looping tween more than once, play each end
+        loop {
+            let now = tween.elapsed.min(total);
+            tween.step.visit(0.0, tween.prev, now, &mut active);
+            tween.prev = now;
+            if tween.elapsed < total {
+                break;
+            }
+
+            tween.played += 1;
+            let more = match tween.repeat {
+                TweenRepeat::Once => false,
+                TweenRepeat::Times(n) => tween.played < n,
+                TweenRepeat::Forever => total > 0.0,
+            };
+            if !more {
+                done = true;
+                break;
+            }
+            tween.elapsed -= total;
+            tween.prev = -1.0;
+        }
+
+        for (lens, t) in active.drain(..) {
+            apply_lens(&mut commands, entity, lens, t, &mut targets);
+        }
+
+        if done {
+            writer.write(TweenCompletedEvent {
+                entity,
+                tag: tween.tag.clone(),
+            });
+            if tween.despawn_on_complete {
+                commands.entity(entity).despawn();
+            } else {
+                commands.entity(entity).remove::<Tween>();
+            }
+        }
+    }
+}
+
+fn apply_lens(
+    commands: &mut Commands,
+    entity: Entity,
+    lens: TweenLens,
+    t: f32,
+    (transform, sprite, node, bg, text): &mut TweenTargetsMut,
+) {
+    match lens {
+        TweenLens::Translation { from, to } => {
+            if let Some(tf) = transform {
+                tf.translation = from.lerp(to, t);
+            }
+        }
+        TweenLens::Scale { from, to } => {
+            if let Some(tf) = transform {
+                tf.scale = from.lerp(to, t);
+            }
+        }
+        TweenLens::Rotation { from, to } => {
+            if let Some(tf) = transform {
+                tf.rotation = from.slerp(to, t);
+            }
+        }
+        TweenLens::SpriteColor { from, to } => {
+            if let Some(sprite) = sprite {
+                sprite.color = from.mix(&to, t);
+            }
+        }
+        TweenLens::NodeSize { from, to } => {
+            if let Some(node) = node {
+                let size = from.lerp(to, t);
+                node.width = Val::Px(size.x);
+                node.height = Val::Px(size.y);
+            }
+        }
+        TweenLens::NodeOffset { from, to } => {
+            if let Some(node) = node {
+                let at = from.lerp(to, t);
+                node.left = Val::Px(at.x);
+                node.top = Val::Px(at.y);
+            }
+        }
+        TweenLens::BackgroundColor { from, to } => {
+            if let Some(bg) = bg {
+                bg.0 = from.mix(&to, t);
+            }
+        }
+        TweenLens::TextColor { from, to } => {
+            if let Some(text) = text {
+                text.0 = from.mix(&to, t);
+            }
+        }
+        TweenLens::Field {
+            component,
+            path,
+            from,
+            to,
+        } => {
+            let value = from + (to - from) * t;
+            commands.queue(move |world: &mut World| {
+                set_reflected_f32(world, entity, &component, &path, value);
+            });
+        }
+    }
+}
+
+// needs the world for the type registry, so Field lenses run as commands
+fn set_reflected_f32(world: &mut World, entity: Entity, component: &str, path: &str, value: f32) {
+    let registry = world.resource::<AppTypeRegistry>().clone();
+    let registry = registry.read();
+    let Some(reflect) = registry
+        .get_with_type_path(component)
+        .and_then(|r| r.data::<ReflectComponent>())
+    else {
+        warn_once!("tween target {component} is not a registered reflected component");
+        return;
+    };
+    let Ok(entity) = world.get_entity_mut(entity) else {
+        return;
+    };
+    let Some(mut target) = reflect.reflect_mut(entity) else {
+        return;
+    };
+    match target
+        .reflect_path_mut(path)
+        .ok()
+        .and_then(|f| f.try_downcast_mut::<f32>())
+    {
+        Some(field) => *field = value,
+        None => warn_once!("tween field {component}::{path} is not an f32"),
+    }
+}
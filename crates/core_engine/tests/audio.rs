@@ -0,0 +1,191 @@
+use std::time::Duration;
+
+use bevy::time::TimeUpdateStrategy;
+use core_engine::{config::SoundCues, prelude::*};
+
+const CUES: &str = r#"(
+    cues: {
+        "hit": (files: ["audio/hit1.ogg", "audio/hit2.ogg"], volume: 0.5, pitch: (0.9, 1.1), max_concurrent: Some(2)),
+        "reel": (files: ["audio/reel.ogg"]),
+        "theme": (files: ["audio/theme.ogg"], bus: Music, looping: true, max_concurrent: Some(1), on_limit: StealOldest),
+    },
+    triggers: [
+        (on: Damage, cue: "hit"),
+        (on: TweenCompleted("reel_stop"), cue: "reel"),
+    ],
+)"#;
+
+// headless app on the null backend, every update advances time by 100ms
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        SoundPlugin {
+            cues: "missing.sounds.ron",
+            settings: None,
+            backend: SoundBackend::Null,
+        },
+    ))
+    .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    app.update();
+
+    let cues: SoundCues = ron::from_str(CUES).unwrap();
+    let handle = app
+        .world_mut()
+        .resource_mut::<Assets<SoundCues>>()
+        .add(cues);
+    app.insert_resource(SoundCuesHandle(handle));
+    app
+}
+
+fn played(app: &App) -> Vec<CuePlayedEvent> {
+    app.world()
+        .resource::<Messages<CuePlayedEvent>>()
+        .iter_current_update_messages()
+        .cloned()
+        .collect()
+}
+
+fn play(app: &mut App, cue: &str, times: usize) {
+    for _ in 0..times {
+        app.world_mut().write_message(PlayCueEvent {
+            cue: cue.to_string(),
+        });
+    }
+}
+
+#[test]
+fn damage_plays_its_cue_on_the_sfx_bus() {
+    let mut app = app();
+    app.world_mut().resource_mut::<AudioSettings>().master = 0.5;
+    app.world_mut().write_message(DamageEvent {
+        target: Entity::PLACEHOLDER,
+        amount: 1.0,
+    });
+    app.update();
+
+    let played = played(&app);
+    assert_eq!(played.len(), 1);
+    let hit = &played[0];
+    assert_eq!(hit.cue, "hit");
+    assert!(hit.file.starts_with("audio/hit"));
+    assert!((0.9..=1.1).contains(&hit.speed));
+    // cue 0.5 * master 0.5 * sfx 1.0
+    assert!((hit.volume - 0.25).abs() < 1e-6);
+}
+
+#[test]
+fn tween_tags_trigger_cues() {
+    let mut app = app();
+    app.world_mut().write_message(TweenCompletedEvent {
+        entity: Entity::PLACEHOLDER,
+        tag: Some("reel_stop".into()),
+    });
+    app.update();
+    assert_eq!(played(&app)[0].cue, "reel");
+}
+
+#[test]
+fn concurrency_limit_skips_until_a_slot_frees() {
+    let mut app = app();
+    play(&mut app, "hit", 5);
+    app.update();
+    assert_eq!(played(&app).len(), 2);
+
+    play(&mut app, "hit", 1);
+    app.update();
+    assert!(played(&app).is_empty());
+
+    // null sounds last half a second
+    for _ in 0..5 {
+        app.update();
+    }
+    play(&mut app, "hit", 1);
+    app.update();
+    assert_eq!(played(&app).len(), 1);
+}
+
+#[test]
+fn steal_oldest_replaces_the_running_instance() {
+    let mut app = app();
+    play(&mut app, "theme", 1);
+    app.update();
+    let first = played(&app)[0].entity;
+
+    play(&mut app, "theme", 1);
+    app.update();
+    let second = played(&app)[0].entity;
+
+    let mut q = app.world_mut().query::<(Entity, &PlayingCue)>();
+    let running: Vec<_> = q.iter(app.world()).map(|(e, _)| e).collect();
+    assert_eq!(running, vec![second]);
+    assert_ne!(first, second);
+}
+
+#[test]
+fn muted_and_music_bus_volumes() {
+    let mut app = app();
+    app.world_mut().resource_mut::<AudioSettings>().music = 0.4;
+    play(&mut app, "theme", 1);
+    app.update();
+    assert!((played(&app)[0].volume - 0.4).abs() < 1e-6);
+
+    app.world_mut().resource_mut::<AudioSettings>().muted = true;
+    play(&mut app, "reel", 1);
+    app.update();
+    assert_eq!(played(&app)[0].volume, 0.0);
+}
+
+#[test]
+fn settings_round_trip() {
+    let path = std::env::temp_dir().join(format!("core_engine_audio_{}.ron", std::process::id()));
+    let settings = AudioSettings {
+        master: 0.3,
+        music: 0.2,
+        sfx: 0.9,
+        muted: true,
+    };
+    settings.save(&path).unwrap();
+    assert_eq!(AudioSettings::load(&path), Some(settings));
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(AudioSettings::load(&path), None);
+}
+
+#[test]
+fn held_sliders_are_saved_once_they_settle() {
+    let path = std::env::temp_dir().join(format!(
+        "core_engine_audio_slider_{}.ron",
+        std::process::id()
+    ));
+    let file: &'static str = path.to_str().unwrap().to_string().leak();
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        SoundPlugin {
+            cues: "missing.sounds.ron",
+            settings: Some(file),
+            backend: SoundBackend::Null,
+        },
+    ))
+    .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    app.update();
+
+    // dragged for half a second, nothing written while it moves
+    for step in 1..=5 {
+        app.world_mut().resource_mut::<AudioSettings>().master = step as f32 / 10.0;
+        app.update();
+    }
+    assert!(!path.exists());
+
+    for _ in 0..6 {
+        app.update();
+    }
+    assert_eq!(AudioSettings::load(&path).unwrap().master, 0.5);
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,172 @@
+use std::time::Duration;
+
+use bevy::time::TimeUpdateStrategy;
+use core_engine::prelude::*;
+
+// headless app, every update advances time by 100ms
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, CorePlugin, CameraPlugin))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+            100,
+        )));
+    app.update(); // first frame has dt = 0
+    app
+}
+
+fn camera_pos(app: &mut App, cam: Entity) -> Vec2 {
+    app.world()
+        .get::<Transform>(cam)
+        .unwrap()
+        .translation
+        .truncate()
+}
+
+#[test]
+fn follow_snaps_without_smoothing() {
+    let mut app = app();
+    let target = app
+        .world_mut()
+        .spawn(Transform::from_xyz(100.0, -50.0, 0.0))
+        .id();
+    let cam = app
+        .world_mut()
+        .spawn((
+            Transform::default(),
+            CameraFollow::new(target).with_smoothing(0.0),
+        ))
+        .id();
+
+    app.update();
+    assert_eq!(camera_pos(&mut app, cam), Vec2::new(100.0, -50.0));
+}
+
+#[test]
+fn dead_zone_holds_camera_still() {
+    let mut app = app();
+    let target = app
+        .world_mut()
+        .spawn(Transform::from_xyz(30.0, 0.0, 0.0))
+        .id();
+    let cam = app
+        .world_mut()
+        .spawn((
+            Transform::default(),
+            CameraFollow::new(target)
+                .with_smoothing(0.0)
+                .with_dead_zone(Vec2::new(50.0, 50.0)),
+        ))
+        .id();
+
+    app.update();
+    assert_eq!(camera_pos(&mut app, cam), Vec2::ZERO);
+
+    // 20 past the edge -> camera moves 20
+    app.world_mut()
+        .get_mut::<Transform>(target)
+        .unwrap()
+        .translation
+        .x = 70.0;
+    app.update();
+    assert_eq!(camera_pos(&mut app, cam), Vec2::new(20.0, 0.0));
+}
+
+#[test]
+fn smoothing_approaches_target() {
+    let mut app = app();
+    let target = app
+        .world_mut()
+        .spawn(Transform::from_xyz(100.0, 0.0, 0.0))
+        .id();
+    let cam = app
+        .world_mut()
+        .spawn((
+            Transform::default(),
+            CameraFollow::new(target).with_smoothing(5.0),
+        ))
+        .id();
+
+    app.update();
+    let first = camera_pos(&mut app, cam).x;
+    assert!(first > 0.0 && first < 100.0);
+    for _ in 0..30 {
+        app.update();
+    }
+    assert!((camera_pos(&mut app, cam).x - 100.0).abs() < 0.5);
+}
+
+#[test]
+fn clamp_keeps_view_inside_arena() {
+    let mut app = app();
+    app.insert_resource(ArenaBounds::new(600.0, 300.0));
+    let target = app
+        .world_mut()
+        .spawn(Transform::from_xyz(1000.0, 1000.0, 0.0))
+        .id();
+    let cam = app
+        .world_mut()
+        .spawn((
+            Transform::default(),
+            CameraFollow::new(target).with_smoothing(0.0),
+            ClampToArena,
+        ))
+        .id();
+
+    app.update();
+    // fallback view is 960x540 -> half 480x270
+    assert_eq!(camera_pos(&mut app, cam), Vec2::new(120.0, 30.0));
+}
+
+#[test]
+fn damage_shakes_and_settles() {
+    let mut app = app();
+    let player = app
+        .world_mut()
+        .spawn((Health::new(100.0), ShakeOnDamage::default()))
+        .id();
+    let cam = app
+        .world_mut()
+        .spawn((Transform::default(), CameraShake::default()))
+        .id();
+
+    app.world_mut().write_message(DamageEvent {
+        target: player,
+        amount: 10.0,
+    });
+    app.update();
+    let trauma = app.world().get::<CameraShake>(cam).unwrap().trauma;
+    assert!(trauma > 0.0);
+    assert_ne!(camera_pos(&mut app, cam), Vec2::ZERO);
+
+    for _ in 0..20 {
+        app.update();
+    }
+    assert_eq!(app.world().get::<CameraShake>(cam).unwrap().trauma, 0.0);
+    assert_eq!(camera_pos(&mut app, cam), Vec2::ZERO);
+}
+
+#[test]
+fn zoom_to_fit_frames_all_targets() {
+    let mut app = app();
+    app.world_mut()
+        .spawn((CameraTarget, Transform::from_xyz(-900.0, 0.0, 0.0)));
+    app.world_mut()
+        .spawn((CameraTarget, Transform::from_xyz(900.0, 100.0, 0.0)));
+    let cam = app
+        .world_mut()
+        .spawn((
+            Transform::default(),
+            ZoomToFit {
+                padding: 30.0,
+                smoothing: 0.0,
+                ..default()
+            },
+        ))
+        .id();
+
+    app.update();
+    let rig = app.world().get::<CameraRig>(cam).unwrap();
+    // 1800 + 60 wide over a 960 view
+    assert!((rig.zoom - 1860.0 / 960.0).abs() < 1e-4);
+    assert_eq!(camera_pos(&mut app, cam), Vec2::new(0.0, 50.0));
+}
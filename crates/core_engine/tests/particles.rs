@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use bevy::time::TimeUpdateStrategy;
+use core_engine::{config::ParticleEffects, prelude::*};
+
+const EFFECTS: &str = r#"(
+    effects: {
+        "hit": (burst: 8, speed: (50.0, 50.0), lifetime: (1.0, 1.0)),
+    },
+)"#;
+
+// headless app, every update advances time by 100ms
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        CorePlugin,
+        ParticlesPlugin {
+            effects: "missing.particles.ron",
+        },
+    ))
+    .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    app.update();
+
+    let effects: ParticleEffects = ron::from_str(EFFECTS).unwrap();
+    let handle = app
+        .world_mut()
+        .resource_mut::<Assets<ParticleEffects>>()
+        .add(effects);
+    app.insert_resource(ParticleEffectsHandle(handle));
+    app
+}
+
+#[test]
+fn message_bursts_start_where_they_were_sent() {
+    let mut app = app();
+    let at = Vec2::new(120.0, -40.0);
+    app.world_mut().write_message(SpawnParticlesEvent {
+        effect: "hit".into(),
+        position: at,
+    });
+    app.update();
+
+    let mut emitters = app.world_mut().query::<&ParticleEmitter>();
+    let particles: Vec<Vec2> = emitters
+        .iter(app.world())
+        .flat_map(|e| e.particles.iter().map(|p| p.position))
+        .collect();
+    assert_eq!(particles.len(), 8);
+    assert!(particles.iter().all(|p| *p == at), "{particles:?}");
+}
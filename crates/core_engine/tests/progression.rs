@@ -0,0 +1,185 @@
+use std::time::Duration;
+
+use bevy::time::TimeUpdateStrategy;
+use core_engine::{
+    config::{LevelCurve, UpgradePool},
+    prelude::*,
+};
+
+const CURVE: &str = "(base: 5.0, growth: 2.0, max_level: Some(4), table: [3])";
+
+const UPGRADES: &str = r#"(
+    upgrades: [
+        (id: "boots", name: "Boots", modifiers: [(stat: MoveSpeed, kind: Flat, value: 10.0)]),
+        (id: "heart", name: "Heart", modifiers: [(stat: MaxHealth, kind: Flat, value: 20.0)]),
+    ],
+)"#;
+
+// headless app, every update advances time by 100ms
+fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        CorePlugin,
+        XpPlugin {
+            curve: Some("missing.xp.ron"),
+            upgrades: Some("missing.upgrades.ron"),
+            choices: 2,
+        },
+    ))
+    .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )));
+    app.update();
+
+    let curve: LevelCurve = ron::from_str(CURVE).unwrap();
+    let pool: UpgradePool = ron::from_str(UPGRADES).unwrap();
+    let curve = app
+        .world_mut()
+        .resource_mut::<Assets<LevelCurve>>()
+        .add(curve);
+    let pool = app
+        .world_mut()
+        .resource_mut::<Assets<UpgradePool>>()
+        .add(pool);
+    let mut cfg = app.world_mut().resource_mut::<LevelUpConfig>();
+    cfg.curve = Some(curve);
+    cfg.pool = Some(pool);
+    app
+}
+
+fn player(app: &mut App) -> Entity {
+    app.world_mut()
+        .spawn((
+            Experience::default(),
+            Stats::new().with(StatId::MoveSpeed, 100.0),
+        ))
+        .id()
+}
+
+fn gain(app: &mut App, entity: Entity, amount: u32) {
+    app.world_mut()
+        .write_message(XpGainEvent { entity, amount });
+    app.update();
+}
+
+fn choose(app: &mut App, entity: Entity, upgrade: &str) {
+    app.world_mut().write_message(UpgradeChosenEvent {
+        entity,
+        upgrade: upgrade.into(),
+    });
+    app.update();
+}
+
+fn level(app: &App, entity: Entity) -> (u32, u32) {
+    let exp = app.world().get::<Experience>(entity).unwrap();
+    (exp.level, exp.xp)
+}
+
+fn offers(app: &App) -> usize {
+    app.world().resource::<PendingLevelUps>().queue.len()
+}
+
+fn paused(app: &App) -> bool {
+    app.world().resource::<Time<Virtual>>().is_paused()
+}
+
+fn speed(app: &App, entity: Entity) -> f32 {
+    app.world()
+        .get::<Stats>(entity)
+        .unwrap()
+        .get_or(StatId::MoveSpeed, 0.0)
+}
+
+#[test]
+fn curve_uses_the_table_then_grows_until_the_cap() {
+    let curve: LevelCurve = ron::from_str(CURVE).unwrap();
+    assert_eq!(curve.xp_to_next(1), Some(3));
+    assert_eq!(curve.xp_to_next(2), Some(10));
+    assert_eq!(curve.xp_to_next(3), Some(20));
+    assert_eq!(curve.xp_to_next(4), None);
+}
+
+#[test]
+fn every_level_gained_is_offered_and_xp_stops_at_the_cap() {
+    let mut app = app();
+    let p = player(&mut app);
+
+    gain(&mut app, p, 2);
+    assert_eq!(level(&app, p), (1, 2));
+    assert_eq!(offers(&app), 0);
+    assert!(!paused(&app));
+
+    // 2 + 12 -> level 2 (3), level 3 (10), 1 left over
+    gain(&mut app, p, 12);
+    assert_eq!(level(&app, p), (3, 1));
+    let pending = app.world().resource::<PendingLevelUps>();
+    let levels: Vec<_> = pending.queue.iter().map(|o| o.level).collect();
+    assert_eq!(levels, vec![2, 3]);
+    assert!(pending.queue.iter().all(|o| o.choices.len() == 2));
+
+    gain(&mut app, p, 500);
+    assert_eq!(level(&app, p), (4, 0));
+    assert_eq!(app.world().get::<Experience>(p).unwrap().total, 514);
+}
+
+#[test]
+fn picking_answers_the_offer_and_resumes() {
+    let mut app = app();
+    let p = player(&mut app);
+    gain(&mut app, p, 3);
+    assert_eq!(offers(&app), 1);
+    assert!(paused(&app));
+
+    choose(&mut app, p, "boots");
+    assert_eq!(offers(&app), 0);
+    assert!(!paused(&app));
+    assert_eq!(speed(&app, p), 110.0);
+}
+
+#[test]
+fn upgrades_that_were_not_offered_are_ignored() {
+    let mut app = app();
+    let p = player(&mut app);
+    choose(&mut app, p, "boots");
+    assert_eq!(speed(&app, p), 100.0);
+
+    gain(&mut app, p, 3);
+    choose(&mut app, p, "wings");
+    let other = player(&mut app);
+    choose(&mut app, other, "boots");
+    assert_eq!(offers(&app), 1);
+    assert!(paused(&app));
+    assert_eq!(speed(&app, other), 100.0);
+}
+
+#[test]
+fn other_pauses_outlast_the_level_up() {
+    let mut app = app();
+    let p = player(&mut app);
+
+    // paused by hand before the level-up: still paused after it
+    app.world_mut().resource_mut::<Time<Virtual>>().pause();
+    gain(&mut app, p, 3);
+    choose(&mut app, p, "boots");
+    assert!(paused(&app));
+    app.world_mut().resource_mut::<Time<Virtual>>().unpause();
+
+    // a menu opened on top of the offer keeps holding the game
+    gain(&mut app, p, 10);
+    app.world_mut()
+        .resource_scope(|world, mut pause: Mut<GamePause>| {
+            let mut time = world.resource_mut::<Time<Virtual>>();
+            pause.hold(GamePause::MENU, &mut time);
+        });
+    choose(&mut app, p, "heart");
+    assert_eq!(offers(&app), 0);
+    assert!(paused(&app));
+    app.world_mut()
+        .resource_scope(|world, mut pause: Mut<GamePause>| {
+            let mut time = world.resource_mut::<Time<Virtual>>();
+            pause.release(GamePause::MENU, &mut time);
+        });
+    assert!(!paused(&app));
+}
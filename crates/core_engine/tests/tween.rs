@@ -0,0 +1,165 @@
+use std::time::Duration;
+
+use bevy::time::TimeUpdateStrategy;
+use core_engine::prelude::*;
+
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+struct Offset(f32);
+
+// headless app, every update advances time by `step_ms`
+fn app(step_ms: u64) -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, TweenPlugin))
+        .register_type::<Offset>()
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+            step_ms,
+        )));
+    app.update(); // first frame has dt = 0
+    app
+}
+
+fn x(app: &App, e: Entity) -> f32 {
+    app.world().get::<Transform>(e).unwrap().translation.x
+}
+
+fn completed(app: &App) -> Vec<Option<String>> {
+    let messages = app.world().resource::<Messages<TweenCompletedEvent>>();
+    messages
+        .iter_current_update_messages()
+        .map(|m| m.tag.clone())
+        .collect()
+}
+
+#[test]
+fn lands_exactly_on_target() {
+    let mut app = app(100);
+    let e = app
+        .world_mut()
+        .spawn((
+            Transform::default(),
+            Tween::translation(Vec3::ZERO, Vec3::X * 10.0, 0.5, EaseFunction::CubicOut)
+                .with_tag("move"),
+        ))
+        .id();
+
+    app.update();
+    let mid = x(&app, e);
+    assert!(mid > 0.0 && mid < 10.0);
+
+    for _ in 0..4 {
+        app.update();
+    }
+    assert_eq!(x(&app, e), 10.0);
+    assert_eq!(completed(&app), vec![Some("move".to_string())]);
+    assert!(app.world().get::<Tween>(e).is_none());
+}
+
+#[test]
+fn big_steps_still_finish_every_step() {
+    // one frame covers the whole sequence
+    let mut app = app(1000);
+    let step = TweenStep::lerp(
+        TweenLens::Translation {
+            from: Vec3::ZERO,
+            to: Vec3::X,
+        },
+        0.1,
+        EaseFunction::Linear,
+    )
+    .then(TweenStep::lerp(
+        TweenLens::Scale {
+            from: Vec3::ONE,
+            to: Vec3::splat(2.0),
+        },
+        0.1,
+        EaseFunction::Linear,
+    ));
+    let e = app
+        .world_mut()
+        .spawn((Transform::default(), Tween::new(step)))
+        .id();
+
+    app.update();
+    let tf = app.world().get::<Transform>(e).unwrap();
+    assert_eq!(tf.translation.x, 1.0);
+    assert_eq!(tf.scale, Vec3::splat(2.0));
+}
+
+#[test]
+fn sequence_waits_and_parallel_runs_together() {
+    let mut app = app(100);
+    let lin = |lens| TweenStep::lerp(lens, 0.2, EaseFunction::Linear);
+    let step = TweenStep::sequence([
+        TweenStep::delay(0.2),
+        TweenStep::parallel([
+            lin(TweenLens::Translation {
+                from: Vec3::ZERO,
+                to: Vec3::X * 2.0,
+            }),
+            lin(TweenLens::SpriteColor {
+                from: Color::BLACK,
+                to: Color::WHITE,
+            }),
+        ]),
+    ]);
+    let e = app
+        .world_mut()
+        .spawn((Transform::default(), Sprite::default(), Tween::new(step)))
+        .id();
+
+    app.update();
+    assert_eq!(x(&app, e), 0.0);
+    app.update();
+    app.update();
+    assert!((x(&app, e) - 1.0).abs() < 1e-4);
+    app.update();
+    assert_eq!(x(&app, e), 2.0);
+    let color = app.world().get::<Sprite>(e).unwrap().color;
+    assert_eq!(color.to_srgba(), Color::WHITE.to_srgba());
+}
+
+#[test]
+fn repeats_then_despawns() {
+    let mut app = app(100);
+    let e = app
+        .world_mut()
+        .spawn((
+            Transform::default(),
+            Tween::translation(Vec3::ZERO, Vec3::X, 0.2, EaseFunction::Linear)
+                .with_repeat(TweenRepeat::Times(3))
+                .despawn_on_complete(),
+        ))
+        .id();
+
+    for _ in 0..5 {
+        app.update();
+        assert!(completed(&app).is_empty());
+    }
+    app.update();
+    assert_eq!(completed(&app).len(), 1);
+    assert!(app.world().get_entity(e).is_err());
+}
+
+#[test]
+fn tweens_reflected_fields() {
+    let mut app = app(100);
+    let e = app
+        .world_mut()
+        .spawn((
+            Offset(0.0),
+            Tween::new(TweenStep::lerp(
+                TweenLens::field::<Offset>("0", 2.0, 6.0),
+                0.4,
+                EaseFunction::Linear,
+            )),
+        ))
+        .id();
+
+    app.update();
+    assert!((app.world().get::<Offset>(e).unwrap().0 - 3.0).abs() < 1e-4);
+    for _ in 0..3 {
+        app.update();
+    }
+    assert_eq!(app.world().get::<Offset>(e).unwrap().0, 6.0);
+}
@@ -0,0 +1,35 @@
+use bevy::prelude::*;
+
+pub mod store;
+pub mod value;
+
+pub mod prelude {
+    pub use crate::SavePlugin;
+    pub use crate::store::{Loaded, Migration, SaveData, SaveError, SaveStore, SlotInfo, unix_now};
+    pub use crate::value::SaveValue;
+}
+
+use store::SaveStore;
+
+// a SaveStore resource for the game's save slots. slots are plain files
+// under `dir`, relative to the working dir like the audio settings
+pub struct SavePlugin {
+    pub dir: &'static str,
+    // older copies kept next to each slot, newest first
+    pub backups: usize,
+}
+
+impl Default for SavePlugin {
+    fn default() -> Self {
+        Self {
+            dir: "saves",
+            backups: 3,
+        }
+    }
+}
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SaveStore::new(self.dir).with_backups(self.backups));
+    }
+}
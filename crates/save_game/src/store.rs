@@ -0,0 +1,440 @@
+use std::{
+    fmt, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bevy::prelude::*;
+use ron::value::RawValue;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::value::SaveValue;
+
+// upgrades the parsed data of a file one version. Err explains what was wrong
+pub type Migration = fn(&mut SaveValue) -> Result<(), String>;
+
+// what a game puts in a slot. bump VERSION whenever the format changes,
+// every file records the version it was written with
+pub trait SaveData: Serialize + DeserializeOwned {
+    const VERSION: u32;
+    // MIGRATIONS[n] turns a version n file into version n + 1. files from before
+    // the versioned format are version 0. steps past the end of the list are
+    // changes old files read fine without, like a new #[serde(default)] field
+    const MIGRATIONS: &'static [Migration] = &[];
+}
+
+// seconds since the unix epoch, what `saved_at` is measured in
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+// on disk every slot is
+// (
+//     version: 2,
+//     saved_at: 1760000000,
+//     data: ( ...the game's SaveData... ),
+// )
+#[derive(Serialize)]
+struct Envelope<'a, T> {
+    version: u32,
+    saved_at: u64,
+    data: &'a T,
+}
+
+#[derive(Deserialize)]
+struct RawEnvelope {
+    version: u32,
+    saved_at: u64,
+    data: Box<RawValue>,
+}
+
+#[derive(Deserialize)]
+struct Header {
+    version: u32,
+    saved_at: u64,
+}
+
+#[derive(Debug)]
+pub enum SaveError {
+    // slot names become file names, so only [A-Za-z0-9_-]
+    BadSlot(String),
+    Io(PathBuf, io::Error),
+    Parse(PathBuf, String),
+    Migration {
+        path: PathBuf,
+        from: u32,
+        reason: String,
+    },
+    // written by a newer build of the game
+    TooNew {
+        path: PathBuf,
+        version: u32,
+        supported: u32,
+    },
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::BadSlot(slot) => write!(f, "invalid save slot name {slot:?}"),
+            Self::Io(path, e) => write!(f, "{}: {e}", path.display()),
+            Self::Parse(path, e) => write!(f, "{} is damaged: {e}", path.display()),
+            Self::Migration { path, from, reason } => write!(
+                f,
+                "{} could not be upgraded from version {from}: {reason}",
+                path.display()
+            ),
+            Self::TooNew {
+                path,
+                version,
+                supported,
+            } => write!(
+                f,
+                "{} is save version {version}, this build reads up to {supported}",
+                path.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+#[derive(Debug)]
+pub struct Loaded<T> {
+    pub data: T,
+    // what the file was written as, before migrating. 0 for files from before
+    // the versioned format. saving writes it back as the current version
+    pub version: u32,
+    // None for files from before the versioned format
+    pub saved_at: Option<u64>,
+    // Some(n) when the slot itself was unreadable and backup n was used
+    pub from_backup: Option<usize>,
+    // what was wrong with the newer copies that were skipped
+    pub skipped: Vec<SaveError>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlotInfo {
+    pub slot: String,
+    pub version: u32,
+    pub saved_at: Option<u64>,
+}
+
+// named slots under one directory: `<slot>.ron`, plus `<slot>.ron.bak1` (newest)
+// to `.bakN`. writes go to a temp file that's renamed over the slot, so a crash
+// mid-save leaves the previous save intact
+#[derive(Resource, Debug, Clone)]
+pub struct SaveStore {
+    dir: PathBuf,
+    backups: usize,
+}
+
+impl SaveStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            backups: 3,
+        }
+    }
+
+    pub fn with_backups(mut self, backups: usize) -> Self {
+        self.backups = backups;
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn path(&self, slot: &str) -> Result<PathBuf, SaveError> {
+        let valid = !slot.is_empty()
+            && slot
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        if !valid {
+            return Err(SaveError::BadSlot(slot.to_string()));
+        }
+        Ok(self.dir.join(format!("{slot}.ron")))
+    }
+
+    fn sibling(path: &Path, suffix: &str) -> PathBuf {
+        let mut name = path.as_os_str().to_owned();
+        name.push(suffix);
+        PathBuf::from(name)
+    }
+
+    fn backup_path(path: &Path, n: usize) -> PathBuf {
+        Self::sibling(path, &format!(".bak{n}"))
+    }
+
+    pub fn exists(&self, slot: &str) -> bool {
+        self.path(slot).is_ok_and(|p| p.exists())
+    }
+
+    pub fn save<T: SaveData>(&self, slot: &str, data: &T) -> Result<(), SaveError> {
+        let path = self.path(slot)?;
+        let io = |e| SaveError::Io(path.clone(), e);
+        let text = ron::ser::to_string_pretty(
+            &Envelope {
+                version: T::VERSION,
+                saved_at: unix_now(),
+                data,
+            },
+            ron::ser::PrettyConfig::default(),
+        )
+        .map_err(|e| io(io::Error::other(e)))?;
+
+        fs::create_dir_all(&self.dir).map_err(io)?;
+        let tmp = Self::sibling(&path, ".tmp");
+        let mut file = fs::File::create(&tmp).map_err(io)?;
+        file.write_all(text.as_bytes()).map_err(io)?;
+        file.sync_all().map_err(io)?;
+        drop(file);
+
+        // shift bak1..bakN-1 down one, oldest falls off the end
+        if self.backups > 0 && path.exists() {
+            for n in (1..self.backups).rev() {
+                let from = Self::backup_path(&path, n);
+                if from.exists() {
+                    fs::rename(&from, Self::backup_path(&path, n + 1)).map_err(io)?;
+                }
+            }
+            // copy rather than rename so the slot never goes missing
+            fs::copy(&path, Self::backup_path(&path, 1)).map_err(io)?;
+        }
+        fs::rename(&tmp, &path).map_err(io)
+    }
+
+    // Ok(None) when the slot has never been saved. a damaged slot falls back to
+    // the newest readable backup and is kept as `<slot>.ron.broken` so the next
+    // save can't rotate it away. Err when no copy could be read, or straight away
+    // with TooNew when the slot comes from a newer build
+    pub fn load<T: SaveData>(&self, slot: &str) -> Result<Option<Loaded<T>>, SaveError> {
+        let path = self.path(slot)?;
+        let mut skipped = Vec::new();
+        let candidates = std::iter::once(path.clone())
+            .chain((1..=self.backups).map(|n| Self::backup_path(&path, n)));
+        for (n, candidate) in candidates.enumerate() {
+            match read_slot::<T>(&candidate) {
+                Ok(None) => continue,
+                Ok(Some((data, version, saved_at))) => {
+                    return Ok(Some(Loaded {
+                        data,
+                        version,
+                        saved_at,
+                        from_backup: (n > 0).then_some(n),
+                        skipped,
+                    }));
+                }
+                // not damaged, just from a newer build. swapping in an older backup
+                // would lose it, the game has to keep from saving over it instead
+                Err(e @ SaveError::TooNew { .. }) if n == 0 => return Err(e),
+                Err(e) => {
+                    warn!("skipping save {}: {e}", candidate.display());
+                    if n == 0 {
+                        let _ = fs::copy(&path, Self::sibling(&path, ".broken"));
+                    }
+                    skipped.push(e);
+                }
+            }
+        }
+        if skipped.is_empty() {
+            Ok(None)
+        } else {
+            Err(skipped.swap_remove(0))
+        }
+    }
+
+    // every slot in the directory, by name. only the header is read
+    pub fn slots(&self) -> Vec<SlotInfo> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        let mut slots: Vec<SlotInfo> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.path();
+                if path.extension()? != "ron" {
+                    return None;
+                }
+                let slot = path.file_stem()?.to_str()?.to_string();
+                let text = fs::read_to_string(&path).ok()?;
+                let (version, saved_at) = match ron::from_str::<Header>(&text) {
+                    Ok(h) => (h.version, Some(h.saved_at)),
+                    Err(_) => (0, None),
+                };
+                Some(SlotInfo {
+                    slot,
+                    version,
+                    saved_at,
+                })
+            })
+            .collect();
+        slots.sort_by(|a, b| a.slot.cmp(&b.slot));
+        slots
+    }
+
+    // the slot and all of its backups
+    pub fn delete(&self, slot: &str) -> Result<(), SaveError> {
+        let path = self.path(slot)?;
+        let files = std::iter::once(path.clone())
+            .chain((1..=self.backups).map(|n| Self::backup_path(&path, n)));
+        for file in files {
+            match fs::remove_file(&file) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => {
+                    return Err(SaveError::Io(file, e));
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+// Ok(None) if the file isn't there. files without the header are the
+// pre-versioning format and read as version 0
+fn read_slot<T: SaveData>(path: &Path) -> Result<Option<(T, u32, Option<u64>)>, SaveError> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(SaveError::Io(path.to_path_buf(), e)),
+    };
+    let parse = |e: String| SaveError::Parse(path.to_path_buf(), e);
+    let (version, saved_at, data) = match ron::from_str::<RawEnvelope>(&text) {
+        Ok(envelope) => (
+            envelope.version,
+            Some(envelope.saved_at),
+            envelope.data.get_ron().to_string(),
+        ),
+        // headerless only counts if it reads as something; otherwise report
+        // why the header didn't
+        Err(e) if SaveValue::parse(&text).is_err() => return Err(parse(e.to_string())),
+        Err(_) => (0, None, text),
+    };
+    if version > T::VERSION {
+        return Err(SaveError::TooNew {
+            path: path.to_path_buf(),
+            version,
+            supported: T::VERSION,
+        });
+    }
+    let data = migrate::<T>(path, version, data)?;
+    let data = ron::from_str::<T>(&data).map_err(|e| parse(e.to_string()))?;
+    Ok(Some((data, version, saved_at)))
+}
+
+// runs MIGRATIONS[version..] over the text, untouched when there's nothing to run
+fn migrate<T: SaveData>(path: &Path, version: u32, data: String) -> Result<String, SaveError> {
+    let steps = T::MIGRATIONS
+        .get(version as usize..T::VERSION as usize)
+        .or_else(|| T::MIGRATIONS.get(version as usize..))
+        .unwrap_or_default();
+    if steps.is_empty() {
+        return Ok(data);
+    }
+    let mut value = SaveValue::parse(&data).map_err(|e| SaveError::Parse(path.to_path_buf(), e))?;
+    for (from, step) in (version..).zip(steps) {
+        step(&mut value).map_err(|reason| SaveError::Migration {
+            path: path.to_path_buf(),
+            from,
+            reason,
+        })?;
+    }
+    Ok(value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Progress {
+        gold: u64,
+        name: String,
+    }
+
+    impl SaveData for Progress {
+        const VERSION: u32 = 2;
+        // v1 renamed coins to gold, v2 only added the header
+        const MIGRATIONS: &'static [Migration] = &[|v| {
+            v.rename_field("coins", "gold");
+            Ok(())
+        }];
+    }
+
+    fn store(test: &str) -> SaveStore {
+        let dir = std::env::temp_dir().join(format!("save_game_{test}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        SaveStore::new(dir).with_backups(2)
+    }
+
+    fn progress(gold: u64) -> Progress {
+        Progress {
+            gold,
+            name: "hero".into(),
+        }
+    }
+
+    #[test]
+    fn damaged_slot_falls_back_to_backup() {
+        let store = store("fallback");
+        assert!(store.load::<Progress>("main").unwrap().is_none());
+        for gold in 1..=4 {
+            store.save("main", &progress(gold)).unwrap();
+        }
+        let loaded = store.load::<Progress>("main").unwrap().unwrap();
+        assert_eq!((loaded.data.gold, loaded.version), (4, 2));
+        assert_eq!(loaded.from_backup, None);
+        // two backups kept, newest first
+        let path = store.path("main").unwrap();
+        assert!(!SaveStore::backup_path(&path, 3).exists());
+
+        // a crash that truncated the slot
+        let text = fs::read_to_string(&path).unwrap();
+        fs::write(&path, &text[..text.len() / 2]).unwrap();
+        let loaded = store.load::<Progress>("main").unwrap().unwrap();
+        assert_eq!(loaded.data.gold, 3);
+        assert_eq!(loaded.from_backup, Some(1));
+        assert!(matches!(loaded.skipped[..], [SaveError::Parse(..)]));
+        assert!(SaveStore::sibling(&path, ".broken").exists());
+
+        store.delete("main").unwrap();
+        assert!(store.load::<Progress>("main").unwrap().is_none());
+        assert!(matches!(store.path("../up"), Err(SaveError::BadSlot(_))));
+        fs::remove_dir_all(store.dir()).unwrap();
+    }
+
+    #[test]
+    fn headerless_and_newer_files() {
+        let store = store("versions");
+        fs::create_dir_all(store.dir()).unwrap();
+        let path = store.path("old").unwrap();
+        fs::write(&path, "(coins: 7, name: \"hero\")").unwrap();
+        let loaded = store.load::<Progress>("old").unwrap().unwrap();
+        assert_eq!(loaded.data, progress(7));
+        assert_eq!((loaded.version, loaded.saved_at), (0, None));
+
+        store.save("new", &progress(8)).unwrap();
+        store.save("new", &progress(9)).unwrap();
+        let new = store.path("new").unwrap();
+        let text = fs::read_to_string(&new).unwrap();
+        let newer = text.replace("version: 2", "version: 5");
+        fs::write(&new, &newer).unwrap();
+        // refused as is, not swapped for the older backup
+        assert!(matches!(
+            store.load::<Progress>("new"),
+            Err(SaveError::TooNew { version: 5, .. })
+        ));
+        assert_eq!(fs::read_to_string(&new).unwrap(), newer);
+        assert!(!SaveStore::sibling(&new, ".broken").exists());
+
+        let slots = store.slots();
+        assert_eq!(slots.len(), 2);
+        assert_eq!((slots[0].slot.as_str(), slots[0].version), ("new", 5));
+        assert_eq!((slots[1].slot.as_str(), slots[1].version), ("old", 0));
+        fs::remove_dir_all(store.dir()).unwrap();
+    }
+}
@@ -0,0 +1,505 @@
+use std::fmt;
+
+// a parsed RON document that keeps everything the text had: enum variant and
+// struct names, and numbers exactly as written. ron::Value drops those, so
+// migrations edit this instead and the result is re-read as the real type
+#[derive(Debug, Clone, PartialEq)]
+pub enum SaveValue {
+    Unit,
+    Bool(bool),
+    // as written, so big integers and floats survive untouched
+    Number(String),
+    Str(String),
+    Char(char),
+    // unit variants and unit structs: `Basic`, `None`
+    Ident(String),
+    Seq(Vec<SaveValue>),
+    Map(Vec<(SaveValue, SaveValue)>),
+    // `(1, 2)`, `Some(3)`, `ItemId(4)`
+    Tuple(Option<String>, Vec<SaveValue>),
+    // `(a: 1)`, `Point(x: 1, y: 2)`
+    Struct(Option<String>, Vec<(String, SaveValue)>),
+}
+
+impl SaveValue {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut p = Parser { src: text, pos: 0 };
+        p.skip_attributes();
+        let value = p.value()?;
+        p.skip_ws();
+        if p.pos < p.src.len() {
+            return Err(p.error("trailing characters"));
+        }
+        Ok(value)
+    }
+
+    pub fn ident(name: impl Into<String>) -> Self {
+        Self::Ident(name.into())
+    }
+
+    pub fn field(&self, name: &str) -> Option<&SaveValue> {
+        match self {
+            Self::Struct(_, fields) => fields.iter().find(|(k, _)| k == name).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn field_mut(&mut self, name: &str) -> Option<&mut SaveValue> {
+        match self {
+            Self::Struct(_, fields) => fields.iter_mut().find(|(k, _)| k == name).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    // adds or replaces. no-op on anything that isn't a struct
+    pub fn set_field(&mut self, name: &str, value: impl Into<SaveValue>) {
+        let value = value.into();
+        if let Some(slot) = self.field_mut(name) {
+            *slot = value;
+        } else if let Self::Struct(_, fields) = self {
+            fields.push((name.to_string(), value));
+        }
+    }
+
+    pub fn remove_field(&mut self, name: &str) -> Option<SaveValue> {
+        let Self::Struct(_, fields) = self else {
+            return None;
+        };
+        let i = fields.iter().position(|(k, _)| k == name)?;
+        Some(fields.remove(i).1)
+    }
+
+    pub fn rename_field(&mut self, from: &str, to: &str) {
+        if let Self::Struct(_, fields) = self
+            && let Some((k, _)) = fields.iter_mut().find(|(k, _)| k == from)
+        {
+            *k = to.to_string();
+        }
+    }
+
+    // elements of a list, or of a tuple
+    pub fn items_mut(&mut self) -> Option<&mut Vec<SaveValue>> {
+        match self {
+            Self::Seq(items) | Self::Tuple(_, items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Number(n) => n.replace('_', "").parse().ok(),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Str(s) | Self::Ident(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+impl From<f64> for SaveValue {
+    fn from(v: f64) -> Self {
+        // `{:?}` keeps the `.0` ron needs to read it back as a float
+        Self::Number(format!("{v:?}"))
+    }
+}
+
+impl From<u64> for SaveValue {
+    fn from(v: u64) -> Self {
+        Self::Number(v.to_string())
+    }
+}
+
+impl From<i64> for SaveValue {
+    fn from(v: i64) -> Self {
+        Self::Number(v.to_string())
+    }
+}
+
+impl From<bool> for SaveValue {
+    fn from(v: bool) -> Self {
+        Self::Bool(v)
+    }
+}
+
+impl From<&str> for SaveValue {
+    fn from(v: &str) -> Self {
+        Self::Str(v.to_string())
+    }
+}
+
+impl From<String> for SaveValue {
+    fn from(v: String) -> Self {
+        Self::Str(v)
+    }
+}
+
+// compact RON, readable by ron::from_str
+impl fmt::Display for SaveValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fn list<T>(
+            f: &mut fmt::Formatter,
+            items: &[T],
+            mut each: impl FnMut(&mut fmt::Formatter, &T) -> fmt::Result,
+        ) -> fmt::Result {
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(",")?;
+                }
+                each(f, item)?;
+            }
+            Ok(())
+        }
+
+        match self {
+            Self::Unit => f.write_str("()"),
+            Self::Bool(b) => write!(f, "{b}"),
+            Self::Number(n) | Self::Ident(n) => f.write_str(n),
+            Self::Str(s) => f.write_str(&ron::to_string(s).map_err(|_| fmt::Error)?),
+            Self::Char(c) => f.write_str(&ron::to_string(c).map_err(|_| fmt::Error)?),
+            Self::Seq(items) => {
+                f.write_str("[")?;
+                list(f, items, |f, v| write!(f, "{v}"))?;
+                f.write_str("]")
+            }
+            Self::Map(entries) => {
+                f.write_str("{")?;
+                list(f, entries, |f, (k, v)| write!(f, "{k}:{v}"))?;
+                f.write_str("}")
+            }
+            Self::Tuple(name, items) => {
+                write!(f, "{}(", name.as_deref().unwrap_or(""))?;
+                list(f, items, |f, v| write!(f, "{v}"))?;
+                f.write_str(")")
+            }
+            Self::Struct(name, fields) => {
+                write!(f, "{}(", name.as_deref().unwrap_or(""))?;
+                list(f, fields, |f, (k, v)| write!(f, "{k}:{v}"))?;
+                f.write_str(")")
+            }
+        }
+    }
+}
+
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.src[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn error(&self, what: &str) -> String {
+        let line = self.src[..self.pos].matches('\n').count() + 1;
+        format!("{what} at line {line}")
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        self.skip_ws();
+        if self.bump() == Some(c) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{c}`")))
+        }
+    }
+
+    fn skip_ws(&mut self) {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+            if trimmed.starts_with("//") {
+                self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else if trimmed.starts_with("/*") {
+                self.pos += trimmed.find("*/").map_or(trimmed.len(), |i| i + 2);
+            } else {
+                return;
+            }
+        }
+    }
+
+    // `#![enable(...)]` lines at the top
+    fn skip_attributes(&mut self) {
+        self.skip_ws();
+        while self.rest().starts_with("#!") {
+            self.pos += self.rest().find(']').map_or(self.rest().len(), |i| i + 1);
+            self.skip_ws();
+        }
+    }
+
+    fn ident(&mut self) -> Option<String> {
+        let rest = self.rest();
+        let starts = rest
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_');
+        if !starts {
+            return None;
+        }
+        let len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        self.pos += len;
+        Some(rest[..len].to_string())
+    }
+
+    fn value(&mut self) -> Result<SaveValue, String> {
+        self.skip_ws();
+        let Some(c) = self.peek() else {
+            return Err(self.error("unexpected end"));
+        };
+        match c {
+            '(' => self.parens(None),
+            '[' => {
+                self.bump();
+                let items = self.separated(']', Self::value)?;
+                Ok(SaveValue::Seq(items))
+            }
+            '{' => {
+                self.bump();
+                let entries = self.separated('}', |p| {
+                    let k = p.value()?;
+                    p.expect(':')?;
+                    Ok((k, p.value()?))
+                })?;
+                Ok(SaveValue::Map(entries))
+            }
+            '"' => self.string().map(SaveValue::Str),
+            'r' if self.rest()[1..].starts_with(['"', '#']) => {
+                self.raw_string().map(SaveValue::Str)
+            }
+            '\'' => {
+                self.bump();
+                let c = match self.bump() {
+                    Some('\\') => self.escape()?,
+                    Some(c) => c,
+                    None => return Err(self.error("unterminated char")),
+                };
+                self.expect('\'')?;
+                Ok(SaveValue::Char(c))
+            }
+            '0'..='9' | '-' | '+' | '.' => {
+                let rest = self.rest();
+                let len = rest
+                    .char_indices()
+                    .skip(1)
+                    .find(|&(i, c)| {
+                        let exponent_sign = matches!(c, '+' | '-')
+                            && rest[..i].ends_with(['e', 'E'])
+                            && !rest.starts_with("0x");
+                        !(c.is_ascii_alphanumeric() || c == '_' || c == '.' || exponent_sign)
+                    })
+                    .map_or(rest.len(), |(i, _)| i);
+                self.pos += len;
+                Ok(SaveValue::Number(rest[..len].to_string()))
+            }
+            _ => {
+                let Some(name) = self.ident() else {
+                    return Err(self.error(&format!("unexpected `{c}`")));
+                };
+                match name.as_str() {
+                    "true" => return Ok(SaveValue::Bool(true)),
+                    "false" => return Ok(SaveValue::Bool(false)),
+                    _ => {}
+                }
+                self.skip_ws();
+                if self.peek() == Some('(') {
+                    self.parens(Some(name))
+                } else {
+                    Ok(SaveValue::Ident(name))
+                }
+            }
+        }
+    }
+
+    // after `(`: a struct if it opens with `name:`, otherwise a tuple
+    fn parens(&mut self, name: Option<String>) -> Result<SaveValue, String> {
+        self.expect('(')?;
+        self.skip_ws();
+        let start = self.pos;
+        let is_struct = self.ident().is_some() && {
+            self.skip_ws();
+            self.peek() == Some(':')
+        };
+        self.pos = start;
+        if is_struct {
+            let fields = self.separated(')', |p| {
+                p.skip_ws();
+                let key = p.ident().ok_or_else(|| p.error("expected a field name"))?;
+                p.expect(':')?;
+                Ok((key, p.value()?))
+            })?;
+            return Ok(SaveValue::Struct(name, fields));
+        }
+        let items = self.separated(')', Self::value)?;
+        Ok(match (name, items.is_empty()) {
+            (None, true) => SaveValue::Unit,
+            (name, _) => SaveValue::Tuple(name, items),
+        })
+    }
+
+    // comma separated up to `close`, trailing comma allowed
+    fn separated<T>(
+        &mut self,
+        close: char,
+        mut item: impl FnMut(&mut Self) -> Result<T, String>,
+    ) -> Result<Vec<T>, String> {
+        let mut out = Vec::new();
+        loop {
+            self.skip_ws();
+            if self.peek() == Some(close) {
+                self.bump();
+                return Ok(out);
+            }
+            out.push(item(self)?);
+            self.skip_ws();
+            match self.bump() {
+                Some(',') => {}
+                Some(c) if c == close => return Ok(out),
+                _ => return Err(self.error(&format!("expected `,` or `{close}`"))),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.bump();
+        let mut out = String::new();
+        loop {
+            match self.bump() {
+                Some('"') => return Ok(out),
+                Some('\\') => out.push(self.escape()?),
+                Some(c) => out.push(c),
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+
+    // r"..." and r#"..."#
+    fn raw_string(&mut self) -> Result<String, String> {
+        self.bump();
+        let hashes = self.rest().len() - self.rest().trim_start_matches('#').len();
+        self.pos += hashes;
+        self.expect('"')?;
+        let end = format!("\"{}", "#".repeat(hashes));
+        let Some(len) = self.rest().find(&end) else {
+            return Err(self.error("unterminated string"));
+        };
+        let s = self.rest()[..len].to_string();
+        self.pos += len + end.len();
+        Ok(s)
+    }
+
+    fn escape(&mut self) -> Result<char, String> {
+        let c = match self.bump() {
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('0') => '\0',
+            Some('b') => '\u{8}',
+            Some('f') => '\u{c}',
+            Some(c @ ('"' | '\'' | '\\' | '/')) => c,
+            Some('u') => {
+                let braced = self.peek() == Some('{');
+                if braced {
+                    self.bump();
+                }
+                let rest = self.rest();
+                let len = if braced {
+                    rest.find('}').unwrap_or(rest.len())
+                } else {
+                    4.min(rest.len())
+                };
+                let code = u32::from_str_radix(&rest[..len], 16).ok();
+                self.pos += len + usize::from(braced);
+                return code
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| self.error("bad unicode escape"));
+            }
+            Some('x') => {
+                let hex = self.rest().get(..2).unwrap_or_default();
+                let code = u8::from_str_radix(hex, 16).ok();
+                self.pos += hex.len();
+                return code.map(char::from).ok_or_else(|| self.error("bad escape"));
+            }
+            _ => return Err(self.error("bad escape")),
+        };
+        Ok(c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum Kind {
+        Basic,
+        Fancy { shine: f32 },
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Id(u16);
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Doc {
+        kinds: Vec<Kind>,
+        pairs: Vec<(Id, u64)>,
+        note: Option<String>,
+        letter: char,
+        big: u64,
+        tags: std::collections::BTreeMap<String, bool>,
+    }
+
+    #[test]
+    fn round_trips_what_ron_value_loses() {
+        let doc = Doc {
+            kinds: vec![Kind::Basic, Kind::Fancy { shine: 0.5 }],
+            pairs: vec![(Id(3), 7)],
+            note: Some("say \"hi\"\n\tünïcode".into()),
+            letter: '\'',
+            big: u64::MAX,
+            tags: [("a".to_string(), true)].into(),
+        };
+        let text = ron::ser::to_string_pretty(&doc, ron::ser::PrettyConfig::default()).unwrap();
+        let value = SaveValue::parse(&format!("// comment\n{text}")).unwrap();
+        assert_eq!(
+            value.field("kinds").unwrap().to_string(),
+            "[Basic,Fancy(shine:0.5)]"
+        );
+        assert_eq!(ron::from_str::<Doc>(&value.to_string()).unwrap(), doc);
+
+        let mut value = value;
+        value.remove_field("note");
+        value.rename_field("big", "huge");
+        value.set_field("extra", 2.0);
+        assert_eq!(
+            value.field("huge").unwrap().to_string(),
+            u64::MAX.to_string()
+        );
+        assert_eq!(value.field("extra").and_then(SaveValue::as_f64), Some(2.0));
+        assert!(value.field("note").is_none());
+
+        assert_eq!(SaveValue::parse("()").unwrap(), SaveValue::Unit);
+        assert_eq!(
+            SaveValue::parse("[1e-3, -2, 0x1F]").unwrap().to_string(),
+            "[1e-3,-2,0x1F]"
+        );
+        assert!(SaveValue::parse("(a: 1").is_err());
+    }
+}
@@ -0,0 +1,260 @@
+use std::fmt::Write;
+
+use bevy::{prelude::*, reflect::ReflectPath};
+use core_engine::big_num::BigNum;
+
+type Render = Box<dyn Fn(&World, bool) -> Option<String> + Send + Sync>;
+
+// keeps a Text (or TextSpan) in sync with a resource or component, instead of a
+// bespoke update_hud system per game:
+// (label("", 28.0), TextBinding::resource::<Money>("$ {0}"))
+#[derive(Component)]
+pub struct TextBinding {
+    // Some(text) when the source changed (or `force`), None leaves the text alone
+    render: Render,
+}
+
+impl TextBinding {
+    // template fields are reflect paths into R, see TextTemplate
+    pub fn resource<R: Resource + Reflect>(template: &str) -> Self {
+        let template = TextTemplate::parse(template);
+        Self::resource_with::<R>(move |r| template.render(r.as_partial_reflect()))
+    }
+
+    // for text a template can't express, like a lookup through a method
+    pub fn resource_with<R: Resource>(f: impl Fn(&R) -> String + Send + Sync + 'static) -> Self {
+        Self {
+            render: Box::new(move |world, force| {
+                let r = world.get_resource_ref::<R>()?;
+                (force || r.is_changed()).then(|| f(&r))
+            }),
+        }
+    }
+
+    pub fn component<C: Component + Reflect>(entity: Entity, template: &str) -> Self {
+        let template = TextTemplate::parse(template);
+        Self::component_with::<C>(entity, move |c| template.render(c.as_partial_reflect()))
+    }
+
+    pub fn component_with<C: Component>(
+        entity: Entity,
+        f: impl Fn(&C) -> String + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            render: Box::new(move |world, force| {
+                let c = world.get_entity(entity).ok()?.get_ref::<C>()?;
+                (force || c.is_changed()).then(|| f(&c))
+            }),
+        }
+    }
+
+    // reads whatever it needs from the world. there's no single source to watch, so it
+    // renders every frame and the text is only written when the string differs
+    pub fn computed(f: impl Fn(&World) -> String + Send + Sync + 'static) -> Self {
+        Self {
+            render: Box::new(move |world, _| Some(f(world))),
+        }
+    }
+}
+
+// "Score: {0}  Time: {time_left:.0}". each {path} is a reflect path into the source
+// ("0", "score.player", "items[2]"), {} is the whole value and :.N sets float
+// precision. {{ and }} are literal braces
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextTemplate {
+    parts: Vec<TemplatePart>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TemplatePart {
+    Literal(String),
+    Field {
+        path: String,
+        precision: Option<usize>,
+    },
+}
+
+impl TextTemplate {
+    // an unclosed { is kept as text rather than failing
+    pub fn parse(template: &str) -> Self {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut rest = template;
+        while let Some(i) = rest.find(['{', '}']) {
+            literal.push_str(&rest[..i]);
+            let tail = &rest[i..];
+            if tail.starts_with("{{") || tail.starts_with("}}") {
+                literal.push_str(&tail[..1]);
+                rest = &tail[2..];
+                continue;
+            }
+            let Some(end) = tail.find('}').filter(|_| tail.starts_with('{')) else {
+                literal.push_str(&tail[..1]);
+                rest = &tail[1..];
+                continue;
+            };
+            if !literal.is_empty() {
+                parts.push(TemplatePart::Literal(std::mem::take(&mut literal)));
+            }
+            let field = tail[1..end].trim();
+            let (path, precision) = match field.split_once(":.") {
+                Some((path, p)) => (path, p.parse().ok()),
+                None => (field, None),
+            };
+            parts.push(TemplatePart::Field {
+                path: path.to_string(),
+                precision,
+            });
+            rest = &tail[end + 1..];
+        }
+        literal.push_str(rest);
+        if !literal.is_empty() {
+            parts.push(TemplatePart::Literal(literal));
+        }
+        Self { parts }
+    }
+
+    // fields that don't resolve show up as "?"
+    pub fn render(&self, source: &dyn PartialReflect) -> String {
+        let mut out = String::new();
+        for part in &self.parts {
+            match part {
+                TemplatePart::Literal(s) => out.push_str(s),
+                TemplatePart::Field { path, precision } => {
+                    let value = if path.is_empty() {
+                        Some(source)
+                    } else {
+                        path.as_str().reflect_element(source).ok()
+                    };
+                    match value {
+                        Some(v) => write_value(&mut out, v, *precision),
+                        None => out.push('?'),
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+fn write_value(out: &mut String, value: &dyn PartialReflect, precision: Option<usize>) {
+    macro_rules! display {
+        ($($t:ty),*) => {
+            $(if let Some(v) = value.try_downcast_ref::<$t>() {
+                let _ = write!(out, "{v}");
+                return;
+            })*
+        };
+    }
+    if let Some(v) = value.try_downcast_ref::<f32>() {
+        let _ = write_float(out, *v as f64, precision);
+        return;
+    }
+    if let Some(v) = value.try_downcast_ref::<f64>() {
+        let _ = write_float(out, *v, precision);
+        return;
+    }
+    if let Some(v) = value.try_downcast_ref::<BigNum>() {
+        let _ = match precision {
+            Some(p) => write!(out, "{v:.p$}"),
+            None => write!(out, "{v}"),
+        };
+        return;
+    }
+    display!(
+        u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, bool, String
+    );
+    let _ = write!(out, "{value:?}");
+}
+
+fn write_float(out: &mut String, v: f64, precision: Option<usize>) -> std::fmt::Result {
+    match precision {
+        Some(p) => write!(out, "{v:.p$}"),
+        None => write!(out, "{v}"),
+    }
+}
+
+// runs after gameplay so the text matches this frame's values
+pub fn update_text_bindings(world: &mut World) {
+    let mut bindings = world.query::<(Entity, Ref<TextBinding>)>();
+    let updates: Vec<(Entity, String)> = bindings
+        .iter(world)
+        .filter_map(|(entity, binding)| {
+            let text = (binding.render)(world, binding.is_added())?;
+            Some((entity, text))
+        })
+        .collect();
+
+    for (entity, text) in updates {
+        if let Some(mut t) = world.get_mut::<Text>(entity) {
+            if t.0 != text {
+                t.0 = text;
+            }
+        } else if let Some(mut t) = world.get_mut::<TextSpan>(entity)
+            && t.0 != text
+        {
+            t.0 = text;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Reflect)]
+    struct Round {
+        score: u32,
+        time_left: f32,
+        names: Vec<String>,
+    }
+
+    #[test]
+    fn template_fills_fields() {
+        let round = Round {
+            score: 12,
+            time_left: 41.75,
+            names: vec!["cat".into()],
+        };
+        let t =
+            TextTemplate::parse("Score: {score}  Time: {time_left:.0}s {names[0]} {{x}} {nope}");
+        assert_eq!(t.render(&round), "Score: 12  Time: 42s cat {x} ?");
+        assert_eq!(TextTemplate::parse("{}").render(&7u32), "7");
+        assert_eq!(
+            TextTemplate::parse("left { open").render(&7u32),
+            "left { open"
+        );
+    }
+
+    #[derive(Resource, Reflect)]
+    struct Money(f32);
+
+    #[test]
+    fn text_follows_the_resource() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(Money(10.0))
+            .add_systems(Update, update_text_bindings);
+        let text = app
+            .world_mut()
+            .spawn((Text::new(""), TextBinding::resource::<Money>("$ {0:.0}")))
+            .id();
+
+        app.update();
+        assert_eq!(app.world().get::<Text>(text).unwrap().0, "$ 10");
+
+        app.world_mut().resource_mut::<Money>().0 = 250.4;
+        app.update();
+        assert_eq!(app.world().get::<Text>(text).unwrap().0, "$ 250");
+
+        // untouched source -> the text isn't even marked changed
+        app.update();
+        assert!(
+            !app.world_mut()
+                .query::<Ref<Text>>()
+                .single(app.world())
+                .unwrap()
+                .is_changed()
+        );
+    }
+}
@@ -0,0 +1,349 @@
+use std::collections::VecDeque;
+
+use bevy::{platform::collections::HashMap, prelude::*};
+use core_engine::{
+    components::Health,
+    events::{DamageEvent, HealEvent},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum CombatTextKind {
+    Damage,
+    Heal,
+}
+
+// a floating number over `target`, rises and fades, then despawns
+#[derive(Component, Debug, Clone, Copy, Reflect)]
+#[reflect(Component)]
+#[require(Text2d)]
+pub struct CombatText {
+    pub target: Entity,
+    pub kind: CombatTextKind,
+    pub amount: f32,
+    pub age: f32,
+    // where it started rising from, world space
+    pub anchor: Vec2,
+}
+
+// insert your own to restyle the numbers
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct CombatTextStyle {
+    pub font_size: f32,
+    pub damage: Color,
+    pub heal: Color,
+    // seconds on screen, the last `fade` of them fading out
+    pub lifetime: f32,
+    pub fade: f32,
+    // world units per second
+    pub rise: f32,
+    // hits on the same target closer together than this add up into one number
+    pub merge_window: f32,
+    // oldest numbers make room when full
+    pub max_live: usize,
+    // spawn point above the target's origin
+    pub offset: Vec2,
+}
+
+impl Default for CombatTextStyle {
+    fn default() -> Self {
+        Self {
+            font_size: 18.0,
+            damage: Color::srgb(1.0, 0.85, 0.3),
+            heal: Color::srgb(0.4, 1.0, 0.5),
+            lifetime: 0.8,
+            fade: 0.3,
+            rise: 40.0,
+            merge_window: 0.25,
+            max_live: 64,
+            offset: Vec2::new(0.0, 20.0),
+        }
+    }
+}
+
+// live numbers by target, and in spawn order for eviction
+#[derive(Resource, Debug, Default)]
+pub struct CombatTextPool {
+    by_target: HashMap<(Entity, CombatTextKind), Entity>,
+    order: VecDeque<Entity>,
+}
+
+impl CombatTextPool {
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    fn forget(&mut self, entity: Entity) {
+        self.order.retain(|e| *e != entity);
+        self.by_target.retain(|_, e| *e != entity);
+    }
+}
+
+pub fn format_amount(kind: CombatTextKind, amount: f32) -> String {
+    let sign = match kind {
+        CombatTextKind::Damage => "",
+        CombatTextKind::Heal => "+",
+    };
+    if amount < 10.0 && amount.fract() > 0.05 {
+        format!("{sign}{amount:.1}")
+    } else {
+        format!("{sign}{amount:.0}")
+    }
+}
+
+// hits seen this frame, with where their target stood
+#[derive(Resource, Debug, Default)]
+pub struct CombatTextHits(Vec<(Entity, CombatTextKind, f32, Vec2)>);
+
+// runs before despawn_on_death, so the killing blow still finds its target
+pub fn capture_combat_hits(
+    style: Res<CombatTextStyle>,
+    mut hits: ResMut<CombatTextHits>,
+    mut damage: MessageReader<DamageEvent>,
+    mut heals: MessageReader<HealEvent>,
+    targets: Query<(&GlobalTransform, Option<&Health>)>,
+) {
+    let seen = damage
+        .read()
+        .map(|ev| (ev.target, CombatTextKind::Damage, ev.amount))
+        .chain(
+            heals
+                .read()
+                .map(|ev| (ev.target, CombatTextKind::Heal, ev.amount)),
+        );
+    for (target, kind, amount) in seen {
+        if amount <= 0.0 {
+            continue;
+        }
+        let Ok((transform, health)) = targets.get(target) else {
+            continue;
+        };
+        // the health pipeline ignored this one
+        if kind == CombatTextKind::Damage && health.is_some_and(|h| h.i_frames > 0.0) {
+            continue;
+        }
+        let anchor = transform.translation().truncate() + style.offset;
+        hits.0.push((target, kind, amount, anchor));
+    }
+}
+
+pub fn spawn_combat_text(
+    mut commands: Commands,
+    style: Res<CombatTextStyle>,
+    mut pool: ResMut<CombatTextPool>,
+    mut hits: ResMut<CombatTextHits>,
+    mut live: Query<(&mut CombatText, &mut Text2d)>,
+) {
+    // new numbers this frame, so same-frame hits merge too
+    let mut fresh: HashMap<(Entity, CombatTextKind), (f32, Vec2)> = HashMap::default();
+    for (target, kind, amount, anchor) in hits.0.drain(..) {
+        // fold into the number that's still fresh instead of stacking a new one
+        if let Some(&existing) = pool.by_target.get(&(target, kind))
+            && let Ok((mut text, mut label)) = live.get_mut(existing)
+            && text.age < style.merge_window
+        {
+            text.amount += amount;
+            text.age = 0.0;
+            text.anchor = anchor;
+            label.0 = format_amount(kind, text.amount);
+            continue;
+        }
+        let entry = fresh.entry((target, kind)).or_insert((0.0, anchor));
+        entry.0 += amount;
+    }
+
+    for ((target, kind), (amount, anchor)) in fresh {
+        while pool.len() >= style.max_live.max(1) {
+            let Some(oldest) = pool.order.pop_front() else {
+                break;
+            };
+            pool.forget(oldest);
+            commands.entity(oldest).try_despawn();
+        }
+
+        let color = match kind {
+            CombatTextKind::Damage => style.damage,
+            CombatTextKind::Heal => style.heal,
+        };
+        let entity = commands
+            .spawn((
+                CombatText {
+                    target,
+                    kind,
+                    amount,
+                    age: 0.0,
+                    anchor,
+                },
+                Text2d::new(format_amount(kind, amount)),
+                TextFont::from_font_size(style.font_size),
+                TextColor(color),
+                Transform::from_translation(anchor.extend(50.0)),
+            ))
+            .id();
+        pool.by_target.insert((target, kind), entity);
+        pool.order.push_back(entity);
+    }
+}
+
+// one query pass, no per-number timers or tweens
+pub fn animate_combat_text(
+    mut commands: Commands,
+    time: Res<Time>,
+    style: Res<CombatTextStyle>,
+    mut pool: ResMut<CombatTextPool>,
+    mut q: Query<(Entity, &mut CombatText, &mut Transform, &mut TextColor)>,
+) {
+    let dt = time.delta_secs();
+    for (entity, mut text, mut transform, mut color) in &mut q {
+        text.age += dt;
+        if text.age >= style.lifetime {
+            pool.forget(entity);
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let pos = text.anchor + Vec2::Y * style.rise * text.age;
+        transform.translation = pos.extend(transform.translation.z);
+        // a quick pop when spawned or merged into
+        transform.scale = Vec3::splat(1.0 + 0.4 * (1.0 - text.age / 0.15).max(0.0));
+
+        let left = style.lifetime - text.age;
+        let alpha = if style.fade > 0.0 {
+            (left / style.fade).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        color.0.set_alpha(alpha);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::CombatUiPlugin;
+    use bevy::time::TimeUpdateStrategy;
+    use core_engine::plugins::CorePlugin;
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                100,
+            )))
+            .add_message::<DamageEvent>()
+            .add_message::<HealEvent>()
+            .init_resource::<CombatTextStyle>()
+            .init_resource::<CombatTextPool>()
+            .init_resource::<CombatTextHits>()
+            .add_systems(
+                Update,
+                (capture_combat_hits, spawn_combat_text, animate_combat_text).chain(),
+            );
+        app
+    }
+
+    fn numbers(app: &mut App) -> Vec<(CombatTextKind, f32)> {
+        app.world_mut()
+            .query::<&CombatText>()
+            .iter(app.world())
+            .map(|t| (t.kind, t.amount))
+            .collect()
+    }
+
+    #[test]
+    fn rapid_hits_merge_and_expire() {
+        let mut app = app();
+        let target = app
+            .world_mut()
+            .spawn((GlobalTransform::default(), Health::new(100.0)))
+            .id();
+        app.update();
+
+        for _ in 0..3 {
+            app.world_mut().write_message(DamageEvent {
+                target,
+                amount: 5.0,
+            });
+            app.update();
+        }
+        app.world_mut().write_message(HealEvent {
+            target,
+            amount: 2.0,
+        });
+        app.update();
+
+        let mut live = numbers(&mut app);
+        live.sort_by_key(|(kind, _)| *kind == CombatTextKind::Heal);
+        assert_eq!(
+            live,
+            vec![(CombatTextKind::Damage, 15.0), (CombatTextKind::Heal, 2.0)]
+        );
+
+        for _ in 0..10 {
+            app.update();
+        }
+        assert!(numbers(&mut app).is_empty());
+        assert!(app.world().resource::<CombatTextPool>().is_empty());
+    }
+
+    #[test]
+    fn oldest_number_makes_room() {
+        let mut app = app();
+        app.insert_resource(CombatTextStyle {
+            max_live: 2,
+            ..default()
+        });
+        let targets: Vec<Entity> = (0..3)
+            .map(|_| app.world_mut().spawn(GlobalTransform::default()).id())
+            .collect();
+        app.update();
+        for target in targets {
+            app.world_mut().write_message(DamageEvent {
+                target,
+                amount: 1.0,
+            });
+        }
+        app.update();
+        assert_eq!(numbers(&mut app).len(), 2);
+    }
+
+    #[test]
+    fn killing_blow_still_shows() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, CorePlugin, CombatUiPlugin));
+        app.update();
+        let target = app
+            .world_mut()
+            .spawn((
+                Transform::from_xyz(30.0, 10.0, 0.0),
+                GlobalTransform::from_xyz(30.0, 10.0, 0.0),
+                Health::new(10.0),
+            ))
+            .id();
+        app.update();
+
+        app.world_mut().write_message(DamageEvent {
+            target,
+            amount: 25.0,
+        });
+        app.update();
+
+        assert!(app.world().get_entity(target).is_err());
+        let mut q = app.world_mut().query::<&CombatText>();
+        let texts: Vec<_> = q.iter(app.world()).collect();
+        assert_eq!(texts.len(), 1);
+        assert_eq!(texts[0].amount, 25.0);
+        assert_eq!(texts[0].anchor, Vec2::new(30.0, 30.0));
+    }
+
+    #[test]
+    fn amounts_read_cleanly() {
+        assert_eq!(format_amount(CombatTextKind::Damage, 12.4), "12");
+        assert_eq!(format_amount(CombatTextKind::Damage, 2.5), "2.5");
+        assert_eq!(format_amount(CombatTextKind::Heal, 3.0), "+3");
+    }
+}
@@ -0,0 +1,264 @@
+use std::collections::VecDeque;
+
+use bevy::{prelude::*, ui::FocusPolicy};
+
+use crate::{
+    interaction::ButtonClicked,
+    menu::MenuInput,
+    theme::{TextRole, ThemedText},
+    widgets::{Focused, button, label, panel, screen_root, themed_button, themed_label},
+};
+
+// dialogs.write(OpenDialog::confirm("reset", "RESET SAVE?", "This can't be undone."))
+// then read DialogResult for id "reset". one shows at a time, the rest queue up
+#[derive(Message, Debug, Clone, PartialEq)]
+pub struct OpenDialog {
+    pub id: String,
+    pub title: String,
+    pub body: String,
+    pub confirm: String,
+    // None for a plain alert with just the confirm button
+    pub cancel: Option<String>,
+}
+
+impl OpenDialog {
+    pub fn confirm(
+        id: impl Into<String>,
+        title: impl Into<String>,
+        body: impl Into<String>,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            title: title.into(),
+            body: body.into(),
+            confirm: "OK".into(),
+            cancel: Some("CANCEL".into()),
+        }
+    }
+
+    pub fn alert(id: impl Into<String>, title: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            cancel: None,
+            ..Self::confirm(id, title, body)
+        }
+    }
+
+    pub fn with_labels(mut self, confirm: impl Into<String>, cancel: impl Into<String>) -> Self {
+        self.confirm = confirm.into();
+        if self.cancel.is_some() {
+            self.cancel = Some(cancel.into());
+        }
+        self
+    }
+}
+
+// answer to the OpenDialog with the same id. Esc / gamepad East cancels,
+// which on an alert counts as confirming
+#[derive(Message, Debug, Clone, PartialEq, Eq)]
+pub struct DialogResult {
+    pub id: String,
+    pub confirmed: bool,
+}
+
+#[derive(Resource, Debug, Default)]
+pub struct DialogQueue {
+    pending: VecDeque<OpenDialog>,
+    open: Option<(OpenDialog, Entity)>,
+}
+
+impl DialogQueue {
+    pub fn is_open(&self) -> bool {
+        self.open.is_some()
+    }
+
+    pub fn current(&self) -> Option<&OpenDialog> {
+        self.open.as_ref().map(|(d, _)| d)
+    }
+}
+
+pub fn dialog_closed(queue: Option<Res<DialogQueue>>) -> bool {
+    queue.is_none_or(|q| !q.is_open())
+}
+
+#[derive(Component, Debug, Clone, Copy)]
+pub struct DialogButton {
+    pub confirmed: bool,
+}
+
+pub fn queue_dialogs(mut events: MessageReader<OpenDialog>, mut queue: ResMut<DialogQueue>) {
+    for ev in events.read() {
+        queue.pending.push_back(ev.clone());
+    }
+}
+
+// clicks, Enter / South on the focused button, Esc / East to cancel
+pub fn answer_dialog(
+    mut commands: Commands,
+    input: MenuInput,
+    mut queue: ResMut<DialogQueue>,
+    mut clicks: MessageReader<ButtonClicked>,
+    buttons: Query<(&DialogButton, Has<Focused>)>,
+    mut results: MessageWriter<DialogResult>,
+) {
+    let Some((dialog, root)) = queue.open.as_ref().map(|(d, r)| (d.clone(), *r)) else {
+        return;
+    };
+    let clicked = clicks
+        .read()
+        .find_map(|c| buttons.get(c.entity).ok())
+        .map(|(b, _)| b.confirmed);
+    let keyed = if input.key(&[KeyCode::Enter, KeyCode::Space]) || input.pad(GamepadButton::South) {
+        buttons.iter().find(|(_, f)| *f).map(|(b, _)| b.confirmed)
+    } else if input.key(&[KeyCode::Escape]) || input.pad(GamepadButton::East) {
+        Some(dialog.cancel.is_none())
+    } else {
+        None
+    };
+    let Some(confirmed) = clicked.or(keyed) else {
+        return;
+    };
+    results.write(DialogResult {
+        id: dialog.id.clone(),
+        confirmed,
+    });
+    commands.entity(root).despawn();
+    queue.open = None;
+}
+
+// left/right (or Tab) moves focus between the two buttons
+pub fn focus_dialog_buttons(
+    mut commands: Commands,
+    input: MenuInput,
+    queue: Res<DialogQueue>,
+    buttons: Query<(Entity, Has<Focused>), With<DialogButton>>,
+) {
+    // an alert's single button keeps focus
+    if !queue.is_open() || buttons.iter().len() < 2 {
+        return;
+    }
+    let step = input.key(&[KeyCode::ArrowLeft, KeyCode::ArrowRight, KeyCode::Tab])
+        || input.pad(GamepadButton::DPadLeft)
+        || input.pad(GamepadButton::DPadRight);
+    if !step {
+        return;
+    }
+    for (entity, focused) in &buttons {
+        if focused {
+            commands.entity(entity).remove::<Focused>();
+        } else {
+            commands.entity(entity).insert(Focused);
+        }
+    }
+}
+
+pub fn show_next_dialog(mut commands: Commands, mut queue: ResMut<DialogQueue>) {
+    if queue.is_open() {
+        return;
+    }
+    let Some(dialog) = queue.pending.pop_front() else {
+        return;
+    };
+    // full-screen backdrop that swallows clicks to whatever is behind it
+    let root = commands
+        .spawn((
+            screen_root(),
+            BackgroundColor(Color::BLACK.with_alpha(0.6)),
+            FocusPolicy::Block,
+            GlobalZIndex(250),
+        ))
+        .id();
+    let body = commands.spawn(panel()).id();
+    commands.entity(root).add_child(body);
+    let title = commands
+        .spawn(themed_label(&dialog.title, TextRole::Heading))
+        .id();
+    let text = commands
+        .spawn((
+            label(&dialog.body, TextRole::Body.default_size()),
+            ThemedText::new(TextRole::Body).colored("text_muted"),
+            Node {
+                max_width: Val::Px(560.0),
+                ..default()
+            },
+        ))
+        .id();
+    let row = commands
+        .spawn(Node {
+            flex_direction: FlexDirection::Row,
+            column_gap: Val::Px(16.0),
+            margin: UiRect::top(Val::Px(8.0)),
+            ..default()
+        })
+        .id();
+    commands.entity(body).add_children(&[title, text, row]);
+
+    // confirm starts focused so Enter accepts
+    let confirm = commands
+        .spawn((
+            button(&dialog.confirm),
+            DialogButton { confirmed: true },
+            Focused,
+        ))
+        .id();
+    commands.entity(row).add_child(confirm);
+    if let Some(cancel) = &dialog.cancel {
+        let cancel = commands
+            .spawn((
+                themed_button(cancel, "danger"),
+                DialogButton { confirmed: false },
+            ))
+            .id();
+        commands.entity(row).add_child(cancel);
+    }
+    queue.open = Some((dialog, root));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UiCommonPlugin;
+
+    #[test]
+    fn dialogs_answer_in_order() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, UiCommonPlugin));
+        app.world_mut()
+            .write_message(OpenDialog::confirm("first", "SURE?", ""));
+        app.world_mut()
+            .write_message(OpenDialog::alert("second", "DONE", ""));
+        app.update();
+        assert_eq!(
+            app.world().resource::<DialogQueue>().current().unwrap().id,
+            "first"
+        );
+
+        let cancel = app
+            .world_mut()
+            .query::<(Entity, &DialogButton)>()
+            .iter(app.world())
+            .find(|(_, b)| !b.confirmed)
+            .map(|(e, _)| e)
+            .unwrap();
+        *app.world_mut().get_mut::<Interaction>(cancel).unwrap() = Interaction::Pressed;
+        app.update();
+
+        let results: Vec<_> = app
+            .world()
+            .resource::<Messages<DialogResult>>()
+            .iter_current_update_messages()
+            .cloned()
+            .collect();
+        assert_eq!(
+            results,
+            vec![DialogResult {
+                id: "first".into(),
+                confirmed: false,
+            }]
+        );
+        // the next one took its place
+        assert_eq!(
+            app.world().resource::<DialogQueue>().current().unwrap().id,
+            "second"
+        );
+    }
+}
@@ -0,0 +1,124 @@
+use bevy::prelude::*;
+use core_engine::components::{Health, Shield};
+
+// draws a bar over this entity's Health, plus its Shield if it has one
+#[derive(Component, Debug, Clone, Copy, Reflect)]
+#[reflect(Component)]
+pub struct HealthBar {
+    // from the entity's origin to the bar's center, world units
+    pub offset: Vec2,
+    pub width: f32,
+    // keep showing at full health, e.g. for bosses
+    pub always_show: bool,
+}
+
+impl Default for HealthBar {
+    fn default() -> Self {
+        Self {
+            offset: Vec2::new(0.0, 24.0),
+            width: 32.0,
+            always_show: false,
+        }
+    }
+}
+
+impl HealthBar {
+    pub fn above(height: f32) -> Self {
+        Self {
+            offset: Vec2::new(0.0, height),
+            ..default()
+        }
+    }
+
+    pub fn with_width(mut self, width: f32) -> Self {
+        self.width = width;
+        self
+    }
+
+    pub fn always_shown(mut self) -> Self {
+        self.always_show = true;
+        self
+    }
+}
+
+// shared by every bar, insert your own to restyle them
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct HealthBarStyle {
+    // screen px, bars stay readable at any zoom
+    pub thickness: f32,
+    pub background: Color,
+    pub full: Color,
+    pub half: Color,
+    pub low: Color,
+    pub shield: Color,
+}
+
+impl Default for HealthBarStyle {
+    fn default() -> Self {
+        Self {
+            thickness: 5.0,
+            background: Color::srgba(0.0, 0.0, 0.0, 0.6),
+            full: Color::srgb(0.30, 0.85, 0.35),
+            half: Color::srgb(0.95, 0.80, 0.20),
+            low: Color::srgb(0.90, 0.20, 0.15),
+            shield: Color::srgba(0.45, 0.75, 1.0, 0.8),
+        }
+    }
+}
+
+impl HealthBarStyle {
+    // full -> half -> low as the ratio drops
+    pub fn fill_color(&self, ratio: f32) -> Color {
+        let r = ratio.clamp(0.0, 1.0);
+        if r >= 0.5 {
+            self.half.mix(&self.full, (r - 0.5) * 2.0)
+        } else {
+            self.low.mix(&self.half, r * 2.0)
+        }
+    }
+}
+
+// own gizmo group so the bar thickness doesn't leak into other debug drawing
+#[derive(Default, Reflect, GizmoConfigGroup)]
+pub struct HealthBarGizmos;
+
+pub fn sync_health_bar_gizmos(style: Res<HealthBarStyle>, mut store: ResMut<GizmoConfigStore>) {
+    if style.is_changed() {
+        let (config, _) = store.config_mut::<HealthBarGizmos>();
+        config.line.width = style.thickness;
+        config.depth_bias = -1.0;
+    }
+}
+
+// one line per layer per bar, no entities or meshes, so hundreds of enemies stay cheap
+pub fn draw_health_bars(
+    mut gizmos: Gizmos<HealthBarGizmos>,
+    style: Res<HealthBarStyle>,
+    q: Query<(&GlobalTransform, &Health, Option<&Shield>, &HealthBar)>,
+) {
+    for (transform, health, shield, bar) in &q {
+        let ratio = health.ratio();
+        let shield_ratio = shield.map(Shield::ratio);
+        let hurt = ratio < 1.0 || shield_ratio.is_some_and(|s| s < 1.0);
+        if health.is_dead() || !(hurt || bar.always_show) {
+            continue;
+        }
+
+        let center = transform.translation().truncate() + bar.offset;
+        let left = center - Vec2::X * bar.width * 0.5;
+        let right = center + Vec2::X * bar.width * 0.5;
+        gizmos.line_2d(left, right, style.background);
+        if ratio > 0.0 {
+            let end = left + Vec2::X * bar.width * ratio;
+            gizmos.line_2d(left, end, style.fill_color(ratio));
+        }
+        if let Some(s) = shield_ratio.filter(|s| *s > 0.0) {
+            gizmos.line_2d(left, left + Vec2::X * bar.width * s, style.shield);
+        }
+    }
+}
+
+// gizmos need the render side, headless apps and tests skip drawing
+pub fn can_draw_health_bars(store: Option<Res<GizmoConfigStore>>) -> bool {
+    store.is_some()
+}
@@ -0,0 +1,117 @@
+use bevy::prelude::*;
+
+use crate::{
+    style::ButtonStyle,
+    widgets::{ButtonText, Disabled, Focused, Selected, UiButton},
+};
+
+// a UiButton was pressed. match `entity` against your own marker components
+#[derive(Message, Debug, Clone, Copy)]
+pub struct ButtonClicked {
+    pub entity: Entity,
+}
+
+type ClickableChanged = (Changed<Interaction>, With<UiButton>, Without<Disabled>);
+
+pub fn emit_button_clicks(
+    q: Query<(Entity, &Interaction), ClickableChanged>,
+    mut writer: MessageWriter<ButtonClicked>,
+) {
+    for (entity, interaction) in q.iter() {
+        if *interaction == Interaction::Pressed {
+            writer.write(ButtonClicked { entity });
+        }
+    }
+}
+
+type ButtonVisuals<'a> = (
+    Entity,
+    Ref<'a, Interaction>,
+    Ref<'a, ButtonStyle>,
+    Option<Ref<'a, Disabled>>,
+    Option<Ref<'a, Focused>>,
+    Option<Ref<'a, Selected>>,
+    &'a mut BackgroundColor,
+    Option<&'a Children>,
+);
+
+// background / text colors from the ButtonStyle and the button's state,
+// only touched when something about the button changed
+pub fn update_button_visuals(
+    mut removed: RemovedComponents<Disabled>,
+    mut unfocused: RemovedComponents<Focused>,
+    mut unselected: RemovedComponents<Selected>,
+    mut buttons: Query<ButtonVisuals, With<UiButton>>,
+    mut texts: Query<&mut TextColor, With<ButtonText>>,
+) {
+    let lost_state: Vec<Entity> = removed
+        .read()
+        .chain(unfocused.read())
+        .chain(unselected.read())
+        .collect();
+    for (entity, interaction, style, disabled, focused, selected, mut bg, children) in
+        buttons.iter_mut()
+    {
+        let dirty = interaction.is_changed()
+            || style.is_changed()
+            || disabled.as_ref().is_some_and(|d| d.is_added())
+            || focused.as_ref().is_some_and(|f| f.is_added())
+            || selected.as_ref().is_some_and(|s| s.is_added())
+            || lost_state.contains(&entity);
+        if !dirty {
+            continue;
+        }
+
+        let disabled = disabled.is_some();
+        bg.0 = match (disabled, *interaction) {
+            (true, _) => style.disabled,
+            (false, Interaction::Pressed) => style.pressed,
+            (false, Interaction::Hovered) => style.hovered,
+            (false, Interaction::None) if focused.is_some() => style.hovered,
+            (false, Interaction::None) if selected.is_some() => style.pressed,
+            (false, Interaction::None) => style.normal,
+        };
+        let text = if disabled {
+            style.text_disabled
+        } else {
+            style.text
+        };
+        for child in children.into_iter().flatten() {
+            if let Ok(mut color) = texts.get_mut(*child) {
+                color.0 = text;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UiCommonPlugin;
+
+    #[test]
+    fn clicks_skip_disabled_buttons() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, UiCommonPlugin));
+        let enabled = app.world_mut().spawn(UiButton).id();
+        let disabled = app.world_mut().spawn((UiButton, Disabled)).id();
+
+        for e in [enabled, disabled] {
+            *app.world_mut().get_mut::<Interaction>(e).unwrap() = Interaction::Pressed;
+        }
+        app.update();
+
+        let clicked: Vec<_> = app
+            .world()
+            .resource::<Messages<ButtonClicked>>()
+            .iter_current_update_messages()
+            .map(|c| c.entity)
+            .collect();
+        assert_eq!(clicked, vec![enabled]);
+
+        let style = ButtonStyle::default();
+        let bg = |e| app.world().get::<BackgroundColor>(e).unwrap().0;
+        assert_eq!(bg(enabled), style.pressed);
+        assert_eq!(bg(disabled), style.disabled);
+    }
+}
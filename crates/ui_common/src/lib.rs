@@ -1,14 +1,216 @@
-pub fn add(left: u64, right: u64) -> u64 {
-    left + right
+use bevy::prelude::*;
+
+pub mod bindings;
+pub mod combat_text;
+pub mod dialog;
+pub mod health_bars;
+pub mod interaction;
+pub mod list;
+pub mod menu;
+pub mod menu_screens;
+pub mod style;
+pub mod theme;
+pub mod toast;
+pub mod tooltip;
+pub mod widgets;
+
+pub mod prelude {
+    pub use crate::bindings::{TextBinding, TextTemplate};
+    pub use crate::combat_text::{CombatText, CombatTextKind, CombatTextStyle};
+    pub use crate::dialog::{DialogResult, OpenDialog, dialog_closed};
+    pub use crate::health_bars::{HealthBar, HealthBarStyle};
+    pub use crate::interaction::ButtonClicked;
+    pub use crate::list::{
+        ListItem, ListItems, ListSelected, ListSource, ListView, SortValue, scroll_list,
+    };
+    pub use crate::menu::{
+        CustomScreen, GameOverSummary, MenuAction, MenuActionEvent, MenuButton, MenuConfig,
+        MenuScreen, MenuStack, menu_closed,
+    };
+    pub use crate::style::{ButtonStyle, PanelStyle};
+    pub use crate::theme::{
+        TextRole, ThemedButton, ThemedPanel, ThemedText, UiTheme, UiThemePlugin, UiThemes,
+    };
+    pub use crate::toast::{ToastEvent, ToastKind, ToastQueue};
+    pub use crate::tooltip::Tooltip;
+    pub use crate::widgets::*;
+    pub use crate::{CombatUiPlugin, MenuPlugin, UiCommonPlugin};
+}
+
+use bindings::update_text_bindings;
+use combat_text::*;
+use core_engine::{components::GamePause, plugins::CoreSet, systems::despawn_on_death};
+use dialog::*;
+use health_bars::*;
+use interaction::*;
+use list::*;
+use menu::*;
+use toast::*;
+use tooltip::*;
+
+// button visuals + ButtonClicked messages for every UiButton, TextBinding updates,
+// toasts, tooltips, modal dialogs and scroll lists
+pub struct UiCommonPlugin;
+
+impl Plugin for UiCommonPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<ButtonClicked>()
+            .add_message::<ToastEvent>()
+            .add_message::<OpenDialog>()
+            .add_message::<DialogResult>()
+            .add_message::<ListSelected>()
+            .init_resource::<ToastQueue>()
+            .init_resource::<TooltipHover>()
+            .init_resource::<DialogQueue>()
+            .add_systems(Update, (emit_button_clicks, update_button_visuals))
+            .add_systems(
+                Update,
+                (
+                    (queue_toasts, show_toasts, expire_toasts).chain(),
+                    update_tooltips,
+                    (
+                        answer_dialog,
+                        focus_dialog_buttons,
+                        queue_dialogs,
+                        show_next_dialog,
+                    )
+                        .chain()
+                        .after(emit_button_clicks),
+                    (
+                        refresh_list_sources,
+                        sort_and_filter_lists,
+                        scroll_lists,
+                        select_list_rows,
+                        relayout_new_pools,
+                        layout_list_rows,
+                    )
+                        .chain()
+                        .after(emit_button_clicks),
+                ),
+            )
+            .add_systems(
+                PostUpdate,
+                update_text_bindings.before(bevy::ui::UiSystems::Prepare),
+            );
+    }
+}
+
+// health bars over every HealthBar entity and floating numbers for DamageEvent / HealEvent.
+// needs CorePlugin for the messages
+pub struct CombatUiPlugin;
+
+impl Plugin for CombatUiPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<HealthBar>()
+            .register_type::<CombatText>()
+            .init_resource::<HealthBarStyle>()
+            .init_resource::<CombatTextStyle>()
+            .init_resource::<CombatTextPool>()
+            .init_resource::<CombatTextHits>()
+            .add_systems(
+                Update,
+                capture_combat_hits
+                    .in_set(CoreSet::Post)
+                    .before(despawn_on_death),
+            )
+            .add_systems(
+                PostUpdate,
+                (spawn_combat_text, animate_combat_text)
+                    .chain()
+                    .before(TransformSystems::Propagate),
+            )
+            .add_systems(
+                PostUpdate,
+                (sync_health_bar_gizmos, draw_health_bars)
+                    .chain()
+                    .after(TransformSystems::Propagate)
+                    .run_if(can_draw_health_bars),
+            );
+        if app.is_plugin_added::<bevy::gizmos::GizmoPlugin>() {
+            app.init_gizmo_group::<HealthBarGizmos>();
+        }
+    }
+}
+
+// stack of title / pause / game-over / settings / custom screens, driven by mouse,
+// keyboard or gamepad. MenuPlugin::new("PIXEL DODGE").with_button(MenuScreen::Title, ...)
+pub struct MenuPlugin {
+    pub config: MenuConfig,
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+impl MenuPlugin {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            config: MenuConfig {
+                title: title.into(),
+                ..default()
+            },
+        }
+    }
+
+    pub fn with_subtitle(mut self, subtitle: impl Into<String>) -> Self {
+        self.config.subtitle = Some(subtitle.into());
+        self
+    }
+
+    // "ROUND OVER", "YOU DIED", ...
+    pub fn with_game_over_title(mut self, title: impl Into<String>) -> Self {
+        self.config.game_over_title = title.into();
+        self
+    }
+
+    // skip the title screen, straight into gameplay
+    pub fn start_in_game(mut self) -> Self {
+        self.config.start_on_title = false;
+        self
+    }
+
+    pub fn with_button(
+        mut self,
+        screen: MenuScreen,
+        text: impl Into<String>,
+        action: MenuAction,
+    ) -> Self {
+        self.config
+            .extra_buttons
+            .entry(screen)
+            .or_default()
+            .push((text.into(), action));
+        self
+    }
+
+    pub fn with_screen(mut self, name: impl Into<String>, screen: CustomScreen) -> Self {
+        self.config.custom.insert(name.into(), screen);
+        self
+    }
+}
 
-    #[test]
-    fn it_works() {
-        let result = add(2, 2);
-        assert_eq!(result, 4);
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<UiCommonPlugin>() {
+            app.add_plugins(UiCommonPlugin);
+        }
+        app.insert_resource(self.config.clone())
+            .init_resource::<MenuStack>()
+            .init_resource::<MenuFocus>()
+            .init_resource::<GameOverSummary>()
+            .init_resource::<GamePause>()
+            .add_message::<MenuActionEvent>()
+            .add_systems(Startup, open_title_on_start)
+            .add_systems(
+                Update,
+                (
+                    toggle_pause_menu.run_if(dialog_closed),
+                    navigate_menu_focus.run_if(dialog_closed),
+                    run_menu_actions,
+                    adjust_settings,
+                    rebuild_menu_screens,
+                    show_menu_focus,
+                )
+                    .chain()
+                    .after(emit_button_clicks)
+                    // Esc that closes a dialog shouldn't also open the pause menu
+                    .before(answer_dialog),
+            );
     }
 }
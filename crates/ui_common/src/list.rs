@@ -0,0 +1,616 @@
+use std::cmp::Ordering;
+
+use bevy::{input::mouse::AccumulatedMouseScroll, prelude::*, ui::RelativeCursorPosition};
+use core_engine::big_num::BigNum;
+
+use crate::{
+    interaction::ButtonClicked,
+    style::ButtonStyle,
+    theme::ActiveTheme,
+    widgets::{ButtonText, Selected, UiButton},
+};
+
+type Source = Box<dyn Fn(&World, bool) -> Option<Vec<ListItem>> + Send + Sync>;
+
+// one row (or grid cell). `key` identifies it across refreshes, so the selection
+// survives re-sorting and items coming and going
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListItem {
+    pub key: u64,
+    pub label: String,
+    // right-aligned second column, e.g. a count or a price
+    pub detail: String,
+    // named values for ListView::sorted_by, "label" is always there
+    pub sort_keys: Vec<(&'static str, SortValue)>,
+}
+
+impl ListItem {
+    pub fn new(key: u64, label: impl Into<String>) -> Self {
+        Self {
+            key,
+            label: label.into(),
+            detail: String::new(),
+            sort_keys: Vec::new(),
+        }
+    }
+
+    pub fn detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = detail.into();
+        self
+    }
+
+    pub fn sort_key(mut self, name: &'static str, value: impl Into<SortValue>) -> Self {
+        self.sort_keys.push((name, value.into()));
+        self
+    }
+
+    fn sort_value(&self, name: &str) -> Option<SortValue> {
+        if name == "label" {
+            return Some(SortValue::Text(self.label.to_lowercase()));
+        }
+        self.sort_keys
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, v)| v.clone())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SortValue {
+    Num(BigNum),
+    Text(String),
+}
+
+impl From<BigNum> for SortValue {
+    fn from(v: BigNum) -> Self {
+        Self::Num(v)
+    }
+}
+
+impl From<f64> for SortValue {
+    fn from(v: f64) -> Self {
+        Self::Num(v.into())
+    }
+}
+
+impl From<u64> for SortValue {
+    fn from(v: u64) -> Self {
+        Self::Num(v.into())
+    }
+}
+
+impl From<u32> for SortValue {
+    fn from(v: u32) -> Self {
+        Self::Num(v.into())
+    }
+}
+
+impl From<&str> for SortValue {
+    fn from(v: &str) -> Self {
+        Self::Text(v.to_lowercase())
+    }
+}
+
+impl From<String> for SortValue {
+    fn from(v: String) -> Self {
+        Self::Text(v.to_lowercase())
+    }
+}
+
+// numbers before text, missing keys last
+fn compare(a: Option<SortValue>, b: Option<SortValue>) -> Ordering {
+    match (a, b) {
+        (Some(SortValue::Num(a)), Some(SortValue::Num(b))) => {
+            a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+        }
+        (Some(SortValue::Text(a)), Some(SortValue::Text(b))) => a.cmp(&b),
+        (Some(SortValue::Num(_)), Some(SortValue::Text(_))) => Ordering::Less,
+        (Some(SortValue::Text(_)), Some(SortValue::Num(_))) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+// where a list's rows come from, refreshed like a TextBinding
+#[derive(Component)]
+pub struct ListSource {
+    // Some(items) when the source changed (or `force`)
+    render: Source,
+}
+
+impl ListSource {
+    pub fn resource_with<R: Resource>(
+        f: impl Fn(&R) -> Vec<ListItem> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            render: Box::new(move |world, force| {
+                let r = world.get_resource_ref::<R>()?;
+                (force || r.is_changed()).then(|| f(&r))
+            }),
+        }
+    }
+
+    // rebuilt from the whole world, but only when R changed. an inventory that needs
+    // the item registry for names: ListSource::when_changed::<Inventory>(inventory_rows)
+    pub fn when_changed<R: Resource>(
+        f: impl Fn(&World) -> Vec<ListItem> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            render: Box::new(move |world, force| {
+                let r = world.get_resource_ref::<R>()?;
+                (force || r.is_changed()).then(|| f(world))
+            }),
+        }
+    }
+
+    // every frame, for lists over entities. rows are only touched when the items differ
+    pub fn computed(f: impl Fn(&World) -> Vec<ListItem> + Send + Sync + 'static) -> Self {
+        Self {
+            render: Box::new(move |world, _| Some(f(world))),
+        }
+    }
+}
+
+// the current items, and the filtered + sorted order they're shown in
+#[derive(Component, Debug, Clone, Default)]
+pub struct ListItems {
+    pub items: Vec<ListItem>,
+    // indices into `items`
+    pub view: Vec<usize>,
+    // sort + filter `view` was built with, so scrolling doesn't re-sort
+    applied: Option<(Option<ListSort>, String)>,
+}
+
+impl ListItems {
+    pub fn visible(&self) -> impl Iterator<Item = &ListItem> {
+        self.view.iter().map(|i| &self.items[*i])
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListSort {
+    pub key: &'static str,
+    pub descending: bool,
+}
+
+// a scrolling list (columns == 1) or grid. only the rows in view have entities,
+// so thousands of items cost a handful of nodes
+#[derive(Component, Debug, Clone, PartialEq)]
+#[require(Node, RelativeCursorPosition, ListItems)]
+pub struct ListView {
+    pub cell: Vec2,
+    pub columns: usize,
+    // visible height, rows past it are scrolled to
+    pub height: f32,
+    // px from the top
+    pub scroll: f32,
+    pub sort: Option<ListSort>,
+    // case-insensitive, matched against the label and detail
+    pub filter: String,
+    pub selected: Option<u64>,
+}
+
+impl ListView {
+    pub fn list(row_height: f32, width: f32, height: f32) -> Self {
+        Self {
+            cell: Vec2::new(width, row_height),
+            columns: 1,
+            height,
+            scroll: 0.0,
+            sort: None,
+            filter: String::new(),
+            selected: None,
+        }
+    }
+
+    pub fn grid(cell: Vec2, columns: usize, height: f32) -> Self {
+        Self {
+            cell,
+            columns: columns.max(1),
+            ..Self::list(cell.y, cell.x, height)
+        }
+    }
+
+    pub fn sorted_by(mut self, key: &'static str, descending: bool) -> Self {
+        self.sort = Some(ListSort { key, descending });
+        self
+    }
+
+    pub fn filtered(mut self, filter: impl Into<String>) -> Self {
+        self.filter = filter.into();
+        self
+    }
+
+    // one grid line of `columns` cells
+    fn lines(&self, count: usize) -> usize {
+        count.div_ceil(self.columns.max(1))
+    }
+
+    fn max_scroll(&self, count: usize) -> f32 {
+        (self.lines(count) as f32 * self.cell.y - self.height).max(0.0)
+    }
+
+    // the pool: every line that can be partly on screen at once
+    fn pool_size(&self) -> usize {
+        ((self.height / self.cell.y).ceil() as usize + 1) * self.columns.max(1)
+    }
+}
+
+// a pooled row entity, showing view[first visible + slot] when there is one
+#[derive(Component, Debug, Clone, Copy)]
+pub struct ListRow {
+    pub list: Entity,
+    pub slot: usize,
+    pub key: Option<u64>,
+}
+
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct ListRowDetail;
+
+// a row was clicked and is now the selection
+#[derive(Message, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ListSelected {
+    pub list: Entity,
+    pub key: u64,
+}
+
+// (scroll_list(ListView::list(36.0, 360.0, 240.0)), ListSource::computed(...))
+pub fn scroll_list(view: ListView) -> impl Bundle {
+    (
+        Node {
+            width: Val::Px(view.cell.x * view.columns as f32 + 12.0),
+            height: Val::Px(view.height),
+            overflow: Overflow::clip(),
+            ..default()
+        },
+        BackgroundColor(Color::BLACK.with_alpha(0.25)),
+        BorderRadius::all(Val::Px(4.0)),
+        view,
+    )
+}
+
+// runs before the row layout so new items show the same frame
+pub fn refresh_list_sources(world: &mut World) {
+    let mut sources = world.query::<(Entity, Ref<ListSource>)>();
+    let updates: Vec<(Entity, Vec<ListItem>)> = sources
+        .iter(world)
+        .filter_map(|(entity, source)| Some((entity, (source.render)(world, source.is_added())?)))
+        .collect();
+
+    for (entity, items) in updates {
+        if let Some(mut list) = world.get_mut::<ListItems>(entity)
+            && list.items != items
+        {
+            list.items = items;
+        }
+    }
+}
+
+// filter + sort into `view` when the items or the view settings changed
+pub fn sort_and_filter_lists(mut lists: Query<(&ListView, &mut ListItems)>) {
+    for (view, mut items) in &mut lists {
+        let settings = (view.sort.clone(), view.filter.clone());
+        if !items.is_changed() && items.applied.as_ref() == Some(&settings) {
+            continue;
+        }
+        let filter = view.filter.to_lowercase();
+        let items = items.bypass_change_detection();
+        let mut order: Vec<usize> = (0..items.items.len())
+            .filter(|i| {
+                let item = &items.items[*i];
+                filter.is_empty()
+                    || item.label.to_lowercase().contains(&filter)
+                    || item.detail.to_lowercase().contains(&filter)
+            })
+            .collect();
+        if let Some(sort) = &view.sort {
+            // stable, so equal keys keep the source order
+            order.sort_by(|a, b| {
+                let ord = compare(
+                    items.items[*a].sort_value(sort.key),
+                    items.items[*b].sort_value(sort.key),
+                );
+                if sort.descending { ord.reverse() } else { ord }
+            });
+        }
+        items.view = order;
+        items.applied = Some(settings);
+    }
+}
+
+// mouse wheel over the list
+pub fn scroll_lists(
+    scroll: Option<Res<AccumulatedMouseScroll>>,
+    mut lists: Query<(&mut ListView, &ListItems, &RelativeCursorPosition)>,
+) {
+    let delta = scroll.map_or(0.0, |s| match s.unit {
+        bevy::input::mouse::MouseScrollUnit::Line => s.delta.y * 32.0,
+        bevy::input::mouse::MouseScrollUnit::Pixel => s.delta.y,
+    });
+    for (mut view, items, cursor) in &mut lists {
+        let max = view.max_scroll(items.view.len());
+        let target = if cursor.cursor_over() && delta != 0.0 {
+            view.scroll - delta
+        } else {
+            view.scroll
+        };
+        // the list may have shrunk under the current scroll
+        let clamped = target.clamp(0.0, max);
+        if clamped != view.scroll {
+            view.scroll = clamped;
+        }
+    }
+}
+
+// keeps the selected key valid and tells the game about clicks
+pub fn select_list_rows(
+    mut clicks: MessageReader<ButtonClicked>,
+    rows: Query<&ListRow>,
+    mut lists: Query<&mut ListView>,
+    mut selected: MessageWriter<ListSelected>,
+) {
+    for row in clicks.read().filter_map(|c| rows.get(c.entity).ok()) {
+        let (Some(key), Ok(mut view)) = (row.key, lists.get_mut(row.list)) else {
+            continue;
+        };
+        view.selected = Some(key);
+        selected.write(ListSelected {
+            list: row.list,
+            key,
+        });
+    }
+}
+
+type Lists<'a> = (
+    Entity,
+    Ref<'a, ListView>,
+    Ref<'a, ListItems>,
+    Option<&'a Children>,
+);
+
+type PooledRows<'a> = (
+    &'a mut ListRow,
+    &'a mut Node,
+    &'a mut Visibility,
+    Has<Selected>,
+    &'a Children,
+);
+
+// spawns the pool and points each pooled row at the item under it
+pub fn layout_list_rows(
+    mut commands: Commands,
+    lists: Query<Lists>,
+    mut rows: Query<PooledRows>,
+    mut texts: Query<(&mut Text, Has<ListRowDetail>)>,
+    theme: ActiveTheme,
+) {
+    for (list, view, items, children) in &lists {
+        if !view.is_changed() && !items.is_changed() {
+            continue;
+        }
+        let pool: Vec<Entity> = children
+            .into_iter()
+            .flatten()
+            .copied()
+            .filter(|c| rows.contains(*c))
+            .collect();
+        if pool.len() < view.pool_size() {
+            let base = theme
+                .get()
+                .map_or(ButtonStyle::default(), |t| t.button_style("primary"));
+            for slot in pool.len()..view.pool_size() {
+                let row = commands.spawn(list_row(list, slot, &view, base)).id();
+                commands.entity(list).add_child(row);
+            }
+            // laid out next frame, once they exist
+            commands.entity(list).insert(ListRelayout);
+            continue;
+        }
+
+        let columns = view.columns.max(1);
+        let first_line = (view.scroll / view.cell.y).floor() as usize;
+        let first = first_line * columns;
+        for entity in pool {
+            let Ok((mut row, mut node, mut visibility, was_selected, row_children)) =
+                rows.get_mut(entity)
+            else {
+                continue;
+            };
+            let index = first + row.slot;
+            let item = items.view.get(index).map(|i| &items.items[*i]);
+            let Some(item) = item else {
+                row.key = None;
+                *visibility = Visibility::Hidden;
+                if was_selected {
+                    commands.entity(entity).remove::<Selected>();
+                }
+                continue;
+            };
+            *visibility = Visibility::Inherited;
+            row.key = Some(item.key);
+
+            let (line, column) = (index / columns, index % columns);
+            node.top = Val::Px(line as f32 * view.cell.y - view.scroll);
+            node.left = Val::Px(column as f32 * view.cell.x);
+
+            let selected = view.selected == Some(item.key);
+            if selected && !was_selected {
+                commands.entity(entity).insert(Selected);
+            } else if !selected && was_selected {
+                commands.entity(entity).remove::<Selected>();
+            }
+            for child in row_children.iter() {
+                if let Ok((mut text, is_detail)) = texts.get_mut(child) {
+                    let want = if is_detail { &item.detail } else { &item.label };
+                    if text.0 != *want {
+                        text.0 = want.clone();
+                    }
+                }
+            }
+        }
+    }
+}
+
+// the pool was just spawned, lay it out on the next pass
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct ListRelayout;
+
+pub fn relayout_new_pools(
+    mut commands: Commands,
+    mut lists: Query<(Entity, &mut ListView), With<ListRelayout>>,
+) {
+    for (entity, mut view) in &mut lists {
+        view.set_changed();
+        commands.entity(entity).remove::<ListRelayout>();
+    }
+}
+
+fn list_row(list: Entity, slot: usize, view: &ListView, style: ButtonStyle) -> impl Bundle {
+    (
+        UiButton,
+        ListRow {
+            list,
+            slot,
+            key: None,
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Px(view.cell.x - 4.0),
+            height: Val::Px(view.cell.y - 4.0),
+            padding: UiRect::horizontal(Val::Px(10.0)),
+            justify_content: JustifyContent::SpaceBetween,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        BackgroundColor(style.normal),
+        BorderRadius::all(Val::Px(4.0)),
+        Visibility::Hidden,
+        style,
+        children![
+            (
+                ButtonText,
+                Text::default(),
+                TextFont::from_font_size(view.cell.y * 0.5),
+                TextColor(style.text),
+            ),
+            (
+                ButtonText,
+                ListRowDetail,
+                Text::default(),
+                TextFont::from_font_size(view.cell.y * 0.5),
+                TextColor(style.text),
+            ),
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UiCommonPlugin;
+
+    #[derive(Resource)]
+    struct Stock(Vec<(&'static str, u64)>);
+
+    fn stock_rows(stock: &Stock) -> Vec<ListItem> {
+        stock
+            .0
+            .iter()
+            .enumerate()
+            .map(|(i, (name, count))| {
+                ListItem::new(i as u64, *name)
+                    .detail(count.to_string())
+                    .sort_key("count", *count)
+            })
+            .collect()
+    }
+
+    fn app(stock: Stock, view: ListView) -> (App, Entity) {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, UiCommonPlugin))
+            .insert_resource(stock);
+        let list = app
+            .world_mut()
+            .spawn((scroll_list(view), ListSource::resource_with(stock_rows)))
+            .id();
+        // pool spawns, then gets laid out
+        app.update();
+        app.update();
+        (app, list)
+    }
+
+    // keys on screen, top to bottom
+    fn shown(app: &mut App) -> Vec<u64> {
+        let mut rows: Vec<(usize, u64)> = app
+            .world_mut()
+            .query::<&ListRow>()
+            .iter(app.world())
+            .filter_map(|r| Some((r.slot, r.key?)))
+            .collect();
+        rows.sort();
+        rows.into_iter().map(|(_, k)| k).collect()
+    }
+
+    #[test]
+    fn thousands_of_rows_stay_pooled() {
+        let names = ["a"; 5000];
+        let stock = Stock(
+            names
+                .iter()
+                .enumerate()
+                .map(|(i, n)| (*n, i as u64))
+                .collect(),
+        );
+        let view = ListView::list(20.0, 200.0, 100.0).sorted_by("count", true);
+        let (mut app, list) = app(stock, view);
+
+        let pool = app
+            .world_mut()
+            .query::<&ListRow>()
+            .iter(app.world())
+            .count();
+        assert_eq!(pool, 6);
+        assert_eq!(shown(&mut app)[..2], [4999, 4998]);
+
+        app.world_mut().get_mut::<ListView>(list).unwrap().scroll = 40.0;
+        app.update();
+        assert_eq!(shown(&mut app)[0], 4997);
+
+        // scrolled past the end, clamped back
+        app.world_mut().get_mut::<ListView>(list).unwrap().scroll = 1e9;
+        app.update();
+        assert_eq!(
+            app.world().get::<ListView>(list).unwrap().scroll,
+            5000.0 * 20.0 - 100.0
+        );
+    }
+
+    #[test]
+    fn filter_sort_and_select() {
+        let stock = Stock(vec![("Wood", 5), ("Stone", 12), ("wool", 1), ("Iron", 12)]);
+        let view = ListView::list(20.0, 200.0, 200.0)
+            .sorted_by("count", true)
+            .filtered("O");
+        let (mut app, list) = app(stock, view);
+        // every name has an o; equal counts keep source order
+        assert_eq!(shown(&mut app), vec![1, 3, 0, 2]);
+
+        app.world_mut().get_mut::<ListView>(list).unwrap().filter = "wo".into();
+        app.update();
+        assert_eq!(shown(&mut app), vec![0, 2]);
+
+        let wool = app
+            .world_mut()
+            .query::<(Entity, &ListRow)>()
+            .iter(app.world())
+            .find(|(_, r)| r.key == Some(2))
+            .map(|(e, _)| e)
+            .unwrap();
+        *app.world_mut().get_mut::<Interaction>(wool).unwrap() = Interaction::Pressed;
+        app.update();
+        assert_eq!(app.world().get::<ListView>(list).unwrap().selected, Some(2));
+        app.update();
+        assert!(app.world().get::<Selected>(wool).is_some());
+    }
+}
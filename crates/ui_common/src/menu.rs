@@ -0,0 +1,519 @@
+use bevy::{ecs::system::SystemParam, platform::collections::HashMap, prelude::*};
+use core_engine::{
+    components::{GamePause, PauseClock},
+    config::AudioSettings,
+};
+
+use crate::{
+    dialog::DialogButton,
+    interaction::ButtonClicked,
+    menu_screens::spawn_menu_screen,
+    theme::UiThemes,
+    widgets::{Disabled, Focused},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MenuScreen {
+    Title,
+    Pause,
+    GameOver,
+    Settings,
+    // one of MenuConfig::custom, by name
+    Custom(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MenuAction {
+    // start a run from the title screen, closes every menu
+    Play,
+    Resume,
+    // start over from the game-over screen, closes every menu
+    Retry,
+    Open(MenuScreen),
+    Back,
+    ToTitle,
+    Quit,
+    // only sent as a MenuActionEvent, the game decides what it does
+    Custom(String),
+}
+
+// a button that runs `0` when clicked or activated with keys / gamepad
+#[derive(Component, Debug, Clone)]
+pub struct MenuButton(pub MenuAction);
+
+// every menu action, after the stack handled the built-in ones.
+// games listen for Play / Retry to reset their run
+#[derive(Message, Debug, Clone)]
+pub struct MenuActionEvent {
+    pub action: MenuAction,
+}
+
+// open menus, the last one is on screen. empty -> in gameplay
+#[derive(Resource, Debug, Clone, Default)]
+pub struct MenuStack {
+    screens: Vec<MenuScreen>,
+}
+
+impl MenuStack {
+    pub fn push(&mut self, screen: MenuScreen) {
+        self.screens.push(screen);
+    }
+
+    pub fn pop(&mut self) -> Option<MenuScreen> {
+        self.screens.pop()
+    }
+
+    // swaps the top screen, or opens `screen` if nothing is open
+    pub fn replace(&mut self, screen: MenuScreen) {
+        self.screens.pop();
+        self.screens.push(screen);
+    }
+
+    pub fn clear(&mut self) {
+        self.screens.clear();
+    }
+
+    pub fn top(&self) -> Option<&MenuScreen> {
+        self.screens.last()
+    }
+
+    pub fn is_open(&self) -> bool {
+        !self.screens.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.screens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.screens.is_empty()
+    }
+}
+
+// run condition for gameplay systems
+pub fn menu_closed(stack: Res<MenuStack>) -> bool {
+    !stack.is_open()
+}
+
+// lines under the game-over heading, set by the game before it opens the screen
+#[derive(Resource, Debug, Clone, Default)]
+pub struct GameOverSummary(pub Vec<String>);
+
+// a game-defined screen, opened with MenuAction::Open(MenuScreen::Custom(name))
+#[derive(Debug, Clone, Default)]
+pub struct CustomScreen {
+    pub title: String,
+    pub lines: Vec<String>,
+    pub buttons: Vec<(String, MenuAction)>,
+}
+
+#[derive(Resource, Debug, Clone)]
+pub struct MenuConfig {
+    pub title: String,
+    pub subtitle: Option<String>,
+    pub game_over_title: String,
+    // open the title screen on startup instead of going straight into gameplay
+    pub start_on_title: bool,
+    // opens pause in gameplay, backs out of menus. gamepad Start does the same
+    pub pause_key: Option<KeyCode>,
+    // stop Time<Virtual> while any menu is open
+    pub pause_time: bool,
+    // added to a built-in screen above its back / quit buttons
+    pub extra_buttons: HashMap<MenuScreen, Vec<(String, MenuAction)>>,
+    pub custom: HashMap<String, CustomScreen>,
+}
+
+impl Default for MenuConfig {
+    fn default() -> Self {
+        Self {
+            title: String::new(),
+            subtitle: None,
+            game_over_title: "GAME OVER".to_string(),
+            start_on_title: true,
+            pause_key: Some(KeyCode::Escape),
+            pause_time: true,
+            extra_buttons: HashMap::default(),
+            custom: HashMap::default(),
+        }
+    }
+}
+
+// the screen on display, and its focusable buttons in navigation order
+#[derive(Component, Debug, Clone)]
+pub struct MenuScreenRoot {
+    pub screen: MenuScreen,
+    pub focusables: Vec<Entity>,
+}
+
+// index into the top screen's focusables
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MenuFocus(pub usize);
+
+// -/+ on the settings screen
+#[derive(Component, Debug, Clone, Copy)]
+pub enum SettingsAdjust {
+    Master(f32),
+    Music(f32),
+    Sfx(f32),
+    ToggleMute,
+    // next UiThemes entry, only shown with the UiThemePlugin
+    CycleTheme,
+}
+
+// keyboard + every connected gamepad, for menu navigation
+#[derive(SystemParam)]
+pub struct MenuInput<'w, 's> {
+    keys: Option<Res<'w, ButtonInput<KeyCode>>>,
+    gamepads: Query<'w, 's, &'static Gamepad>,
+}
+
+impl MenuInput<'_, '_> {
+    pub fn key(&self, codes: &[KeyCode]) -> bool {
+        self.keys
+            .as_ref()
+            .is_some_and(|k| codes.iter().any(|c| k.just_pressed(*c)))
+    }
+
+    pub fn pad(&self, button: GamepadButton) -> bool {
+        self.gamepads.iter().any(|g| g.just_pressed(button))
+    }
+}
+
+// what a screen is built from
+#[derive(SystemParam)]
+pub struct MenuContent<'w> {
+    pub config: Res<'w, MenuConfig>,
+    pub summary: Res<'w, GameOverSummary>,
+    pub audio: Option<Res<'w, AudioSettings>>,
+    pub themes: Option<Res<'w, UiThemes>>,
+}
+
+pub fn open_title_on_start(config: Res<MenuConfig>, mut stack: ResMut<MenuStack>) {
+    if config.start_on_title {
+        stack.push(MenuScreen::Title);
+    }
+}
+
+// pause key / Start: opens pause in gameplay, backs out of pause and settings.
+// title and game-over have nothing to go back to
+pub fn toggle_pause_menu(config: Res<MenuConfig>, input: MenuInput, mut stack: ResMut<MenuStack>) {
+    let key = config.pause_key.is_some_and(|k| input.key(&[k]));
+    if !key && !input.pad(GamepadButton::Start) {
+        return;
+    }
+    match stack.top() {
+        None => stack.push(MenuScreen::Pause),
+        Some(MenuScreen::Title | MenuScreen::GameOver) => {}
+        Some(_) => {
+            stack.pop();
+        }
+    }
+}
+
+// up/down (or left/right, W/S, Tab, d-pad) moves focus, Enter / Space / gamepad South
+// clicks the focused button, gamepad East goes back. hovering also moves focus
+pub fn navigate_menu_focus(
+    input: MenuInput,
+    screens: Query<&MenuScreenRoot>,
+    hovered: Query<&Interaction, (Changed<Interaction>, With<MenuButton>)>,
+    disabled: Query<(), With<Disabled>>,
+    mut focus: ResMut<MenuFocus>,
+    mut stack: ResMut<MenuStack>,
+    mut clicks: MessageWriter<ButtonClicked>,
+) {
+    let Some(root) = screens.iter().next() else {
+        return;
+    };
+    let count = root.focusables.len();
+
+    if let Some(i) = root
+        .focusables
+        .iter()
+        .position(|e| hovered.get(*e).is_ok_and(|i| *i == Interaction::Hovered))
+    {
+        focus.set_if_neq(MenuFocus(i));
+    }
+
+    if count > 0 {
+        let prev = input.key(&[KeyCode::ArrowUp, KeyCode::ArrowLeft, KeyCode::KeyW])
+            || input.pad(GamepadButton::DPadUp)
+            || input.pad(GamepadButton::DPadLeft);
+        let next = input.key(&[
+            KeyCode::ArrowDown,
+            KeyCode::ArrowRight,
+            KeyCode::KeyS,
+            KeyCode::Tab,
+        ]) || input.pad(GamepadButton::DPadDown)
+            || input.pad(GamepadButton::DPadRight);
+        if prev {
+            focus.0 = (focus.0 + count - 1) % count;
+        } else if next {
+            focus.0 = (focus.0 + 1) % count;
+        }
+
+        if (input.key(&[KeyCode::Enter, KeyCode::Space]) || input.pad(GamepadButton::South))
+            && let Some(&entity) = root.focusables.get(focus.0)
+            && !disabled.contains(entity)
+        {
+            clicks.write(ButtonClicked { entity });
+        }
+    }
+
+    if input.pad(GamepadButton::East)
+        && !matches!(root.screen, MenuScreen::Title | MenuScreen::GameOver)
+    {
+        stack.pop();
+    }
+}
+
+// MenuButton clicks -> stack changes + MenuActionEvent
+pub fn run_menu_actions(
+    mut clicks: MessageReader<ButtonClicked>,
+    buttons: Query<&MenuButton>,
+    mut stack: ResMut<MenuStack>,
+    mut actions: MessageWriter<MenuActionEvent>,
+    mut exit: MessageWriter<AppExit>,
+) {
+    for button in clicks.read().filter_map(|c| buttons.get(c.entity).ok()) {
+        let action = button.0.clone();
+        match &action {
+            MenuAction::Play | MenuAction::Resume | MenuAction::Retry => stack.clear(),
+            MenuAction::Open(screen) => stack.push(screen.clone()),
+            MenuAction::Back => {
+                stack.pop();
+            }
+            MenuAction::ToTitle => {
+                stack.clear();
+                stack.push(MenuScreen::Title);
+            }
+            MenuAction::Quit => {
+                exit.write(AppExit::Success);
+            }
+            MenuAction::Custom(_) => {}
+        }
+        actions.write(MenuActionEvent { action });
+    }
+}
+
+// saved by the SoundPlugin like any other change to AudioSettings
+pub fn adjust_settings(
+    mut clicks: MessageReader<ButtonClicked>,
+    buttons: Query<&SettingsAdjust>,
+    mut settings: Option<ResMut<AudioSettings>>,
+    mut themes: Option<ResMut<UiThemes>>,
+) {
+    for adjust in clicks.read().filter_map(|c| buttons.get(c.entity).ok()) {
+        let step = |v: &mut f32, d: f32| *v = ((*v + d) * 10.0).round().clamp(0.0, 10.0) / 10.0;
+        if let SettingsAdjust::CycleTheme = adjust {
+            if let Some(themes) = themes.as_mut() {
+                themes.cycle();
+            }
+            continue;
+        }
+        let Some(settings) = settings.as_mut() else {
+            continue;
+        };
+        match *adjust {
+            SettingsAdjust::Master(d) => step(&mut settings.master, d),
+            SettingsAdjust::Music(d) => step(&mut settings.music, d),
+            SettingsAdjust::Sfx(d) => step(&mut settings.sfx, d),
+            SettingsAdjust::ToggleMute => settings.muted = !settings.muted,
+            SettingsAdjust::CycleTheme => {}
+        }
+    }
+}
+
+// only the top screen exists, the ones below are rebuilt when they're back on top
+pub fn rebuild_menu_screens(
+    mut commands: Commands,
+    stack: Res<MenuStack>,
+    content: MenuContent,
+    roots: Query<Entity, With<MenuScreenRoot>>,
+    mut focus: ResMut<MenuFocus>,
+    mut clock: PauseClock,
+) {
+    if !stack.is_changed() {
+        return;
+    }
+    for root in &roots {
+        commands.entity(root).despawn();
+    }
+    focus.0 = 0;
+    if let Some(screen) = stack.top() {
+        spawn_menu_screen(&mut commands, screen, &content);
+    }
+
+    // the menu's own hold, a pending level-up keeps the game paused after closing
+    if content.config.pause_time {
+        if stack.is_open() {
+            clock.hold(GamePause::MENU);
+        } else {
+            clock.release(GamePause::MENU);
+        }
+    }
+}
+
+pub fn show_menu_focus(
+    mut commands: Commands,
+    focus: Res<MenuFocus>,
+    screens: Query<Ref<MenuScreenRoot>>,
+    // a dialog over the menu keeps its own focus
+    focused: Query<Entity, (With<Focused>, Without<DialogButton>)>,
+) {
+    let Some(root) = screens.iter().next() else {
+        return;
+    };
+    if !focus.is_changed() && !root.is_added() {
+        return;
+    }
+    let target = root.focusables.get(focus.0).copied();
+    for entity in &focused {
+        if Some(entity) != target {
+            commands.entity(entity).remove::<Focused>();
+        }
+    }
+    if let Some(target) = target {
+        commands.entity(target).insert(Focused);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MenuPlugin, UiCommonPlugin};
+
+    #[derive(Resource, Default)]
+    struct Sent(Vec<MenuAction>);
+
+    fn record(mut reader: MessageReader<MenuActionEvent>, mut sent: ResMut<Sent>) {
+        sent.0.extend(reader.read().map(|e| e.action.clone()));
+    }
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, UiCommonPlugin))
+            .add_plugins(MenuPlugin::new("TEST"))
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<Sent>()
+            .add_systems(Last, record);
+        app.update();
+        app
+    }
+
+    fn tap(app: &mut App, key: KeyCode) {
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(key);
+        app.update();
+        let mut keys = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        keys.release(key);
+        keys.clear();
+        app.update();
+    }
+
+    fn top(app: &App) -> Option<MenuScreen> {
+        app.world().resource::<MenuStack>().top().cloned()
+    }
+
+    fn paused(app: &App) -> bool {
+        app.world().resource::<Time<Virtual>>().is_paused()
+    }
+
+    #[test]
+    fn title_play_pause_resume() {
+        let mut app = app();
+        assert_eq!(top(&app), Some(MenuScreen::Title));
+        assert!(paused(&app));
+
+        // escape does nothing on the title screen, Play is focused first
+        tap(&mut app, KeyCode::Escape);
+        assert_eq!(top(&app), Some(MenuScreen::Title));
+        tap(&mut app, KeyCode::Enter);
+        assert_eq!(top(&app), None);
+        assert!(!paused(&app));
+
+        tap(&mut app, KeyCode::Escape);
+        assert_eq!(top(&app), Some(MenuScreen::Pause));
+        assert!(paused(&app));
+
+        // Resume, Settings, ...: open settings, then back out twice
+        tap(&mut app, KeyCode::ArrowDown);
+        tap(&mut app, KeyCode::Enter);
+        assert_eq!(top(&app), Some(MenuScreen::Settings));
+        tap(&mut app, KeyCode::Escape);
+        assert_eq!(top(&app), Some(MenuScreen::Pause));
+        tap(&mut app, KeyCode::Escape);
+        assert_eq!(top(&app), None);
+        assert!(!paused(&app));
+        assert!(
+            app.world_mut()
+                .query::<&MenuScreenRoot>()
+                .iter(app.world())
+                .next()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn focus_wraps_and_game_over_retries() {
+        let mut app = app();
+        app.world_mut().resource_mut::<MenuStack>().clear();
+        app.update();
+        app.world_mut()
+            .resource_mut::<MenuStack>()
+            .push(MenuScreen::GameOver);
+        app.update();
+
+        // Retry is first, going up wraps to the last button
+        tap(&mut app, KeyCode::ArrowUp);
+        let root = app
+            .world_mut()
+            .query::<&MenuScreenRoot>()
+            .single(app.world())
+            .unwrap()
+            .clone();
+        assert_eq!(
+            app.world().resource::<MenuFocus>().0,
+            root.focusables.len() - 1
+        );
+        assert!(
+            app.world()
+                .get::<Focused>(*root.focusables.last().unwrap())
+                .is_some()
+        );
+
+        tap(&mut app, KeyCode::ArrowDown);
+        tap(&mut app, KeyCode::Enter);
+        assert_eq!(top(&app), None);
+        assert_eq!(app.world().resource::<Sent>().0, vec![MenuAction::Retry]);
+    }
+
+    fn level_up_pending(app: &mut App, pending: bool) {
+        app.world_mut()
+            .resource_scope(|world, mut pause: Mut<GamePause>| {
+                let mut time = world.resource_mut::<Time<Virtual>>();
+                if pending {
+                    pause.hold(GamePause::LEVEL_UP, &mut time);
+                } else {
+                    pause.release(GamePause::LEVEL_UP, &mut time);
+                }
+            });
+    }
+
+    #[test]
+    fn closing_keeps_a_level_up_paused() {
+        let mut app = app();
+        tap(&mut app, KeyCode::Enter);
+        assert!(!paused(&app));
+
+        level_up_pending(&mut app, true);
+        tap(&mut app, KeyCode::Escape);
+        assert_eq!(top(&app), Some(MenuScreen::Pause));
+        tap(&mut app, KeyCode::Escape);
+        assert_eq!(top(&app), None);
+        assert!(paused(&app));
+
+        level_up_pending(&mut app, false);
+        assert!(!paused(&app));
+    }
+}
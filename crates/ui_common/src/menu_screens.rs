@@ -0,0 +1,258 @@
+use bevy::prelude::*;
+use core_engine::config::AudioSettings;
+
+use crate::{
+    bindings::TextBinding,
+    menu::{MenuAction, MenuButton, MenuContent, MenuScreen, MenuScreenRoot, SettingsAdjust},
+    theme::{TextRole, ThemedText, UiTheme, UiThemes},
+    widgets::{button, label, panel, screen_root, themed_button, themed_label},
+};
+
+// label, the -/+ adjustment, and the value it shows
+type VolumeRow = (
+    &'static str,
+    fn(f32) -> SettingsAdjust,
+    fn(&AudioSettings) -> f32,
+);
+
+// builds `screen` as one tree under a MenuScreenRoot, buttons in focus order
+pub fn spawn_menu_screen(
+    commands: &mut Commands,
+    screen: &MenuScreen,
+    content: &MenuContent,
+) -> Entity {
+    let config = &content.config;
+    let mut focusables = Vec::new();
+    let root = commands
+        .spawn((
+            screen_root(),
+            BackgroundColor(Color::BLACK.with_alpha(0.55)),
+            GlobalZIndex(100),
+        ))
+        .id();
+    let body = commands.spawn(panel()).id();
+    commands.entity(root).add_child(body);
+
+    let buttons =
+        |commands: &mut Commands, focusables: &mut Vec<Entity>, list: &[(&str, MenuAction)]| {
+            for (text, action) in list {
+                let variant = match action {
+                    MenuAction::Quit => "danger",
+                    _ => "primary",
+                };
+                let e = commands
+                    .spawn((themed_button(*text, variant), MenuButton(action.clone())))
+                    .id();
+                commands.entity(body).add_child(e);
+                focusables.push(e);
+            }
+        };
+    let extras = config
+        .extra_buttons
+        .get(screen)
+        .cloned()
+        .unwrap_or_default();
+    let extras: Vec<(&str, MenuAction)> = extras
+        .iter()
+        .map(|(text, action)| (text.as_str(), action.clone()))
+        .collect();
+
+    match screen {
+        MenuScreen::Title => {
+            add_text(commands, body, themed_label(&config.title, TextRole::Title));
+            if let Some(subtitle) = &config.subtitle {
+                add_text(
+                    commands,
+                    body,
+                    (
+                        label(subtitle, TextRole::Small.default_size()),
+                        ThemedText::new(TextRole::Small).colored("text_muted"),
+                    ),
+                );
+            }
+            buttons(commands, &mut focusables, &[("PLAY", MenuAction::Play)]);
+            buttons(commands, &mut focusables, &extras);
+            buttons(
+                commands,
+                &mut focusables,
+                &[
+                    ("SETTINGS", MenuAction::Open(MenuScreen::Settings)),
+                    ("QUIT", MenuAction::Quit),
+                ],
+            );
+        }
+        MenuScreen::Pause => {
+            add_text(commands, body, themed_label("PAUSED", TextRole::Heading));
+            buttons(
+                commands,
+                &mut focusables,
+                &[
+                    ("RESUME", MenuAction::Resume),
+                    ("SETTINGS", MenuAction::Open(MenuScreen::Settings)),
+                ],
+            );
+            buttons(commands, &mut focusables, &extras);
+            buttons(
+                commands,
+                &mut focusables,
+                &[
+                    ("MAIN MENU", MenuAction::ToTitle),
+                    ("QUIT", MenuAction::Quit),
+                ],
+            );
+        }
+        MenuScreen::GameOver => {
+            add_text(
+                commands,
+                body,
+                themed_label(&config.game_over_title, TextRole::Heading),
+            );
+            for line in &content.summary.0 {
+                add_text(commands, body, themed_label(line, TextRole::Body));
+            }
+            buttons(commands, &mut focusables, &[("RETRY", MenuAction::Retry)]);
+            buttons(commands, &mut focusables, &extras);
+            buttons(
+                commands,
+                &mut focusables,
+                &[
+                    ("MAIN MENU", MenuAction::ToTitle),
+                    ("QUIT", MenuAction::Quit),
+                ],
+            );
+        }
+        MenuScreen::Settings => {
+            add_text(commands, body, themed_label("SETTINGS", TextRole::Heading));
+            if content.audio.is_some() {
+                let rows: [VolumeRow; 3] = [
+                    ("Master", SettingsAdjust::Master, |a| a.master),
+                    ("Music", SettingsAdjust::Music, |a| a.music),
+                    ("Sfx", SettingsAdjust::Sfx, |a| a.sfx),
+                ];
+                for (name, adjust, get) in rows {
+                    let row = volume_row(commands, name, adjust, get, &mut focusables);
+                    commands.entity(body).add_child(row);
+                }
+                let mute = commands
+                    .spawn((button(""), SettingsAdjust::ToggleMute))
+                    .id();
+                relabel(
+                    commands,
+                    mute,
+                    TextBinding::resource_with::<AudioSettings>(|a| {
+                        if a.muted { "SOUND: OFF" } else { "SOUND: ON" }.to_string()
+                    }),
+                );
+                commands.entity(body).add_child(mute);
+                focusables.push(mute);
+            }
+            if content.themes.is_some() {
+                let theme = commands
+                    .spawn((button(""), SettingsAdjust::CycleTheme))
+                    .id();
+                relabel(commands, theme, TextBinding::computed(theme_name));
+                commands.entity(body).add_child(theme);
+                focusables.push(theme);
+            }
+            buttons(commands, &mut focusables, &extras);
+            buttons(commands, &mut focusables, &[("BACK", MenuAction::Back)]);
+        }
+        MenuScreen::Custom(name) => {
+            let custom = config.custom.get(name).cloned().unwrap_or_default();
+            add_text(
+                commands,
+                body,
+                themed_label(&custom.title, TextRole::Heading),
+            );
+            for line in &custom.lines {
+                add_text(commands, body, themed_label(line, TextRole::Small));
+            }
+            let list: Vec<(&str, MenuAction)> = custom
+                .buttons
+                .iter()
+                .map(|(text, action)| (text.as_str(), action.clone()))
+                .collect();
+            buttons(commands, &mut focusables, &list);
+            buttons(commands, &mut focusables, &extras);
+            buttons(commands, &mut focusables, &[("BACK", MenuAction::Back)]);
+        }
+    }
+
+    commands.entity(root).insert(MenuScreenRoot {
+        screen: screen.clone(),
+        focusables,
+    });
+    root
+}
+
+fn add_text(commands: &mut Commands, parent: Entity, bundle: impl Bundle) {
+    let e = commands.spawn(bundle).id();
+    commands.entity(parent).add_child(e);
+}
+
+// "Music  70%  [-] [+]"
+fn volume_row(
+    commands: &mut Commands,
+    name: &'static str,
+    adjust: fn(f32) -> SettingsAdjust,
+    get: fn(&AudioSettings) -> f32,
+    focusables: &mut Vec<Entity>,
+) -> Entity {
+    let value = move |a: &AudioSettings| format!("{name}  {:>3.0}%", get(a) * 100.0);
+    let row = commands
+        .spawn(Node {
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(12.0),
+            ..default()
+        })
+        .id();
+    let text = commands
+        .spawn((
+            themed_label("", TextRole::Label),
+            Node {
+                width: Val::Px(180.0),
+                ..default()
+            },
+            TextBinding::resource_with::<AudioSettings>(value),
+        ))
+        .id();
+    commands.entity(row).add_child(text);
+    for (sign, delta) in [("-", -0.1), ("+", 0.1)] {
+        let b = commands.spawn((button(sign), adjust(delta))).id();
+        commands.entity(b).insert(Node {
+            width: Val::Px(60.0),
+            height: Val::Px(48.0),
+            border: UiRect::all(Val::Px(3.0)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        });
+        commands.entity(row).add_child(b);
+        focusables.push(b);
+    }
+    row
+}
+
+// "THEME: High contrast", the name from the file once it's loaded
+fn theme_name(world: &World) -> String {
+    let name = world.get_resource::<UiThemes>().map(|themes| {
+        world
+            .get_resource::<Assets<UiTheme>>()
+            .and_then(|assets| themes.current(assets))
+            .map_or_else(|| themes.active.clone(), |theme| theme.name.clone())
+    });
+    format!("THEME: {}", name.unwrap_or_default().to_uppercase())
+}
+
+// puts `binding` on a button's ButtonText child
+fn relabel(commands: &mut Commands, button: Entity, binding: TextBinding) {
+    commands.queue(move |world: &mut World| {
+        let Some(children) = world.get::<Children>(button) else {
+            return;
+        };
+        if let Some(&text) = children.first() {
+            world.entity_mut(text).insert(binding);
+        }
+    });
+}
@@ -0,0 +1,76 @@
+use bevy::prelude::*;
+
+// colors a button goes through, applied by update_button_visuals
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct ButtonStyle {
+    pub normal: Color,
+    pub hovered: Color,
+    pub pressed: Color,
+    pub disabled: Color,
+    pub border: Color,
+    pub text: Color,
+    pub text_disabled: Color,
+    pub font_size: f32,
+}
+
+impl Default for ButtonStyle {
+    fn default() -> Self {
+        Self::primary()
+    }
+}
+
+impl ButtonStyle {
+    pub fn primary() -> Self {
+        Self {
+            normal: Color::srgb(0.15, 0.15, 0.18),
+            hovered: Color::srgb(0.22, 0.22, 0.27),
+            pressed: Color::srgb(0.32, 0.30, 0.40),
+            disabled: Color::srgb(0.10, 0.10, 0.10),
+            border: Color::srgb(0.85, 0.85, 0.85),
+            text: Color::srgb(0.9, 0.9, 0.9),
+            text_disabled: Color::srgb(0.45, 0.45, 0.45),
+            font_size: 24.0,
+        }
+    }
+
+    // same shape, text in `accent`. the tycoon buy/upgrade buttons
+    pub fn accent(accent: Color) -> Self {
+        Self {
+            text: accent,
+            border: accent,
+            ..Self::primary()
+        }
+    }
+
+    pub fn danger() -> Self {
+        Self::accent(Color::srgb(0.9, 0.3, 0.25))
+    }
+
+    pub fn with_font_size(mut self, size: f32) -> Self {
+        self.font_size = size;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PanelStyle {
+    pub background: Color,
+    pub border: Color,
+    pub padding: f32,
+    pub gap: f32,
+    pub radius: f32,
+}
+
+impl Default for PanelStyle {
+    fn default() -> Self {
+        Self {
+            background: Color::srgb(0.05, 0.05, 0.07).with_alpha(0.92),
+            border: Color::NONE,
+            padding: 24.0,
+            gap: 10.0,
+            radius: 8.0,
+        }
+    }
+}
+
+pub const LABEL_COLOR: Color = Color::srgb(0.9, 0.9, 0.9);
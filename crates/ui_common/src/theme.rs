@@ -0,0 +1,517 @@
+use bevy::{
+    asset::{AssetEvent, embedded_asset},
+    ecs::system::SystemParam,
+    platform::collections::HashMap,
+    prelude::*,
+};
+use core_engine::config::RonAssetPlugin;
+use serde::Deserialize;
+
+use crate::{
+    style::{ButtonStyle, PanelStyle},
+    widgets::{ButtonText, UiButton},
+};
+
+pub const DEFAULT_THEME: &str = "embedded://ui_common/themes/default.theme.ron";
+pub const HIGH_CONTRAST_THEME: &str = "embedded://ui_common/themes/high_contrast.theme.ron";
+
+// palettes, type scale, spacing tokens and widget variants, see themes/default.theme.ron.
+// anything a variant leaves out falls back to the same palette names in every theme
+#[derive(Asset, TypePath, Deserialize, Debug, Clone)]
+pub struct UiTheme {
+    pub name: String,
+    pub palette: HashMap<String, String>,
+    pub typography: HashMap<TextRole, f32>,
+    #[serde(default)]
+    pub spacing: HashMap<String, f32>,
+    #[serde(default)]
+    pub radii: HashMap<String, f32>,
+    #[serde(default)]
+    pub buttons: HashMap<String, ButtonVariant>,
+    #[serde(default)]
+    pub panels: HashMap<String, PanelVariant>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Deserialize, Reflect)]
+pub enum TextRole {
+    Title,
+    Heading,
+    Body,
+    #[default]
+    Label,
+    Small,
+}
+
+impl TextRole {
+    // the default theme's scale, used until a theme is loaded
+    pub fn default_size(self) -> f32 {
+        match self {
+            TextRole::Title => 56.0,
+            TextRole::Heading => 44.0,
+            TextRole::Body => 28.0,
+            TextRole::Label => 24.0,
+            TextRole::Small => 20.0,
+        }
+    }
+}
+
+// colors are palette names or "#hex", sizes are token names or numbers
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct ButtonVariant {
+    pub normal: String,
+    pub hovered: String,
+    pub pressed: String,
+    pub disabled: String,
+    pub border: String,
+    pub text: String,
+    pub text_disabled: String,
+    pub font: TextRole,
+    pub radius: String,
+    pub border_width: f32,
+}
+
+impl Default for ButtonVariant {
+    fn default() -> Self {
+        Self {
+            normal: "surface".into(),
+            hovered: "surface_hover".into(),
+            pressed: "surface_pressed".into(),
+            disabled: "disabled".into(),
+            border: "border".into(),
+            text: "text".into(),
+            text_disabled: "text_disabled".into(),
+            font: TextRole::Label,
+            radius: "md".into(),
+            border_width: 3.0,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct PanelVariant {
+    pub background: String,
+    pub border: String,
+    pub padding: String,
+    pub gap: String,
+    pub radius: String,
+}
+
+impl Default for PanelVariant {
+    fn default() -> Self {
+        Self {
+            background: "panel".into(),
+            border: "none".into(),
+            padding: "lg".into(),
+            gap: "md".into(),
+            radius: "md".into(),
+        }
+    }
+}
+
+impl UiTheme {
+    // palette name, "#hex" or "none". unknown names are magenta so they stand out
+    pub fn color(&self, name: &str) -> Color {
+        if name == "none" {
+            return Color::NONE;
+        }
+        let hex = self.palette.get(name).map_or(name, String::as_str);
+        Srgba::hex(hex).map_or(Color::srgb(1.0, 0.0, 1.0), Color::from)
+    }
+
+    pub fn font_size(&self, role: TextRole) -> f32 {
+        self.typography
+            .get(&role)
+            .copied()
+            .unwrap_or(role.default_size())
+    }
+
+    pub fn space(&self, token: &str) -> f32 {
+        token_or_number(&self.spacing, token)
+    }
+
+    pub fn radius(&self, token: &str) -> f32 {
+        token_or_number(&self.radii, token)
+    }
+
+    fn button(&self, variant: &str) -> ButtonVariant {
+        self.buttons
+            .get(variant)
+            .or_else(|| self.buttons.get("primary"))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn panel(&self, variant: &str) -> PanelVariant {
+        self.panels
+            .get(variant)
+            .or_else(|| self.panels.get("default"))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    // a missing variant falls back to "primary"
+    pub fn button_style(&self, variant: &str) -> ButtonStyle {
+        let v = self.button(variant);
+        ButtonStyle {
+            normal: self.color(&v.normal),
+            hovered: self.color(&v.hovered),
+            pressed: self.color(&v.pressed),
+            disabled: self.color(&v.disabled),
+            border: self.color(&v.border),
+            text: self.color(&v.text),
+            text_disabled: self.color(&v.text_disabled),
+            font_size: self.font_size(v.font),
+        }
+    }
+
+    pub fn panel_style(&self, variant: &str) -> PanelStyle {
+        let v = self.panel(variant);
+        PanelStyle {
+            background: self.color(&v.background),
+            border: self.color(&v.border),
+            padding: self.space(&v.padding),
+            gap: self.space(&v.gap),
+            radius: self.radius(&v.radius),
+        }
+    }
+}
+
+fn token_or_number(tokens: &HashMap<String, f32>, token: &str) -> f32 {
+    tokens
+        .get(token)
+        .copied()
+        .or_else(|| token.parse().ok())
+        .unwrap_or(0.0)
+}
+
+// restyled from the active theme whenever it changes
+#[derive(Component, Debug, Clone, PartialEq, Eq)]
+pub struct ThemedButton(pub String);
+
+impl Default for ThemedButton {
+    fn default() -> Self {
+        Self("primary".into())
+    }
+}
+
+#[derive(Component, Debug, Clone, PartialEq, Eq)]
+pub struct ThemedPanel(pub String);
+
+impl Default for ThemedPanel {
+    fn default() -> Self {
+        Self("default".into())
+    }
+}
+
+// font size from the type scale, color from the palette ("text" unless set)
+#[derive(Component, Debug, Clone, PartialEq, Eq, Default)]
+pub struct ThemedText {
+    pub role: TextRole,
+    pub color: Option<String>,
+}
+
+impl ThemedText {
+    pub fn new(role: TextRole) -> Self {
+        Self { role, color: None }
+    }
+
+    pub fn colored(mut self, palette_name: impl Into<String>) -> Self {
+        self.color = Some(palette_name.into());
+        self
+    }
+}
+
+// every loaded theme by name, and the one on screen. switch with `set_active`
+#[derive(Resource, Debug, Clone, Default)]
+pub struct UiThemes {
+    pub themes: Vec<(String, Handle<UiTheme>)>,
+    pub active: String,
+}
+
+impl UiThemes {
+    pub fn set_active(&mut self, name: &str) {
+        if self.themes.iter().any(|(n, _)| n == name) {
+            self.active = name.to_string();
+        }
+    }
+
+    // next theme in registration order, wraps around
+    pub fn cycle(&mut self) {
+        let i = self.themes.iter().position(|(n, _)| *n == self.active);
+        if let Some((name, _)) = self
+            .themes
+            .get(i.map_or(0, |i| (i + 1) % self.themes.len()))
+        {
+            self.active = name.clone();
+        }
+    }
+
+    pub fn handle(&self) -> Option<&Handle<UiTheme>> {
+        self.themes
+            .iter()
+            .find(|(n, _)| *n == self.active)
+            .map(|(_, h)| h)
+    }
+
+    pub fn current<'a>(&self, assets: &'a Assets<UiTheme>) -> Option<&'a UiTheme> {
+        assets.get(self.handle()?)
+    }
+}
+
+// the active theme if there is one, for widgets spawned with theme colors baked in
+#[derive(SystemParam)]
+pub struct ActiveTheme<'w> {
+    themes: Option<Res<'w, UiThemes>>,
+    assets: Option<Res<'w, Assets<UiTheme>>>,
+}
+
+impl ActiveTheme<'_> {
+    pub fn get(&self) -> Option<&UiTheme> {
+        self.themes.as_ref()?.current(self.assets.as_ref()?)
+    }
+
+    // palette entry, or `fallback` without a theme
+    pub fn color(&self, name: &str, fallback: Color) -> Color {
+        self.get().map_or(fallback, |theme| theme.color(name))
+    }
+}
+
+type ThemedButtons<'a> = (
+    Ref<'a, ThemedButton>,
+    &'a mut ButtonStyle,
+    &'a mut Node,
+    &'a mut BorderColor,
+    &'a mut BorderRadius,
+    Option<&'a Children>,
+);
+
+type ThemedPanels<'a> = (
+    Ref<'a, ThemedPanel>,
+    &'a mut Node,
+    &'a mut BackgroundColor,
+    &'a mut BorderColor,
+    &'a mut BorderRadius,
+);
+
+// restyles everything on a switch or a hot reload, otherwise only new widgets
+pub fn apply_ui_theme(
+    themes: Res<UiThemes>,
+    assets: Res<Assets<UiTheme>>,
+    mut events: MessageReader<AssetEvent<UiTheme>>,
+    mut buttons: Query<ThemedButtons, With<UiButton>>,
+    mut panels: Query<ThemedPanels, Without<UiButton>>,
+    mut texts: Query<(Ref<ThemedText>, &mut TextFont, &mut TextColor)>,
+    mut button_fonts: Query<&mut TextFont, (With<ButtonText>, Without<ThemedText>)>,
+) {
+    let reloaded = events.read().any(|ev| {
+        themes
+            .handle()
+            .is_some_and(|h| ev.is_loaded_with_dependencies(h.id()) || ev.is_modified(h.id()))
+    });
+    let Some(theme) = themes.current(&assets) else {
+        return;
+    };
+    let all = reloaded || themes.is_changed();
+
+    for (themed, mut style, mut node, mut border, mut radius, children) in &mut buttons {
+        if !all && !themed.is_added() {
+            continue;
+        }
+        let variant = theme.button(&themed.0);
+        *style = theme.button_style(&themed.0);
+        node.border = UiRect::all(Val::Px(variant.border_width));
+        *border = BorderColor::all(style.border);
+        *radius = BorderRadius::all(Val::Px(theme.radius(&variant.radius)));
+        for child in children.into_iter().flatten() {
+            if let Ok(mut font) = button_fonts.get_mut(*child) {
+                font.font_size = style.font_size;
+            }
+        }
+    }
+
+    for (themed, mut node, mut bg, mut border, mut radius) in &mut panels {
+        if !all && !themed.is_added() {
+            continue;
+        }
+        let style = theme.panel_style(&themed.0);
+        node.padding = UiRect::all(Val::Px(style.padding));
+        node.row_gap = Val::Px(style.gap);
+        bg.0 = style.background;
+        *border = BorderColor::all(style.border);
+        *radius = BorderRadius::all(Val::Px(style.radius));
+    }
+
+    for (themed, mut font, mut color) in &mut texts {
+        if !all && !themed.is_added() {
+            continue;
+        }
+        font.font_size = theme.font_size(themed.role);
+        color.0 = theme.color(themed.color.as_deref().unwrap_or("text"));
+    }
+}
+
+// loads the built-in themes plus the game's own, and keeps every Themed* widget in sync.
+// needs the AssetPlugin, so add it after DefaultPlugins. edits hot-reload with the `dev`
+// feature, which turns on bevy's file and embedded watchers.
+// UiThemePlugin::default().with_theme("neon", "config/neon.theme.ron").active("neon")
+pub struct UiThemePlugin {
+    pub themes: Vec<(&'static str, &'static str)>,
+    pub active: &'static str,
+}
+
+impl Default for UiThemePlugin {
+    fn default() -> Self {
+        Self {
+            themes: vec![
+                ("default", DEFAULT_THEME),
+                ("high_contrast", HIGH_CONTRAST_THEME),
+            ],
+            active: "default",
+        }
+    }
+}
+
+impl UiThemePlugin {
+    // files under the game's assets/ hot-reload like any other asset
+    pub fn with_theme(mut self, name: &'static str, path: &'static str) -> Self {
+        self.themes.push((name, path));
+        self
+    }
+
+    pub fn active(mut self, name: &'static str) -> Self {
+        self.active = name;
+        self
+    }
+}
+
+impl Plugin for UiThemePlugin {
+    fn build(&self, app: &mut App) {
+        embedded_asset!(app, "themes/default.theme.ron");
+        embedded_asset!(app, "themes/high_contrast.theme.ron");
+
+        let themes = self.themes.clone();
+        let active = self.active.to_string();
+        app.add_plugins(RonAssetPlugin::<UiTheme>::new(&["theme.ron"]))
+            .add_systems(
+                PreStartup,
+                move |mut commands: Commands, server: Res<AssetServer>| {
+                    commands.insert_resource(UiThemes {
+                        themes: themes
+                            .iter()
+                            .map(|(name, path)| (name.to_string(), server.load(*path)))
+                            .collect(),
+                        active: active.clone(),
+                    });
+                },
+            )
+            .add_systems(
+                Update,
+                apply_ui_theme
+                    .run_if(resource_exists::<UiThemes>)
+                    .before(crate::interaction::update_button_visuals),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::color::Luminance;
+
+    fn builtin(src: &str) -> UiTheme {
+        ron::from_str(src).unwrap()
+    }
+
+    fn contrast(a: Color, b: Color) -> f32 {
+        let (la, lb) = (a.luminance(), b.luminance());
+        (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+    }
+
+    #[test]
+    fn builtin_themes_resolve() {
+        for src in [
+            include_str!("themes/default.theme.ron"),
+            include_str!("themes/high_contrast.theme.ron"),
+        ] {
+            let theme = builtin(src);
+            let magenta = Color::srgb(1.0, 0.0, 1.0);
+            for name in theme.buttons.keys() {
+                let style = theme.button_style(name);
+                for c in [style.normal, style.hovered, style.text, style.border] {
+                    assert_ne!(c, magenta, "{} / {name}", theme.name);
+                }
+            }
+            assert_ne!(theme.panel_style("default").background, magenta);
+            // unknown variants fall back instead of failing
+            assert_eq!(theme.button_style("nope"), theme.button_style("primary"));
+        }
+    }
+
+    #[test]
+    fn high_contrast_is_aaa() {
+        let theme = builtin(include_str!("themes/high_contrast.theme.ron"));
+        for name in theme.buttons.keys() {
+            let s = theme.button_style(name);
+            for (text, bg) in [
+                (s.text, s.normal),
+                (s.text, s.hovered),
+                (s.text, s.pressed),
+                (s.text_disabled, s.disabled),
+            ] {
+                let ratio = contrast(text, bg);
+                assert!(ratio >= 7.0, "{name}: {ratio:.1}");
+            }
+        }
+        let panel = theme.panel_style("default").background;
+        for text in ["text", "text_muted"] {
+            assert!(contrast(theme.color(text), panel) >= 7.0, "{text}");
+        }
+    }
+
+    #[test]
+    fn edited_theme_restyles_live_widgets() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<UiTheme>()
+            .add_systems(Update, apply_ui_theme);
+        let handle = app
+            .world_mut()
+            .resource_mut::<Assets<UiTheme>>()
+            .add(builtin(include_str!("themes/default.theme.ron")));
+        app.insert_resource(UiThemes {
+            themes: vec![("default".into(), handle.clone())],
+            active: "default".into(),
+        });
+        let button = app
+            .world_mut()
+            .spawn((UiButton, ThemedButton::default()))
+            .id();
+        let text = app
+            .world_mut()
+            .spawn((Text::new("hi"), ThemedText::new(TextRole::Body)))
+            .id();
+        app.update();
+        app.update();
+
+        let mut assets = app.world_mut().resource_mut::<Assets<UiTheme>>();
+        let theme = assets.get_mut(&handle).unwrap();
+        theme.palette.insert("surface".into(), "#ff0000".into());
+        theme.palette.insert("text".into(), "#00ff00".into());
+        theme.typography.insert(TextRole::Body, 40.0);
+        app.update();
+        app.update();
+
+        let world = app.world();
+        assert_eq!(
+            world.get::<ButtonStyle>(button).unwrap().normal,
+            Color::srgb(1.0, 0.0, 0.0)
+        );
+        assert_eq!(world.get::<TextFont>(text).unwrap().font_size, 40.0);
+        assert_eq!(
+            world.get::<TextColor>(text).unwrap().0,
+            Color::srgb(0.0, 1.0, 0.0)
+        );
+    }
+}
@@ -0,0 +1,267 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::{
+    style::PanelStyle,
+    theme::{ActiveTheme, TextRole, ThemedText},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ToastKind {
+    #[default]
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl ToastKind {
+    // theme palette entry for the border and text
+    pub fn palette(self) -> &'static str {
+        match self {
+            ToastKind::Info => "info",
+            ToastKind::Success => "success",
+            ToastKind::Warning => "accent",
+            ToastKind::Error => "danger",
+        }
+    }
+
+    // same colors as the default theme, for games without UiThemePlugin
+    pub fn color(self) -> Color {
+        match self {
+            ToastKind::Info => Color::srgb(0.30, 0.60, 0.85),
+            ToastKind::Success => Color::srgb(0.30, 0.85, 0.30),
+            ToastKind::Warning => Color::srgb(0.85, 0.30, 0.0),
+            ToastKind::Error => Color::srgb(0.90, 0.30, 0.25),
+        }
+    }
+}
+
+// toasts.write(ToastEvent::success("Bought a factory"))
+#[derive(Message, Debug, Clone, PartialEq)]
+pub struct ToastEvent {
+    pub text: String,
+    pub kind: ToastKind,
+    // seconds on screen once shown
+    pub duration: f32,
+}
+
+impl ToastEvent {
+    pub fn new(kind: ToastKind, text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            kind,
+            duration: 2.5,
+        }
+    }
+
+    pub fn info(text: impl Into<String>) -> Self {
+        Self::new(ToastKind::Info, text)
+    }
+
+    pub fn success(text: impl Into<String>) -> Self {
+        Self::new(ToastKind::Success, text)
+    }
+
+    pub fn warning(text: impl Into<String>) -> Self {
+        Self::new(ToastKind::Warning, text)
+    }
+
+    pub fn error(text: impl Into<String>) -> Self {
+        Self::new(ToastKind::Error, text)
+    }
+
+    pub fn with_duration(mut self, seconds: f32) -> Self {
+        self.duration = seconds;
+        self
+    }
+}
+
+// toasts waiting for a free slot, oldest first
+#[derive(Resource, Debug, Clone)]
+pub struct ToastQueue {
+    pub pending: VecDeque<ToastEvent>,
+    // on screen at once, the rest wait their turn
+    pub max_visible: usize,
+    // waiting ones past this are dropped, oldest first
+    pub max_pending: usize,
+}
+
+impl Default for ToastQueue {
+    fn default() -> Self {
+        Self {
+            pending: VecDeque::new(),
+            max_visible: 3,
+            max_pending: 16,
+        }
+    }
+}
+
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Toast {
+    pub kind: ToastKind,
+    pub remaining: f32,
+    // border color, faded out with the rest
+    pub color: Color,
+}
+
+// column at the bottom of the screen the toasts stack in
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct ToastStack;
+
+// last this many seconds fade out
+const FADE: f32 = 0.3;
+
+pub fn queue_toasts(mut events: MessageReader<ToastEvent>, mut queue: ResMut<ToastQueue>) {
+    for ev in events.read() {
+        // the same message twice in a row is just noise
+        if queue.pending.back() == Some(ev) {
+            continue;
+        }
+        queue.pending.push_back(ev.clone());
+        while queue.pending.len() > queue.max_pending.max(1) {
+            queue.pending.pop_front();
+        }
+    }
+}
+
+pub fn show_toasts(
+    mut commands: Commands,
+    mut queue: ResMut<ToastQueue>,
+    stacks: Query<Entity, With<ToastStack>>,
+    live: Query<(), With<Toast>>,
+    theme: ActiveTheme,
+) {
+    if queue.pending.is_empty() {
+        return;
+    }
+    let stack = match stacks.iter().next() {
+        Some(stack) => stack,
+        None => commands
+            .spawn((
+                ToastStack,
+                Node {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    bottom: Val::Px(24.0),
+                    flex_direction: FlexDirection::ColumnReverse,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(8.0),
+                    ..default()
+                },
+                Pickable::IGNORE,
+                GlobalZIndex(200),
+            ))
+            .id(),
+    };
+
+    let background = theme.get().map_or(PanelStyle::default().background, |t| {
+        t.panel_style("default").background
+    });
+    let mut visible = live.iter().count();
+    while visible < queue.max_visible
+        && let Some(ev) = queue.pending.pop_front()
+    {
+        let color = theme.color(ev.kind.palette(), ev.kind.color());
+        let toast = commands
+            .spawn((
+                Toast {
+                    kind: ev.kind,
+                    remaining: ev.duration,
+                    color,
+                },
+                Node {
+                    padding: UiRect::axes(Val::Px(16.0), Val::Px(8.0)),
+                    border: UiRect::all(Val::Px(2.0)),
+                    ..default()
+                },
+                BackgroundColor(background),
+                BorderColor::all(color),
+                BorderRadius::all(Val::Px(8.0)),
+                Pickable::IGNORE,
+                children![(
+                    Text::new(ev.text),
+                    TextFont::from_font_size(TextRole::Label.default_size()),
+                    TextColor(color),
+                    ThemedText::new(TextRole::Label).colored(ev.kind.palette()),
+                )],
+            ))
+            .id();
+        commands.entity(stack).add_child(toast);
+        visible += 1;
+    }
+}
+
+pub fn expire_toasts(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut toasts: Query<(
+        Entity,
+        &mut Toast,
+        &mut BackgroundColor,
+        &mut BorderColor,
+        &Children,
+    )>,
+    mut texts: Query<&mut TextColor>,
+) {
+    // real time, so toasts still go away while the game is paused
+    let dt = time.delta_secs();
+    for (entity, mut toast, mut bg, mut border, children) in &mut toasts {
+        toast.remaining -= dt;
+        if toast.remaining <= 0.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        if toast.remaining > FADE {
+            continue;
+        }
+        let alpha = toast.remaining / FADE;
+        let faded = bg.0.alpha().min(alpha);
+        bg.0.set_alpha(faded);
+        *border = BorderColor::all(toast.color.with_alpha(alpha));
+        for child in children.iter() {
+            if let Ok(mut color) = texts.get_mut(child) {
+                color.0.set_alpha(alpha);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::UiCommonPlugin;
+    use bevy::time::TimeUpdateStrategy;
+
+    #[test]
+    fn toasts_wait_their_turn_and_expire() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, UiCommonPlugin))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                100,
+            )));
+        app.update();
+
+        for i in 0..5 {
+            let toast = ToastEvent::info(format!("toast {i}")).with_duration(0.5);
+            app.world_mut().write_message(toast.clone());
+            if i == 0 {
+                // repeated straight away, dropped
+                app.world_mut().write_message(toast);
+            }
+        }
+        app.update();
+        let live = |app: &mut App| app.world_mut().query::<&Toast>().iter(app.world()).count();
+        assert_eq!(live(&mut app), 3);
+        assert_eq!(app.world().resource::<ToastQueue>().pending.len(), 2);
+
+        for _ in 0..20 {
+            app.update();
+        }
+        assert_eq!(live(&mut app), 0);
+        assert!(app.world().resource::<ToastQueue>().pending.is_empty());
+    }
+}
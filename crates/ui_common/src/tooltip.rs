@@ -0,0 +1,123 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+
+use crate::{
+    style::PanelStyle,
+    theme::{ActiveTheme, TextRole, ThemedText},
+};
+
+// shown next to the cursor after hovering this node for `delay` seconds.
+// works on any UI node, e.g. (button("BUY"), Tooltip::new("Costs $50"))
+#[derive(Component, Debug, Clone, PartialEq)]
+#[require(Interaction)]
+pub struct Tooltip {
+    pub text: String,
+    pub delay: f32,
+}
+
+impl Tooltip {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            delay: 0.4,
+        }
+    }
+
+    pub fn with_delay(mut self, seconds: f32) -> Self {
+        self.delay = seconds;
+        self
+    }
+}
+
+// the one tooltip box on screen
+#[derive(Component, Debug, Clone, Copy)]
+pub struct TooltipPanel {
+    pub owner: Entity,
+}
+
+// which node the cursor is resting on, and for how long
+#[derive(Resource, Debug, Clone, Copy, Default)]
+pub struct TooltipHover {
+    pub target: Option<Entity>,
+    pub hovered_for: f32,
+}
+
+// below and right of the cursor, clear of the pointer
+const CURSOR_OFFSET: Vec2 = Vec2::new(16.0, 20.0);
+
+pub fn update_tooltips(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut hover: ResMut<TooltipHover>,
+    owners: Query<(Entity, &Interaction, Ref<Tooltip>)>,
+    mut panels: Query<(Entity, &TooltipPanel, &mut Node)>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    theme: ActiveTheme,
+) {
+    let target = owners
+        .iter()
+        .find(|(_, interaction, _)| **interaction == Interaction::Hovered)
+        .map(|(e, ..)| e);
+    if target != hover.target {
+        *hover = TooltipHover {
+            target,
+            hovered_for: 0.0,
+        };
+    } else {
+        hover.hovered_for += time.delta_secs();
+    }
+
+    let shown = target
+        .and_then(|e| owners.get(e).ok())
+        .filter(|(_, _, tip)| hover.hovered_for >= tip.delay && !tip.text.is_empty());
+    let cursor = windows
+        .single()
+        .ok()
+        .and_then(Window::cursor_position)
+        .map(|c| c + CURSOR_OFFSET);
+
+    let mut has_panel = false;
+    for (entity, panel, mut node) in &mut panels {
+        // text changed while it's up (e.g. a cost that just went up): rebuilt below
+        let stale = shown
+            .as_ref()
+            .is_none_or(|(owner, _, tip)| *owner != panel.owner || tip.is_changed());
+        if stale {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        has_panel = true;
+        if let Some(at) = cursor {
+            node.left = Val::Px(at.x);
+            node.top = Val::Px(at.y);
+        }
+    }
+
+    if let (Some((owner, _, tip)), false) = (shown, has_panel) {
+        let at = cursor.unwrap_or_default();
+        let style = theme
+            .get()
+            .map_or(PanelStyle::default(), |t| t.panel_style("default"));
+        commands.spawn((
+            TooltipPanel { owner },
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(at.x),
+                top: Val::Px(at.y),
+                max_width: Val::Px(320.0),
+                padding: UiRect::axes(Val::Px(10.0), Val::Px(6.0)),
+                border: UiRect::all(Val::Px(1.0)),
+                ..default()
+            },
+            BackgroundColor(style.background),
+            BorderColor::all(theme.color("border", Color::srgb(0.85, 0.85, 0.85))),
+            BorderRadius::all(Val::Px(4.0)),
+            Pickable::IGNORE,
+            GlobalZIndex(300),
+            children![(
+                Text::new(tip.text.clone()),
+                TextFont::from_font_size(TextRole::Small.default_size()),
+                ThemedText::new(TextRole::Small),
+            )],
+        ));
+    }
+}
@@ -0,0 +1,121 @@
+use bevy::prelude::*;
+
+use crate::{
+    style::{ButtonStyle, LABEL_COLOR, PanelStyle},
+    theme::{TextRole, ThemedButton, ThemedPanel, ThemedText},
+};
+
+// a clickable button, sends ButtonClicked when pressed (unless Disabled)
+#[derive(Component, Debug, Clone, Copy, Default)]
+#[require(Button, ButtonStyle)]
+pub struct UiButton;
+
+// greys the button out and swallows clicks
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct Disabled;
+
+// keyboard / gamepad focus, drawn like a hover
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct Focused;
+
+// drawn pressed while idle, e.g. the chosen row of a list
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct Selected;
+
+// the Text child of a UiButton, to relabel it
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct ButtonText;
+
+pub fn button(text: impl Into<String>) -> impl Bundle {
+    themed_button(text, "primary")
+}
+
+// styled by the active theme's `variant` ("accent", "danger", ...) once UiThemePlugin is in
+pub fn themed_button(text: impl Into<String>, variant: impl Into<String>) -> impl Bundle {
+    (
+        button_with(text, ButtonStyle::default()),
+        ThemedButton(variant.into()),
+    )
+}
+
+// commands.spawn((button_with("BUY", ButtonStyle::accent(GREEN)), BtnBuy))
+pub fn button_with(text: impl Into<String>, style: ButtonStyle) -> impl Bundle {
+    (
+        UiButton,
+        Node {
+            width: Val::Px(320.0),
+            height: Val::Px(60.0),
+            border: UiRect::all(Val::Px(3.0)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        BorderColor::all(style.border),
+        BorderRadius::all(Val::Px(8.0)),
+        BackgroundColor(style.normal),
+        style,
+        children![(
+            ButtonText,
+            Text::new(text),
+            TextFont {
+                font_size: style.font_size,
+                ..default()
+            },
+            TextColor(style.text),
+        )],
+    )
+}
+
+pub fn label(text: impl Into<String>, font_size: f32) -> impl Bundle {
+    label_with(text, font_size, LABEL_COLOR)
+}
+
+// sized and colored from the theme's type scale and palette
+pub fn themed_label(text: impl Into<String>, role: TextRole) -> impl Bundle {
+    (label(text, role.default_size()), ThemedText::new(role))
+}
+
+pub fn label_with(text: impl Into<String>, font_size: f32, color: Color) -> impl Bundle {
+    (
+        Text::new(text),
+        TextFont {
+            font_size,
+            ..default()
+        },
+        TextColor(color),
+    )
+}
+
+// vertical box, children are stacked with `gap` between them
+pub fn panel() -> impl Bundle {
+    (panel_with(PanelStyle::default()), ThemedPanel::default())
+}
+
+pub fn panel_with(style: PanelStyle) -> impl Bundle {
+    (
+        Node {
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            padding: UiRect::all(Val::Px(style.padding)),
+            row_gap: Val::Px(style.gap),
+            border: UiRect::all(Val::Px(2.0)),
+            ..default()
+        },
+        BackgroundColor(style.background),
+        BorderColor::all(style.border),
+        BorderRadius::all(Val::Px(style.radius)),
+    )
+}
+
+// full-window layer that centers its children, e.g. for a panel
+pub fn screen_root() -> impl Bundle {
+    Node {
+        position_type: PositionType::Absolute,
+        width: Val::Percent(100.0),
+        height: Val::Percent(100.0),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    }
+}
@@ -6,10 +6,7 @@ fn main() {
         .add_plugins(DefaultPlugins)
         .add_plugins(CorePlugin)
         .insert_resource(Gravity(Vec2::new(0.0, -900.0)))
-        .insert_resource(Bounds {
-            half_w: 480.0,
-            half_h: 270.0,
-        })
+        .insert_resource(ArenaBounds::new(480.0, 270.0))
         .add_systems(Startup, (setup_camera, spawn_balls))
         .add_systems(Update, (apply_gravity, bounce_off_walls).chain())
         .run();
@@ -18,12 +15,6 @@ fn main() {
 #[derive(Resource)]
 struct Gravity(Vec2);
 
-#[derive(Resource)]
-struct Bounds {
-    half_w: f32,
-    half_h: f32,
-}
-
 #[derive(Component)]
 struct Ball;
 
@@ -65,7 +56,7 @@ fn apply_gravity(mut q: Query<&mut Velocity, With<Ball>>, g: Res<Gravity>, time:
 }
 
 fn bounce_off_walls(
-    bounds: Res<Bounds>,
+    bounds: Res<ArenaBounds>,
     mut q: Query<(&mut Transform, &mut Velocity, &CircleCollider), With<Ball>>,
 ) {
     for (mut t, mut v, c) in &mut q {
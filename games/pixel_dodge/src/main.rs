@@ -4,33 +4,39 @@ use bevy::{
     color::palettes::tailwind::{BLUE_300, RED_300},
     prelude::*,
 };
-use core_engine::prelude::{CircleCollider, CorePlugin, Health, Lifetime, Velocity};
+use core_engine::prelude::{
+    ArenaBounds, CircleCollider, CorePlugin, Health, Lifetime, StatId, Stats, Velocity,
+};
+use ui_common::prelude::*;
 
-const HALF_W: f32 = 480.0;
-const HALF_H: f32 = 270.0;
 fn main() {
     App::new()
-        .add_plugins(DefaultPlugins)
+        .add_plugins(DefaultPlugins.set(AssetPlugin {
+            watch_for_changes_override: Some(cfg!(feature = "dev")),
+            ..default()
+        }))
         .add_plugins(CorePlugin)
-        .insert_resource(Bounds {
-            half_w: HALF_W,
-            half_h: HALF_H,
-        })
+        .add_plugins(MenuPlugin::new("PIXEL DODGE").with_subtitle("dodge the falling blocks"))
+        .add_plugins(UiThemePlugin::default())
+        .insert_resource(ArenaBounds::new(480.0, 270.0))
         .insert_resource(SpawnTuning::default())
+        .init_resource::<Survived>()
         .add_systems(Startup, (setup_camera, spawn_player))
         .add_systems(
             Update,
-            (player_input, clamp_player, spawn_hazards, end_if_collision),
+            (
+                player_input,
+                clamp_player,
+                spawn_hazards,
+                tick_survived,
+                end_if_collision,
+            )
+                .run_if(menu_closed),
         )
+        .add_systems(Update, reset_run)
         .run();
 }
 
-#[derive(Resource)]
-struct Bounds {
-    half_w: f32,
-    half_h: f32,
-}
-
 #[derive(Resource)]
 struct SpawnTuning {
     timer: Timer,
@@ -46,6 +52,10 @@ impl Default for SpawnTuning {
     }
 }
 
+// seconds alive this run
+#[derive(Resource, Default)]
+struct Survived(f32);
+
 #[derive(Component)]
 struct Player;
 #[derive(Component)]
@@ -54,7 +64,7 @@ struct Hazard;
 fn setup_camera(mut commands: Commands) {
     commands.spawn(Camera2d);
 }
-fn spawn_player(mut commands: Commands) {
+fn spawn_player(mut commands: Commands, bounds: Res<ArenaBounds>) {
     commands.spawn((
         Sprite {
             custom_size: Some(Vec2::splat(24.0)),
@@ -63,15 +73,19 @@ fn spawn_player(mut commands: Commands) {
             ..default()
         },
         Health::new(1.0),
+        Stats::new().with(StatId::MoveSpeed, 300.0),
         Velocity::with_drag(Vec2::ZERO, 0.8),
         CircleCollider::new(12.0),
-        Transform::from_xyz(0.0, -HALF_H, 0.0),
+        Transform::from_xyz(0.0, -bounds.half_h, 0.0),
         Player,
     ));
 }
 
-fn player_input(kb: Res<ButtonInput<KeyCode>>, mut q: Query<&mut Velocity, With<Player>>) {
-    if let Ok(mut v) = q.single_mut() {
+fn player_input(
+    kb: Res<ButtonInput<KeyCode>>,
+    mut q: Query<(&mut Velocity, &Stats), With<Player>>,
+) {
+    if let Ok((mut v, stats)) = q.single_mut() {
         let mut dir = Vec2::ZERO;
 
         if kb.pressed(KeyCode::KeyA) || kb.pressed(KeyCode::ArrowLeft) {
@@ -82,7 +96,7 @@ fn player_input(kb: Res<ButtonInput<KeyCode>>, mut q: Query<&mut Velocity, With<
             dir.x += 1.0;
         }
 
-        let speed = 300.0;
+        let speed = stats.get_or(StatId::MoveSpeed, 300.0);
 
         // v.lin_vel = if dir == Vec2::ZERO {
         //     Vec2::ZERO
@@ -97,7 +111,7 @@ fn player_input(kb: Res<ButtonInput<KeyCode>>, mut q: Query<&mut Velocity, With<
     }
 }
 fn clamp_player(
-    bounds: Res<Bounds>,
+    bounds: Res<ArenaBounds>,
     mut q: Query<(&mut Transform, &mut Velocity, &CircleCollider), With<Player>>,
 ) {
     for (mut t, mut v, c) in q.iter_mut() {
@@ -118,7 +132,7 @@ fn clamp_player(
 fn spawn_hazards(
     mut commands: Commands,
     time: Res<Time>,
-    bounds: Res<Bounds>,
+    bounds: Res<ArenaBounds>,
     mut tune: ResMut<SpawnTuning>,
 ) {
     // speeding up spawn over time, min 0.25s
@@ -151,9 +165,16 @@ fn spawn_hazards(
     }
 }
 
+fn tick_survived(time: Res<Time>, mut survived: ResMut<Survived>) {
+    survived.0 += time.delta_secs();
+}
+
 fn end_if_collision(
     player: Query<(&Transform, &CircleCollider), With<Player>>,
     hazards: Query<(&Transform, &CircleCollider), With<Hazard>>,
+    survived: Res<Survived>,
+    mut summary: ResMut<GameOverSummary>,
+    mut menu: ResMut<MenuStack>,
 ) {
     // not going to abbreviate, it's fucking confusing
     if let Ok((player_transform, player_circle)) = player.single() {
@@ -162,8 +183,37 @@ fn end_if_collision(
             let d2 = player_position.distance_squared(hazard_transform.translation.truncate());
             let r = player_circle.radius + hazard_circle.radius;
             if d2 <= r * r {
-                info!("GAME OVER");
+                summary.0 = vec![format!("You lasted {:.1}s", survived.0)];
+                menu.push(MenuScreen::GameOver);
+                return;
             }
         }
     }
 }
+
+// Play from the title or Retry after a game over starts a fresh run
+fn reset_run(
+    mut commands: Commands,
+    mut actions: MessageReader<MenuActionEvent>,
+    bounds: Res<ArenaBounds>,
+    hazards: Query<Entity, With<Hazard>>,
+    mut player: Query<(&mut Transform, &mut Velocity), With<Player>>,
+    mut tune: ResMut<SpawnTuning>,
+    mut survived: ResMut<Survived>,
+) {
+    if !actions
+        .read()
+        .any(|a| matches!(a.action, MenuAction::Play | MenuAction::Retry))
+    {
+        return;
+    }
+    for e in &hazards {
+        commands.entity(e).despawn();
+    }
+    for (mut t, mut v) in &mut player {
+        t.translation = Vec3::new(0.0, -bounds.half_h, 0.0);
+        v.lin_vel = Vec2::ZERO;
+    }
+    *tune = SpawnTuning::default();
+    survived.0 = 0.0;
+}
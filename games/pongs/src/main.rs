@@ -1,9 +1,10 @@
 use bevy::math::bounding::{Aabb2d, BoundingVolume, IntersectsVolume};
 use bevy::prelude::*;
+use ui_common::prelude::*;
 
 fn main() {
     App::new()
-        .add_plugins(DefaultPlugins)
+        .add_plugins((DefaultPlugins, UiCommonPlugin))
         .insert_resource(Score { player: 0, ai: 0 })
         .add_systems(
             Startup,
@@ -25,7 +26,6 @@ fn main() {
                 handle_player_input.before(move_paddles),
                 constrain_paddle_position.after(move_paddles),
                 detect_goal.after(move_ball),
-                update_scoreboard,
                 move_ai,
             ),
         )
@@ -357,7 +357,7 @@ fn constrain_paddle_position(
     }
 }
 
-#[derive(Resource)]
+#[derive(Resource, Reflect)]
 struct Score {
     player: u32,
     ai: u32,
@@ -414,12 +414,6 @@ fn update_score(
     }
 }
 
-#[derive(Component)]
-struct PlayerScore;
-
-#[derive(Component)]
-struct AiScore;
-
 fn spawn_scoreboard(mut commands: Commands) {
     let container = Node {
         width: percent(100.0),
@@ -437,8 +431,8 @@ fn spawn_scoreboard(mut commands: Commands) {
 
     // The players score on the left hand side
     let player_score = (
-        PlayerScore,
         Text::new("0"),
+        TextBinding::resource::<Score>("{player}"),
         TextFont::from_font_size(72.0),
         TextColor(Color::WHITE),
         TextLayout::new_with_justify(Justify::Center),
@@ -452,8 +446,8 @@ fn spawn_scoreboard(mut commands: Commands) {
 
     // The AI score on the right hand side
     let ai_score = (
-        AiScore,
         Text::new("0"),
+        TextBinding::resource::<Score>("{ai}"),
         TextFont::from_font_size(72.0),
         TextColor(Color::WHITE),
         TextLayout::new_with_justify(Justify::Center),
@@ -471,17 +465,6 @@ fn spawn_scoreboard(mut commands: Commands) {
     ));
 }
 
-fn update_scoreboard(
-    mut player_score: Single<&mut Text, (With<PlayerScore>, Without<AiScore>)>,
-    mut ai_score: Single<&mut Text, (With<AiScore>, Without<PlayerScore>)>,
-    score: Res<Score>,
-) {
-    if score.is_changed() {
-        player_score.0 = score.player.to_string();
-        ai_score.0 = score.ai.to_string();
-    }
-}
-
 fn move_ai(ai: Single<(&mut Velocity, &Position), With<Ai>>, ball: Single<&Position, With<Ball>>) {
     let (mut velocity, position) = ai.into_inner();
     let a_to_b = ball.0 - position.0;
@@ -503,7 +486,7 @@ use bevy_rapier2d::prelude::*;
 
 fn main_with_rapier() {
     App::new()
-        .add_plugins(DefaultPlugins)
+        .add_plugins((DefaultPlugins, UiCommonPlugin))
         .add_plugins(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(100.0))
         // .add_plugins(RapierDebugRenderPlugin::default())  // Optional: visual debugging
         .insert_resource(Score { player: 0, ai: 0 })
@@ -524,7 +507,6 @@ fn main_with_rapier() {
                 // Rapier does this automatically!
                 handle_player_input_rapier,
                 detect_goal,  // Keep our custom goal detection
-                update_scoreboard,
                 move_ai_rapier,
             ),
         )
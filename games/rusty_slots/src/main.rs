@@ -1,8 +1,23 @@
-use bevy::prelude::*;
+use core_engine::prelude::*;
+use ui_common::prelude::*;
 
 fn main() {
     App::new()
-        .add_plugins(DefaultPlugins)
+        .add_plugins((
+            DefaultPlugins.set(AssetPlugin {
+                watch_for_changes_override: Some(cfg!(feature = "dev")),
+                ..default()
+            }),
+            UiCommonPlugin,
+            UiThemePlugin::default(),
+        ))
+        .add_plugins(TweenPlugin)
+        .add_plugins(SoundPlugin {
+            cues: "config/slots.sounds.ron",
+            settings: Some("settings/audio.ron"),
+            backend: SoundBackend::Bevy,
+        })
+        .register_type::<ReelOffset>()
         // .init_resource::<InputFocus>()
         .insert_resource(Credits(100))
         .insert_resource(Bet(5))
@@ -10,20 +25,26 @@ fn main() {
         .add_systems(Startup, (setup_camera, spawn_ui, spawn_reels))
         .add_systems(
             Update,
-            (spin_button, drive_spinning, ease_to_stop, settle_and_payout),
+            (
+                spin_button,
+                drive_spinning,
+                ease_to_stop,
+                settle_and_payout,
+                warn_no_credits.before(spin_button),
+            ),
         )
         .add_systems(
             Update,
-            update_ui.after(spin_button).after(settle_and_payout),
+            sync_spin_button.after(spin_button).after(settle_and_payout),
         )
         .run();
 }
 
 // ======================== Resource =========================
 
-#[derive(Resource)]
+#[derive(Resource, Reflect)]
 struct Credits(pub i32);
-#[derive(Resource)]
+#[derive(Resource, Reflect)]
 struct Bet(pub i32);
 #[derive(Resource)]
 struct SpinLock(pub bool); // to disable button when spinning
@@ -38,29 +59,37 @@ struct Reel {
 struct SpinTimer(Option<Timer>);
 #[derive(Component)]
 struct ReelSpeed(pub f32);
-#[derive(Component)]
+// rows scrolled, tweened by name when the reel eases to a stop
+#[derive(Component, Reflect)]
+#[reflect(Component)]
 struct ReelOffset(pub f32);
 #[derive(Component)]
 struct Stopping {
-    target: i32,
-    ease: Timer,
+    target: usize, // row the reel lands on
 }
 
 #[derive(Component)]
 struct BtnSpin;
 #[derive(Component)]
-struct UiCredits;
-#[derive(Component)]
-struct UiBet;
-#[derive(Component)]
 struct UiResult;
 
 fn setup_camera(mut commands: Commands) {
     commands.spawn(Camera2d);
 }
-fn spawn_ui(mut commands: Commands, credits: Res<Credits>, bet: Res<Bet>) {
-    // spin button
-    commands.spawn(button(credits, bet));
+fn spawn_ui(mut commands: Commands) {
+    commands.spawn(screen_root()).with_children(|root| {
+        root.spawn(panel()).with_children(|parent| {
+            parent.spawn((
+                themed_label("", TextRole::Body),
+                TextBinding::resource::<Credits>("Credits: {0}"),
+            ));
+            parent.spawn((
+                themed_label("", TextRole::Body),
+                TextBinding::resource::<Bet>("Bet: {0}"),
+            ));
+            parent.spawn((button("SPIN"), BtnSpin));
+        });
+    });
 }
 
 fn spawn_reels(mut commands: Commands) {
@@ -88,43 +117,26 @@ fn spawn_reels(mut commands: Commands) {
 }
 
 fn spin_button(
-    q_btn: Query<(&Interaction, &Children), (Changed<Interaction>, With<BtnSpin>)>,
-    mut q_text: Query<&mut Text>,
+    mut clicks: MessageReader<ButtonClicked>,
+    q_btn: Query<(), With<BtnSpin>>,
     mut reels: Query<(&mut SpinTimer, &mut ReelOffset, &mut ReelSpeed, &Reel)>,
     mut lock: ResMut<SpinLock>,
     mut credits: ResMut<Credits>,
     bet: Res<Bet>,
+    mut sfx: MessageWriter<PlayCueEvent>,
 ) {
-    for (interaction, children) in &q_btn {
-        // Update button text based on interaction state
-        for child in children.iter() {
-            if let Ok(mut text) = q_text.get_mut(child) {
-                match *interaction {
-                    Interaction::Hovered => continue,
-                    Interaction::Pressed => text.0 = "GOOD LUCK!".to_string(),
-                    Interaction::None => text.0 = "SPIN".to_string(),
-                }
-            }
-        }
-
-        // Handle spin logic only on press
-        if *interaction != Interaction::Pressed {
-            continue;
-        }
-
-        println!(
-            "Button pressed! Lock: {}, Credits: {}, Bet: {}",
-            lock.0, credits.0, bet.0
-        );
-
+    for _ in clicks.read().filter(|c| q_btn.contains(c.entity)) {
+        // disabled while spinning, this also guards double clicks in one frame
         if lock.0 || credits.0 < bet.0 {
-            println!("Blocked: lock={} or insufficient credits", lock.0);
             return;
         }
 
         // lock and pay bet
         lock.0 = true;
         credits.0 -= bet.0;
+        sfx.write(PlayCueEvent {
+            cue: "spin".to_string(),
+        });
 
         for (mut t, mut off, mut spd, _r) in &mut reels {
             *off = ReelOffset(0.0);
@@ -144,21 +156,91 @@ fn drive_spinning(
             continue;
         }
 
-        if let Some(t) = timer.0.as_mut() {
+        // once the timer is done ease_to_stop takes over
+        if let Some(t) = timer.0.as_mut()
+            && !t.is_finished()
+        {
             t.tick(time.delta());
 
             let rows_per_sec = 12.0;
             off.0 = (off.0 + rows_per_sec * time.delta_secs()) % reel.rows as f32;
+        }
+    }
+}
+// where the stop tween ends: past the next whole turn, then at least one more
+// full turn, landing on `target`
+fn stop_offset(from: f32, rows: usize, target: usize) -> f32 {
+    let rows = rows as f32;
+    (from / rows).ceil() * rows + rows + target as f32
+}
 
-            // Clear timer when finished
-            if t.is_finished() {
-                timer.0 = None;
-                println!("Reel finished spinning");
-            }
+fn ease_to_stop(
+    mut commands: Commands,
+    mut stopped: MessageReader<TweenCompletedEvent>,
+    mut q: Query<(
+        Entity,
+        &mut SpinTimer,
+        &mut ReelOffset,
+        &Reel,
+        Option<&Stopping>,
+    )>,
+) {
+    use rand::Rng;
+    let mut rng = rand::rng();
+
+    for (entity, timer, off, reel, stopping) in &mut q {
+        let finished = timer.0.as_ref().is_some_and(|t| t.is_finished());
+        if !finished || stopping.is_some() {
+            continue;
+        }
+        // later reels take longer to land
+        let target = rng.random_range(0..reel.rows);
+        let to = stop_offset(off.0, reel.rows, target);
+        commands.entity(entity).insert((
+            Stopping { target },
+            Tween::new(TweenStep::lerp(
+                TweenLens::field::<ReelOffset>("0", off.0, to),
+                0.8 + reel.idx as f32 * 0.35,
+                EaseFunction::BackOut,
+            ))
+            .with_tag("reel_stop"),
+        ));
+    }
+
+    for ev in stopped.read() {
+        if ev.tag.as_deref() != Some("reel_stop") {
+            continue;
         }
+        let Ok((entity, mut timer, mut off, reel, Some(stopping))) = q.get_mut(ev.entity) else {
+            continue;
+        };
+        // back into [0, rows), which is the target row again
+        off.0 = off.0.round() % reel.rows as f32;
+        timer.0 = None;
+        // little thump when it lands
+        let bump = TweenStep::lerp(
+            TweenLens::Scale {
+                from: Vec3::ONE,
+                to: Vec3::splat(1.08),
+            },
+            0.08,
+            EaseFunction::QuadraticOut,
+        )
+        .then(TweenStep::lerp(
+            TweenLens::Scale {
+                from: Vec3::splat(1.08),
+                to: Vec3::ONE,
+            },
+            0.15,
+            EaseFunction::QuadraticIn,
+        ));
+        commands
+            .entity(entity)
+            .remove::<Stopping>()
+            .insert(Tween::new(bump));
+        debug!("reel {} stopped on row {}", ev.entity, stopping.target);
     }
 }
-fn ease_to_stop() {}
 fn settle_and_payout(mut lock: ResMut<SpinLock>, q_timers: Query<&SpinTimer>) {
     // Check if any reels are still spinning
     let any_spinning = q_timers.iter().any(|timer| timer.0.is_some());
@@ -166,118 +248,72 @@ fn settle_and_payout(mut lock: ResMut<SpinLock>, q_timers: Query<&SpinTimer>) {
     // If no reels are spinning and we're locked, unlock
     if !any_spinning && lock.0 {
         lock.0 = false;
-        println!("Spin complete - unlocked!");
     }
 }
-fn update_ui(
+// greys the button out while the reels spin, says so when the bet can't be paid
+fn sync_spin_button(
+    mut commands: Commands,
+    lock: Res<SpinLock>,
     credits: Res<Credits>,
     bet: Res<Bet>,
-    mut queries: ParamSet<(
-        Query<&mut Text, With<UiCredits>>,
-        Query<&mut Text, With<UiBet>>,
-    )>,
+    q_btn: Query<(Entity, &Children), With<BtnSpin>>,
+    mut q_text: Query<&mut Text, With<ButtonText>>,
 ) {
-    // Only update if credits changed
-    if credits.is_changed() {
-        if let Ok(mut t) = queries.p0().single_mut() {
-            println!("Updating UI text to: Credits: {}", credits.0);
-            t.0 = format!("Credits: {}", credits.0);
+    if !lock.is_changed() && !credits.is_changed() && !bet.is_changed() {
+        return;
+    }
+    let label = if lock.0 {
+        "GOOD LUCK!"
+    } else if credits.0 < bet.0 {
+        "NO CREDITS"
+    } else {
+        "SPIN"
+    };
+    for (entity, children) in &q_btn {
+        if lock.0 {
+            commands.entity(entity).insert(Disabled);
         } else {
-            println!("Failed to get credits text query!");
+            commands.entity(entity).remove::<Disabled>();
+        }
+        for child in children.iter() {
+            if let Ok(mut text) = q_text.get_mut(child) {
+                text.0 = label.to_string();
+            }
         }
     }
-    // Only update if bet changed
-    if bet.is_changed() {
-        if let Ok(mut t) = queries.p1().single_mut() {
-            t.0 = format!("Bet: {}", bet.0);
+}
+
+// the button stays clickable when broke, so a click says why nothing spins
+fn warn_no_credits(
+    mut clicks: MessageReader<ButtonClicked>,
+    q_btn: Query<(), With<BtnSpin>>,
+    lock: Res<SpinLock>,
+    credits: Res<Credits>,
+    bet: Res<Bet>,
+    mut toasts: MessageWriter<ToastEvent>,
+) {
+    for _ in clicks.read().filter(|c| q_btn.contains(c.entity)) {
+        if !lock.0 && credits.0 < bet.0 {
+            toasts.write(ToastEvent::warning(format!(
+                "Not enough credits for a {} bet",
+                bet.0
+            )));
         }
     }
 }
 
-fn button(credits: Res<Credits>, bet: Res<Bet>) -> impl Bundle {
-    let init_credits = format!("Credits: {}", credits.0);
-    let init_bet = format!("Bet: {}", bet.0);
-    (
-        Node {
-            width: percent(100),
-            height: percent(100),
-            align_items: AlignItems::Center,
-            justify_content: JustifyContent::Center,
-            ..default()
-        },
-        children![
-            (
-                Button,
-                BtnSpin,
-                Node {
-                    width: px(150),
-                    height: px(65),
-                    border: UiRect::all(px(5)),
-                    // horizontally center child text
-                    justify_content: JustifyContent::Center,
-                    // vertically center child text
-                    align_items: AlignItems::Center,
-                    ..default()
-                },
-                BorderColor::all(Color::WHITE),
-                BorderRadius::MAX,
-                BackgroundColor(Color::BLACK),
-                children![(
-                    Text::new("SPIN"),
-                    TextFont {
-                        font_size: 33.0,
-                        ..default()
-                    },
-                    TextColor(Color::srgb(0.9, 0.9, 0.9)),
-                    TextShadow::default(),
-                )]
-            ),
-            (
-                Node {
-                    width: px(150),
-                    height: px(65),
-                    border: UiRect::all(px(5)),
-                    justify_content: JustifyContent::Center,
-                    align_items: AlignItems::Center,
-                    ..default()
-                },
-                BorderColor::all(Color::WHITE),
-                BorderRadius::MAX,
-                BackgroundColor(Color::BLACK),
-                children![(
-                    UiCredits,
-                    Text::new(&init_credits),
-                    TextFont {
-                        font_size: 33.0,
-                        ..default()
-                    },
-                    TextColor(Color::srgb(0.9, 0.9, 0.9)),
-                    TextShadow::default(),
-                )]
-            ),
-            (
-                Node {
-                    width: px(150),
-                    height: px(65),
-                    border: UiRect::all(px(5)),
-                    justify_content: JustifyContent::Center,
-                    align_items: AlignItems::Center,
-                    ..default()
-                },
-                BorderColor::all(Color::WHITE),
-                BorderRadius::MAX,
-                BackgroundColor(Color::BLACK),
-                children![(
-                    UiBet,
-                    Text::new(&init_bet),
-                    TextFont {
-                        font_size: 33.0,
-                        ..default()
-                    },
-                    TextColor(Color::srgb(0.9, 0.9, 0.9)),
-                    TextShadow::default(),
-                )]
-            )
-        ],
-    )
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stops_land_on_the_target_row() {
+        for from in [0.0, 0.4, 2.7, 4.99] {
+            for target in 0..5 {
+                let to = stop_offset(from, 5, target);
+                assert_eq!(to % 5.0, target as f32, "{from} -> {target}");
+                assert!(to - from >= 5.0);
+            }
+        }
+    }
 }
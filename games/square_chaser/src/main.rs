@@ -2,6 +2,7 @@ use bevy::asset::AssetPlugin;
 use bevy_common_assets::ron::RonAssetPlugin;
 use bevy_inspector_egui::{bevy_egui::EguiPlugin, quick::WorldInspectorPlugin};
 use core_engine::prelude::*;
+use ui_common::prelude::*;
 
 mod config;
 use config::PlayerConfig;
@@ -14,6 +15,9 @@ fn main() {
             ..default()
         }))
         .add_plugins(CorePlugin) // movement, lifetime, damage,
+        .add_plugins(MenuPlugin::new("SQUARE CHASER").with_game_over_title("ROUND OVER"))
+        .add_plugins(UiThemePlugin::default())
+        .add_plugins(SpriteAnimationPlugin)
         .add_plugins(RonAssetPlugin::<PlayerConfig>::new(&["player.ron"]))
         .register_type::<PlayerConfig>() // for inspector later if you want
         .add_plugins(EguiPlugin::default())
@@ -23,19 +27,23 @@ fn main() {
         .insert_resource(ColliderDebug::default())
         .insert_resource(Score::default())
         .insert_resource(RoundTimer { time_left: 60.0 })
+        .insert_resource(ArenaBounds::new(480.0, 270.0))
         .add_systems(Startup, (setup_camera, spawn_ui, load_player_cfg))
         .add_systems(
             Update,
             (
                 draw_colliders,
-                player_input,
-                clamp_bounds,
-                spawn_target_periodically,
-                collect_targets,
-                update_hud,
-                tick_round,
+                (
+                    player_input,
+                    clamp_bounds,
+                    spawn_target_periodically,
+                    collect_targets,
+                    tick_round,
+                )
+                    .run_if(menu_closed),
                 maybe_spawn_player,
                 react_to_player_cfg_changes,
+                reset_round,
             ),
         )
         .run();
@@ -49,13 +57,10 @@ fn load_player_cfg(mut commands: Commands, server: Res<AssetServer>) {
     println!("Asset handle: {:?}", h.id());
     commands.insert_resource(PlayerCfgHandle(h));
 }
-const ARENA_HALF_W: f32 = 480.0;
-const ARENA_HALF_H: f32 = 270.0;
-
-#[derive(Default, Resource)]
+#[derive(Default, Resource, Reflect)]
 struct Score(u32);
 
-#[derive(Resource)]
+#[derive(Resource, Reflect)]
 struct RoundTimer {
     time_left: f32,
 }
@@ -64,8 +69,6 @@ struct RoundTimer {
 struct Player;
 #[derive(Component)]
 struct Target;
-#[derive(Component)]
-struct HudText;
 
 pub fn setup_camera(mut commands: Commands) {
     commands.spawn(Camera2d);
@@ -101,7 +104,14 @@ pub fn spawn_ui(mut commands: Commands) {
             font_size: 60.0,
             ..Default::default()
         },
-        HudText,
+        TextBinding::resource::<Score>("Score: {0} \n"),
+        children![(
+            TextSpan::default(),
+            TextFont::from_font_size(60.0),
+            TextBinding::resource_with::<RoundTimer>(|round| {
+                format!(" Time: {}", round.time_left.max(0.0).floor() as i32)
+            }),
+        )],
     ));
 }
 
@@ -140,10 +150,11 @@ fn player_input(
         }
     }
 }
-fn clamp_bounds(mut q: Query<&mut Transform, With<Player>>) {
+fn clamp_bounds(bounds: Res<ArenaBounds>, mut q: Query<&mut Transform, With<Player>>) {
     if let Ok(mut t) = q.single_mut() {
-        t.translation.x = t.translation.x.clamp(-ARENA_HALF_W, ARENA_HALF_W);
-        t.translation.y = t.translation.y.clamp(-ARENA_HALF_H, ARENA_HALF_H);
+        let p = bounds.clamp(t.translation.truncate(), 0.0);
+        t.translation.x = p.x;
+        t.translation.y = p.y;
     }
 }
 
@@ -154,6 +165,7 @@ fn spawn_target_periodically(
     time: Res<Time>,
     mut clock: Local<SpawnClock>,
     existing: Query<Entity, With<Target>>,
+    bounds: Res<ArenaBounds>,
 ) {
     if clock.0.duration().is_zero() {
         clock.0 = Timer::from_seconds(2.0, TimerMode::Repeating);
@@ -163,8 +175,8 @@ fn spawn_target_periodically(
     if clock.0.just_finished() && existing.is_empty() {
         use rand::Rng;
         let mut rng = rand::rng();
-        let x = rng.random_range(-ARENA_HALF_W + 20.0..ARENA_HALF_W - 20.0);
-        let y = rng.random_range(-ARENA_HALF_H + 20.0..ARENA_HALF_H - 20.0);
+        let x = rng.random_range(-bounds.half_w + 20.0..bounds.half_w - 20.0);
+        let y = rng.random_range(-bounds.half_h + 20.0..bounds.half_h - 20.0);
 
         commands.spawn((
             Target,
@@ -198,21 +210,44 @@ fn collect_targets(
         }
     }
 }
-fn update_hud(score: Res<Score>, round: Res<RoundTimer>, mut q: Query<&mut Text, With<HudText>>) {
-    if !score.is_changed() && !round.is_changed() {
-        return;
-    }
-
-    if let Ok(mut text) = q.single_mut() {
-        let secs = round.time_left.max(0.0).floor() as i32;
-        text.0 = format!("Score: {} \n Time: {}", score.0, secs);
-    }
-}
-fn tick_round(time: Res<Time>, mut round: ResMut<RoundTimer>) {
+fn tick_round(
+    time: Res<Time>,
+    score: Res<Score>,
+    mut round: ResMut<RoundTimer>,
+    mut summary: ResMut<GameOverSummary>,
+    mut menu: ResMut<MenuStack>,
+) {
     round.time_left -= time.delta_secs();
     if round.time_left <= 0.0 {
         round.time_left = 0.0;
-        info!("Round over.") // TODO: state change
+        summary.0 = vec![format!("Score: {}", score.0)];
+        menu.push(MenuScreen::GameOver);
+    }
+}
+
+// Play / Retry: fresh score and clock, player back in the middle
+fn reset_round(
+    mut commands: Commands,
+    mut actions: MessageReader<MenuActionEvent>,
+    mut score: ResMut<Score>,
+    mut round: ResMut<RoundTimer>,
+    targets: Query<Entity, With<Target>>,
+    mut player: Query<(&mut Transform, &mut Velocity), With<Player>>,
+) {
+    if !actions
+        .read()
+        .any(|a| matches!(a.action, MenuAction::Play | MenuAction::Retry))
+    {
+        return;
+    }
+    score.0 = 0;
+    round.time_left = 60.0;
+    for e in &targets {
+        commands.entity(e).despawn();
+    }
+    for (mut t, mut v) in &mut player {
+        t.translation = Vec3::ZERO;
+        v.lin_vel = Vec2::ZERO;
     }
 }
 
@@ -259,6 +294,7 @@ fn maybe_spawn_player(
         let image = assets.load("cat.png");
         commands.spawn((
             Player,
+            SpriteAnimator::new(assets.load("config/cat.anim.ron")),
             Health::new(1.0),
             Velocity::default(),
             CircleCollider::new(cfg.collider_radius),
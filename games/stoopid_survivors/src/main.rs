@@ -1,3 +1,230 @@
+use bevy::color::palettes::tailwind::{AMBER_300, EMERALD_400};
+use core_engine::prelude::*;
+use ui_common::prelude::*;
+
 fn main() {
-    println!("Hello, world!");
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins((CorePlugin, CameraPlugin, UiCommonPlugin, CombatUiPlugin))
+        // twice the screen, the camera follows the player around
+        .insert_resource(ArenaBounds::new(960.0, 540.0))
+        .add_plugins(XpPlugin {
+            curve: Some("config/survivor.xp.ron"),
+            upgrades: Some("config/survivor.upgrades.ron"),
+            choices: 3,
+        })
+        .add_plugins(ParticlesPlugin {
+            effects: "config/survivor.particles.ron",
+        })
+        .add_systems(Startup, (spawn_player, spawn_ui, setup_camera).chain())
+        .add_systems(
+            Update,
+            (
+                player_input,
+                keep_player_in_arena,
+                spawn_xp_orbs,
+                show_level_up_choices,
+                pick_level_up_choice,
+            ),
+        )
+        .run();
+}
+
+#[derive(Component)]
+struct Player;
+#[derive(Component)]
+struct LevelUpText;
+
+fn setup_camera(mut commands: Commands, player: Single<Entity, With<Player>>) {
+    commands.spawn((
+        Camera2d,
+        CameraFollow::new(*player).with_dead_zone(Vec2::new(60.0, 40.0)),
+        ClampToArena,
+        CameraShake::default(),
+    ));
+}
+
+fn spawn_player(mut commands: Commands) {
+    commands.spawn((
+        Player,
+        Sprite {
+            custom_size: Some(Vec2::splat(28.0)),
+            color: Color::Srgba(EMERALD_400),
+            ..default()
+        },
+        Health::new(100.0),
+        HealthBar::above(24.0),
+        ShakeOnDamage::default(),
+        Stats::new()
+            .with(StatId::MaxHealth, 100.0)
+            .with(StatId::MoveSpeed, 220.0)
+            .with(StatId::FireRate, 1.0)
+            .with(StatId::Damage, 10.0)
+            .with(StatId::MagnetRadius, 120.0),
+        Experience::default(),
+        XpCollector::default(),
+        Velocity::default(),
+        CircleCollider::new(14.0),
+        Transform::default(),
+    ));
+}
+
+fn spawn_ui(mut commands: Commands, player: Single<Entity, With<Player>>) {
+    let player = *player;
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.),
+            left: Val::Px(12.),
+            ..default()
+        },
+        Text::new(""),
+        TextFont {
+            font_size: 22.0,
+            ..default()
+        },
+        TextBinding::component::<Experience>(player, "Lv {level}  xp {xp}"),
+        children![
+            (
+                TextSpan::default(),
+                TextFont::from_font_size(22.0),
+                TextBinding::component::<Health>(player, "  hp {current:.0}/{max:.0}"),
+            ),
+            (
+                TextSpan::default(),
+                TextFont::from_font_size(22.0),
+                TextBinding::component_with::<Stats>(player, |stats| {
+                    format!("  speed {:.0}", stats.get_or(StatId::MoveSpeed, 0.0))
+                }),
+            ),
+        ],
+    ));
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Percent(35.),
+            left: Val::Percent(30.),
+            ..default()
+        },
+        Text::new(""),
+        TextFont {
+            font_size: 26.0,
+            ..default()
+        },
+        LevelUpText,
+    ));
+}
+
+fn player_input(
+    kb: Res<ButtonInput<KeyCode>>,
+    mut q: Query<(&mut Velocity, &Stats), With<Player>>,
+) {
+    let Ok((mut v, stats)) = q.single_mut() else {
+        return;
+    };
+    let mut dir = Vec2::ZERO;
+    if kb.pressed(KeyCode::KeyW) || kb.pressed(KeyCode::ArrowUp) {
+        dir.y += 1.0;
+    }
+    if kb.pressed(KeyCode::KeyS) || kb.pressed(KeyCode::ArrowDown) {
+        dir.y -= 1.0;
+    }
+    if kb.pressed(KeyCode::KeyD) || kb.pressed(KeyCode::ArrowRight) {
+        dir.x += 1.0;
+    }
+    if kb.pressed(KeyCode::KeyA) || kb.pressed(KeyCode::ArrowLeft) {
+        dir.x -= 1.0;
+    }
+    v.lin_vel = dir.normalize_or_zero() * stats.get_or(StatId::MoveSpeed, 220.0);
+}
+
+fn keep_player_in_arena(
+    bounds: Res<ArenaBounds>,
+    mut q: Query<(&mut Transform, &CircleCollider), With<Player>>,
+) {
+    for (mut t, c) in &mut q {
+        let p = bounds.clamp(t.translation.truncate(), c.radius);
+        t.translation.x = p.x;
+        t.translation.y = p.y;
+    }
+}
+
+// placeholder until enemies drop them
+fn spawn_xp_orbs(
+    mut commands: Commands,
+    time: Res<Time>,
+    bounds: Res<ArenaBounds>,
+    mut rng: ResMut<GameRng>,
+    mut clock: Local<Timer>,
+) {
+    if clock.duration().is_zero() {
+        *clock = Timer::from_seconds(0.6, TimerMode::Repeating);
+    }
+    clock.tick(time.delta());
+    if !clock.just_finished() {
+        return;
+    }
+
+    use rand::Rng;
+    let x = rng.random_range(-bounds.half_w..bounds.half_w);
+    let y = rng.random_range(-bounds.half_h..bounds.half_h);
+    commands.spawn((
+        XpPickup::new(rng.random_range(1..=3)),
+        Sprite {
+            custom_size: Some(Vec2::splat(8.0)),
+            color: Color::Srgba(AMBER_300),
+            ..default()
+        },
+        Transform::from_xyz(x, y, -1.0),
+    ));
+}
+
+fn show_level_up_choices(
+    pending: Res<PendingLevelUps>,
+    data: LevelUpData,
+    mut text: Single<&mut Text, With<LevelUpText>>,
+) {
+    if !pending.is_changed() {
+        return;
+    }
+    let Some(offer) = pending.current() else {
+        text.0.clear();
+        return;
+    };
+
+    let mut lines = format!("LEVEL {}!\n", offer.level);
+    for (i, id) in offer.choices.iter().enumerate() {
+        let (name, desc) = data
+            .pool()
+            .and_then(|p| p.get(id))
+            .map(|u| (u.name.as_str(), u.description.as_str()))
+            .unwrap_or((id.as_str(), ""));
+        lines.push_str(&format!("[{}] {}  {}\n", i + 1, name, desc));
+    }
+    text.0 = lines;
+}
+
+fn pick_level_up_choice(
+    kb: Res<ButtonInput<KeyCode>>,
+    pending: Res<PendingLevelUps>,
+    mut writer: MessageWriter<UpgradeChosenEvent>,
+) {
+    let Some(offer) = pending.current() else {
+        return;
+    };
+    let keys = [
+        KeyCode::Digit1,
+        KeyCode::Digit2,
+        KeyCode::Digit3,
+        KeyCode::Digit4,
+    ];
+    for (key, id) in keys.iter().zip(offer.choices.iter()) {
+        if kb.just_pressed(*key) {
+            writer.write(UpgradeChosenEvent {
+                entity: offer.entity,
+                upgrade: id.clone(),
+            });
+            return;
+        }
+    }
 }
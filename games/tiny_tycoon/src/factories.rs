@@ -0,0 +1,528 @@
+use std::{collections::BTreeMap, ops::DerefMut};
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use core_engine::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub const FACTORY_CATALOG: &str = "config/tycoon.factories.ron";
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FactoryKind {
+    Basic,
+    Advanced,
+    Tower,
+}
+
+#[derive(Component)]
+pub struct Factory {
+    pub kind: FactoryKind,
+    pub level: u8,
+}
+
+#[derive(Component)]
+pub struct Produces {
+    pub item: ItemId,
+    pub per_second: BigNum,
+    // used up for every item made
+    pub inputs: Vec<(ItemId, u32)>,
+    // the part-made item carried into the next step
+    pub carry: f64,
+}
+
+// one buildable factory. everything about a kind lives in the catalog file
+#[derive(Deserialize, Debug, Clone)]
+pub struct FactoryDef {
+    pub kind: FactoryKind,
+    pub name: String,
+    pub sprite: String,
+    pub size: (f32, f32),
+    #[serde(default = "no_tint")]
+    pub tint: (f32, f32, f32),
+    pub cost: BigNum,
+    // ItemDef key of what it makes
+    pub item: String,
+    // ItemDef keys and how many of each go into one of `item`. empty for raw goods
+    #[serde(default)]
+    pub inputs: Vec<(String, u32)>,
+    // items a second at level 1
+    pub rate: BigNum,
+    // every level past the first multiplies the rate by this
+    pub level_mult: f64,
+    // level n -> n + 1 costs n times this
+    pub upgrade_cost: BigNum,
+}
+
+fn no_tint() -> (f32, f32, f32) {
+    (1.0, 1.0, 1.0)
+}
+
+impl FactoryDef {
+    pub fn rate_at(&self, level: u8) -> BigNum {
+        self.rate * BigNum::from(self.level_mult).pow(level.saturating_sub(1) as f64)
+    }
+
+    pub fn upgrade_cost(&self, level: u8) -> BigNum {
+        self.upgrade_cost * level as f64
+    }
+
+    // `levels` upgrades in a row starting at `level`
+    pub fn upgrade_cost_n(&self, level: u8, levels: u32) -> BigNum {
+        let (l, n) = (level as f64, levels as f64);
+        self.upgrade_cost * (n * l + n * (n - 1.0) / 2.0)
+    }
+
+    // (levels, total cost) one click buys in `mode`. for xMax that's as many as
+    // `budget` covers, but at least one so there's a price to show
+    pub fn upgrade_plan(&self, level: u8, mode: BuyMode, budget: BigNum) -> (u32, BigNum) {
+        let room = (u8::MAX - level) as u32;
+        let levels = match mode {
+            BuyMode::Max => (1..=room)
+                .take_while(|&n| self.upgrade_cost_n(level, n) <= budget)
+                .last()
+                .unwrap_or(1),
+            mode => mode.count(),
+        }
+        .min(room);
+        (levels, self.upgrade_cost_n(level, levels))
+    }
+
+    pub fn output(&self, registry: &ItemRegistry) -> ItemId {
+        registry.by_key(&self.item).map_or_else(
+            || {
+                warn!("{} makes unknown item {:?}", self.name, self.item);
+                ItemId(0)
+            },
+            |def| def.id,
+        )
+    }
+
+    pub fn inputs(&self, registry: &ItemRegistry) -> Vec<(ItemId, u32)> {
+        self.inputs
+            .iter()
+            .filter_map(|(key, n)| match registry.by_key(key) {
+                Some(def) => Some((def.id, *n)),
+                None => {
+                    warn!("{} uses unknown item {key:?}", self.name);
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+// the RON file, copied into a resource of the same type once it's loaded
+#[derive(Asset, TypePath, Resource, Deserialize, Debug, Clone, Default)]
+pub struct FactoryCatalog {
+    pub factories: Vec<FactoryDef>,
+}
+
+impl FactoryCatalog {
+    pub fn get(&self, kind: FactoryKind) -> Option<&FactoryDef> {
+        self.factories.iter().find(|d| d.kind == kind)
+    }
+}
+
+// `secs` of work for every producer, straight out of and into `stock`. suppliers go
+// before the recipes that use their output, and a recipe short on inputs makes only
+// what they cover. part-made items carry over, so one long step makes what many
+// short ones would. returns what was made
+pub fn run_production<P: DerefMut<Target = Produces>>(
+    producers: impl IntoIterator<Item = P>,
+    secs: f64,
+    stock: &mut BTreeMap<ItemId, u64>,
+) -> BTreeMap<ItemId, u64> {
+    let producers: Vec<P> = producers.into_iter().collect();
+    let mut ranked: Vec<(usize, P)> = recipe_steps(&producers)
+        .into_iter()
+        .zip(producers)
+        .collect();
+    ranked.sort_by_key(|(step, _)| *step);
+
+    let mut made = BTreeMap::new();
+    for (_, mut p) in ranked {
+        let p = &mut *p;
+        let due = p.carry + p.per_second.to_f64() * secs;
+        // sums of steps like 0.1 land a hair under the whole number they add up to
+        let mut count = (due + 1e-9).floor().max(0.0) as u64;
+        for (item, n) in &p.inputs {
+            count = count.min(stock.get(item).copied().unwrap_or(0) / (*n).max(1) as u64);
+        }
+        // a starved recipe keeps its part-made item, not the ones it had no inputs for
+        p.carry = (due - count as f64).clamp(0.0, due.fract().max(0.0));
+        if count == 0 {
+            continue;
+        }
+        for (item, n) in &p.inputs {
+            *stock.entry(*item).or_default() -= count * *n as u64;
+        }
+        *stock.entry(p.item).or_default() += count;
+        *made.entry(p.item).or_default() += count;
+    }
+    made
+}
+
+// how many recipes each producer sits above raw goods: 0 without inputs, else one more
+// than the deepest producer of any input. a cycle stops deepening after every
+// producer has had its turn
+fn recipe_steps(producers: &[impl std::ops::Deref<Target = Produces>]) -> Vec<usize> {
+    let mut steps = vec![0; producers.len()];
+    let mut item_steps: BTreeMap<ItemId, usize> = BTreeMap::new();
+    for _ in 0..producers.len() {
+        let mut changed = false;
+        for (p, step) in producers.iter().zip(&mut steps) {
+            let deepest = p
+                .inputs
+                .iter()
+                .map(|(item, _)| item_steps.get(item).map_or(1, |s| s + 1))
+                .max()
+                .unwrap_or(0);
+            if deepest > *step {
+                *step = deepest;
+                changed = true;
+            }
+            let made = item_steps.entry(p.item).or_default();
+            *made = (*made).max(*step);
+        }
+        if !changed {
+            break;
+        }
+    }
+    steps
+}
+
+// what each item gains (or loses to recipes) a second with every input on hand
+pub fn item_rates<'a>(producers: impl IntoIterator<Item = &'a Produces>) -> BTreeMap<ItemId, f64> {
+    let mut rates = BTreeMap::new();
+    for p in producers {
+        let per_second = p.per_second.to_f64();
+        *rates.entry(p.item).or_default() += per_second;
+        for (item, n) in &p.inputs {
+            *rates.entry(*item).or_default() -= per_second * *n as f64;
+        }
+    }
+    rates
+}
+
+// cheapest next level anywhere first, until `budget` runs out. levels are
+// raised in place; returns (levels bought, money spent)
+pub fn upgrade_all_affordable(
+    catalog: &FactoryCatalog,
+    factories: &mut [(FactoryKind, u8)],
+    budget: BigNum,
+) -> (u32, BigNum) {
+    let (mut bought, mut spent) = (0, BigNum::ZERO);
+    loop {
+        let cheapest = factories
+            .iter_mut()
+            .filter(|(_, level)| *level < u8::MAX)
+            .filter_map(|(kind, level)| Some((catalog.get(*kind)?.upgrade_cost(*level), level)))
+            .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        let Some((cost, level)) = cheapest else {
+            break;
+        };
+        if spent + cost > budget {
+            break;
+        }
+        *level += 1;
+        spent += cost;
+        bought += 1;
+    }
+    (bought, spent)
+}
+
+// how many factories or levels one click buys
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BuyMode {
+    #[default]
+    One,
+    Ten,
+    Max,
+}
+
+impl BuyMode {
+    pub fn next(self) -> Self {
+        match self {
+            Self::One => Self::Ten,
+            Self::Ten => Self::Max,
+            Self::Max => Self::One,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::One => "x1",
+            Self::Ten => "x10",
+            Self::Max => "xMAX",
+        }
+    }
+
+    // fixed count, Max works it out from the money
+    fn count(self) -> u32 {
+        match self {
+            Self::Ten => 10,
+            _ => 1,
+        }
+    }
+
+    // factories at `cost` each. xMax stops at 100 so a big bank doesn't
+    // flood the screen
+    pub fn factories(self, cost: BigNum, budget: BigNum) -> u32 {
+        match self {
+            Self::Max if cost > BigNum::ZERO => {
+                (budget / cost).floor().to_u64().clamp(1, 100) as u32
+            }
+            mode => mode.count(),
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct FactoryCatalogHandle(pub Handle<FactoryCatalog>);
+
+pub fn load_factory_catalog(mut commands: Commands, server: Res<AssetServer>) {
+    commands.insert_resource(FactoryCatalogHandle(server.load(FACTORY_CATALOG)));
+}
+
+// again on every hot reload, which re-rates the factories already built
+pub fn sync_factory_catalog(
+    mut commands: Commands,
+    mut events: MessageReader<AssetEvent<FactoryCatalog>>,
+    handle: Res<FactoryCatalogHandle>,
+    catalogs: Res<Assets<FactoryCatalog>>,
+) {
+    for ev in events.read() {
+        if (ev.is_loaded_with_dependencies(&handle.0) || ev.is_modified(&handle.0))
+            && let Some(catalog) = catalogs.get(&handle.0)
+        {
+            commands.insert_resource(catalog.clone());
+        }
+    }
+}
+
+// what building a factory needs, for the systems that buy or restore them
+#[derive(SystemParam)]
+pub struct FactoryBuilder<'w, 's> {
+    pub commands: Commands<'w, 's>,
+    pub catalog: Res<'w, FactoryCatalog>,
+    pub registry: Res<'w, ItemRegistry>,
+    asset_server: Res<'w, AssetServer>,
+}
+
+impl FactoryBuilder<'_, '_> {
+    // None if the kind isn't in the catalog
+    pub fn spawn(&mut self, kind: FactoryKind, level: u8, pos: Vec2) -> Option<Entity> {
+        let def = self.catalog.get(kind)?;
+        Some(spawn_factory(
+            &mut self.commands,
+            def,
+            level,
+            pos,
+            &self.asset_server,
+            &self.registry,
+        ))
+    }
+}
+
+fn spawn_factory(
+    commands: &mut Commands,
+    def: &FactoryDef,
+    level: u8,
+    pos: Vec2,
+    asset_server: &AssetServer,
+    registry: &ItemRegistry,
+) -> Entity {
+    let (r, g, b) = def.tint;
+    commands
+        .spawn((
+            Factory {
+                kind: def.kind,
+                level,
+            },
+            Produces {
+                item: def.output(registry),
+                per_second: def.rate_at(level),
+                inputs: def.inputs(registry),
+                carry: 0.0,
+            },
+            Stats::new().with(StatId::ProductionRate, 1.0),
+            Sprite {
+                image: asset_server.load(&def.sprite),
+                color: Color::srgb(r, g, b),
+                custom_size: Some(Vec2::new(def.size.0, def.size.1)),
+                image_mode: SpriteImageMode::Scale(ScalingMode::FillCenter),
+                ..default()
+            },
+            Transform::from_xyz(pos.x, pos.y, 0.0),
+        ))
+        .id()
+}
+
+// the catalog rate for the factory's level, times anything else on ProductionRate
+pub fn sync_production_rate(
+    catalog: Res<FactoryCatalog>,
+    registry: Res<ItemRegistry>,
+    mut q: Query<(Ref<Factory>, Ref<Stats>, &mut Produces)>,
+) {
+    let reload = catalog.is_changed() || registry.is_changed();
+    for (factory, stats, mut p) in &mut q {
+        if !(reload || factory.is_changed() || stats.is_changed()) {
+            continue;
+        }
+        let Some(def) = catalog.get(factory.kind) else {
+            continue;
+        };
+        p.item = def.output(&registry);
+        p.inputs = def.inputs(&registry);
+        p.per_second = def.rate_at(factory.level) * stats.get_or(StatId::ProductionRate, 1.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catalog_covers_every_kind() {
+        let catalog = catalog();
+        for kind in [
+            FactoryKind::Basic,
+            FactoryKind::Advanced,
+            FactoryKind::Tower,
+        ] {
+            assert!(catalog.get(kind).is_some(), "{kind:?}");
+        }
+        let basic = catalog.get(FactoryKind::Basic).unwrap();
+        assert_eq!(basic.rate_at(1), basic.rate);
+        assert_eq!(
+            basic.rate_at(3),
+            basic.rate * basic.level_mult * basic.level_mult
+        );
+        assert_eq!(basic.upgrade_cost(6), basic.upgrade_cost * 6.0);
+    }
+
+    fn catalog() -> FactoryCatalog {
+        ron::from_str(include_str!("../assets/config/tycoon.factories.ron")).unwrap()
+    }
+
+    #[test]
+    fn buy_modes_plan_levels() {
+        let catalog = catalog();
+        let basic = catalog.get(FactoryKind::Basic).unwrap();
+        // 100 per level: 2 -> 3 -> 4 costs 200 + 300
+        assert_eq!(basic.upgrade_cost_n(2, 2), BigNum::from(500.0));
+        let budget = BigNum::from(1000.0);
+        assert_eq!(basic.upgrade_plan(2, BuyMode::One, budget).0, 1);
+        assert_eq!(basic.upgrade_plan(2, BuyMode::Ten, budget).0, 10);
+        // 200 + 300 + 400 fits, + 500 doesn't
+        assert_eq!(
+            basic.upgrade_plan(2, BuyMode::Max, budget),
+            (3, BigNum::from(900.0))
+        );
+        assert_eq!(basic.upgrade_plan(2, BuyMode::Max, BigNum::ZERO).0, 1);
+        assert_eq!(basic.upgrade_plan(u8::MAX - 1, BuyMode::Ten, budget).0, 1);
+        assert_eq!(BuyMode::Max.factories(BigNum::from(50.0), budget), 20);
+
+        // basic's 100, 200 and 300 all undercut advanced's 400, which won't fit after
+        let mut levels = [(FactoryKind::Advanced, 1), (FactoryKind::Basic, 1)];
+        let spent = upgrade_all_affordable(&catalog, &mut levels, BigNum::from(650.0));
+        assert_eq!(spent, (3, BigNum::from(600.0)));
+        assert_eq!(
+            levels,
+            [(FactoryKind::Advanced, 1), (FactoryKind::Basic, 4)]
+        );
+    }
+
+    #[test]
+    fn recipes_only_make_what_their_inputs_cover() {
+        let (yarn, cushion) = (ItemId(1), ItemId(2));
+        let mut spinner = Produces {
+            item: yarn,
+            per_second: BigNum::from(4.0),
+            inputs: Vec::new(),
+            carry: 0.0,
+        };
+        let mut sewer = Produces {
+            item: cushion,
+            per_second: BigNum::from(10.0),
+            inputs: vec![(yarn, 3)],
+            carry: 0.0,
+        };
+        // the spinner runs first even though it's listed last
+        let mut stock = BTreeMap::from([(yarn, 5)]);
+        let made = run_production([&mut sewer, &mut spinner], 1.0, &mut stock);
+        assert_eq!(made, BTreeMap::from([(yarn, 4), (cushion, 3)]));
+        assert_eq!(stock, BTreeMap::from([(yarn, 0), (cushion, 3)]));
+
+        // a one-input recipe fed by a two-input one still runs after its supplier
+        let (tower, gift) = (ItemId(3), ItemId(4));
+        let mut wrapper = Produces {
+            item: gift,
+            per_second: BigNum::from(1.0),
+            inputs: vec![(tower, 1)],
+            carry: 0.0,
+        };
+        let mut builder = Produces {
+            item: tower,
+            per_second: BigNum::from(1.0),
+            inputs: vec![(cushion, 1), (yarn, 1)],
+            carry: 0.0,
+        };
+        sewer.inputs = vec![(yarn, 1)];
+        sewer.per_second = BigNum::from(2.0);
+        spinner.per_second = BigNum::from(10.0);
+        let mut stock = BTreeMap::new();
+        let made = run_production(
+            [&mut wrapper, &mut builder, &mut sewer, &mut spinner],
+            1.0,
+            &mut stock,
+        );
+        assert_eq!(
+            made,
+            BTreeMap::from([(yarn, 10), (cushion, 2), (tower, 1), (gift, 1)])
+        );
+    }
+
+    #[test]
+    fn fractional_output_carries_over() {
+        let yarn = ItemId(1);
+        let mut slow = Produces {
+            item: yarn,
+            per_second: BigNum::from(1.5),
+            inputs: Vec::new(),
+            carry: 0.0,
+        };
+        let mut stock = BTreeMap::new();
+        for _ in 0..10 {
+            run_production([&mut slow], 1.0, &mut stock);
+        }
+        assert_eq!(stock[&yarn], 15);
+
+        // one 10 s step makes the same as ten 1 s ones
+        let mut long = Produces { carry: 0.0, ..slow };
+        run_production([&mut long], 10.0, &mut stock);
+        assert_eq!(stock[&yarn], 30);
+
+        // down to slivers of a second
+        let mut trickle = Produces {
+            item: yarn,
+            per_second: BigNum::from(0.1),
+            inputs: Vec::new(),
+            carry: 0.0,
+        };
+        let mut stock = BTreeMap::new();
+        for _ in 0..1000 {
+            run_production([&mut trickle], 0.1, &mut stock);
+        }
+        assert_eq!(stock[&yarn], 10);
+
+        let sewer = Produces {
+            item: ItemId(2),
+            per_second: BigNum::from(0.5),
+            inputs: vec![(yarn, 3)],
+            carry: 0.0,
+        };
+        let rates = item_rates([&trickle, &sewer]);
+        assert_eq!(rates[&ItemId(2)], 0.5);
+        assert!((rates[&yarn] + 1.4).abs() < 1e-9);
+    }
+}
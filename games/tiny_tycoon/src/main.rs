@@ -1,28 +1,94 @@
-use std::collections::HashMap;
+use std::{collections::BTreeMap, ops::DerefMut};
 
-use bevy::prelude::*;
-use core_engine::prelude::*;
+use bevy::{ecs::system::SystemParam, prelude::*};
+use core_engine::{config::RonAssetPlugin, prelude::*};
+use save_game::prelude::*;
 use serde::{Deserialize, Serialize};
+use ui_common::{list::ListSort, prelude::*};
+
+mod factories;
+mod market;
+mod prestige;
+mod selection;
+use factories::*;
+use market::*;
+use prestige::*;
+use selection::*;
 
 fn main() {
     App::new()
-        .add_plugins((DefaultPlugins, CorePlugin))
-        .insert_resource(Money(100.0))
+        .add_plugins((
+            DefaultPlugins.set(AssetPlugin {
+                watch_for_changes_override: Some(cfg!(feature = "dev")),
+                ..default()
+            }),
+            CorePlugin,
+            UiCommonPlugin,
+            UiThemePlugin::default(),
+            SavePlugin::default(),
+        ))
+        .add_plugins(ItemsPlugin {
+            catalog: "config/tycoon.items.ron",
+        })
+        .add_plugins(RonAssetPlugin::<FactoryCatalog>::new(&["factories.ron"]))
+        .insert_resource(Money(BigNum::from(100.0)))
         .insert_resource(Inventory::default())
+        .init_resource::<OfflineConfig>()
+        .init_resource::<MarketConfig>()
+        .init_resource::<Market>()
+        .init_resource::<PrestigeConfig>()
+        .init_resource::<Prestige>()
+        .init_resource::<SelectedFactory>()
+        .init_resource::<BuyMode>()
         .insert_resource(ProductionClock(Timer::from_seconds(
             1.0,
             TimerMode::Repeating,
         )))
-        .insert_resource(SavePath("saves/tycoon.ron".into()))
-        .add_systems(Startup, (spawn_camera, spawn_ui, try_load_then_reconstruct))
+        .add_systems(
+            Startup,
+            (spawn_camera, spawn_ui, load_factory_catalog, read_save),
+        )
         .add_systems(
             Update,
             (
-                tick_production,
-                update_ui,
-                click_buy_factory,
-                click_upgrade_factory,
-                autosave_every_5s_dev,
+                sync_factory_catalog.run_if(resource_exists::<FactoryCatalogHandle>),
+                (
+                    restore_save.run_if(resource_exists::<PendingRestore>),
+                    apply_prestige_bonus,
+                    sync_production_rate,
+                    (
+                        tick_production,
+                        credit_offline_progress.run_if(resource_exists::<OfflineTime>),
+                    ),
+                    auto_sell,
+                )
+                    .chain()
+                    .run_if(resource_exists::<FactoryCatalog>),
+                (
+                    describe_buy_buttons,
+                    click_buy_factory,
+                    click_upgrade_selected,
+                    click_upgrade_all,
+                    update_upgrade_button,
+                )
+                    .run_if(resource_exists::<FactoryCatalog>),
+                (
+                    pick_factory.run_if(dialog_closed),
+                    select_from_list,
+                    sync_list_selection,
+                    highlight_selected,
+                )
+                    .chain(),
+                (
+                    recover_market,
+                    click_sell_selected,
+                    click_auto_sell,
+                    update_auto_sell_button,
+                ),
+                (click_buy_prestige, click_rebirth, answer_rebirth),
+                click_buy_mode,
+                click_sort_inventory,
+                autosave_every_5s_dev.run_if(autosave_allowed),
             ),
         )
         .run();
@@ -34,14 +100,46 @@ fn spawn_camera(mut commands: Commands) {
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct SaveFile {
-    pub money: f32,
+    pub money: BigNum,
     pub inventory: Vec<(ItemId, u32)>,
     pub factories: Vec<FactorySave>, // world snapshot
-    pub last_real_secs: f64,         // offline catch-up
+    pub auto_sell: Vec<ItemId>,
+    pub prestige: Prestige,
+}
+
+// every format ever shipped has a file in tests/saves that must keep loading.
+// bump VERSION, add a step here and a fixture there whenever SaveFile changes
+impl SaveData for SaveFile {
+    const VERSION: u32 = 4;
+    const MIGRATIONS: &'static [Migration] = &[
+        // 0 -> 1: only the header was added
+        |_| Ok(()),
+        // 1 -> 2: the save header's saved_at replaced the session clock
+        |save| {
+            save.remove_field("last_real_secs");
+            Ok(())
+        },
+        // 2 -> 3: auto-sell toggles, all off before the market existed
+        |save| {
+            save.set_field("auto_sell", SaveValue::Seq(Vec::new()));
+            Ok(())
+        },
+        // 3 -> 4: prestige, nothing earned towards it yet
+        |save| {
+            let zero = || SaveValue::from(0u64);
+            let prestige = vec![
+                ("stars".to_string(), zero()),
+                ("claimed".to_string(), zero()),
+                ("lifetime".to_string(), zero()),
+                ("upgrades".to_string(), SaveValue::Map(Vec::new())),
+            ];
+            save.set_field("prestige", SaveValue::Struct(None, prestige));
+            Ok(())
+        },
+    ];
 }
 
-#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
-pub struct ItemId(pub u16);
+const SAVE_SLOT: &str = "tycoon";
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct FactorySave {
@@ -50,287 +148,367 @@ pub struct FactorySave {
     pub pos: (f32, f32),
 }
 
-#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
-pub enum FactoryKind {
-    Basic,
-    Advanced,
-}
-
 // =========== RUNTIME RESOURCE ===============
-#[derive(Resource)]
-pub struct Money(pub f32);
-#[derive(Resource, Default)]
-pub struct Inventory(pub HashMap<ItemId, u32>);
+#[derive(Resource, Reflect)]
+pub struct Money(pub BigNum);
 
 #[derive(Resource)]
 pub struct ProductionClock(pub Timer);
 
-#[derive(Resource)]
-pub struct SavePath(std::path::PathBuf);
-
-// =========== World Components ===============
-#[derive(Component)]
-pub struct Factory {
-    pub kind: FactoryKind,
-    pub level: u8,
-}
-#[derive(Component)]
-pub struct Produces {
-    pub item: ItemId,
-    pub per_second: f32,
-}
-
 // =========== UI Components ===============
 
-#[derive(Component)]
-pub struct UiMoneyText;
-#[derive(Component)]
-pub struct UiInvText;
 #[derive(Component)]
 pub struct BtnBuyFactory(FactoryKind);
 #[derive(Component)]
-pub struct BtnUpgradeFactory;
-
-fn spawn_basic_factory(mut commands: Commands, pos: Vec2, asset_server: &AssetServer) {
-    let sprite = asset_server.load("cat_3.png");
-    commands.spawn((
-        Factory {
-            kind: FactoryKind::Basic,
-            level: 1,
-        },
-        Produces {
-            item: ItemId(1),
-            per_second: 1.0, // 1 item / sec
-        },
-        Sprite {
-            image: sprite,
-            custom_size: Some(Vec2::new(691.0 / 10.0, 563.0 / 10.0)),
-            image_mode: SpriteImageMode::Scale(ScalingMode::FillCenter),
-            ..default()
-        },
-        Transform::from_xyz(pos.x, pos.y, 0.0),
-    ));
-}
+pub struct BtnSortInventory;
+#[derive(Component)]
+pub struct InventoryList;
 
 fn tick_production(
     time: Res<Time>,
     mut clock: ResMut<ProductionClock>,
     mut inv: ResMut<Inventory>,
-    q: Query<&Produces>,
+    registry: Res<ItemRegistry>,
+    mut q: Query<&mut Produces>,
 ) {
     clock.0.tick(time.delta());
-    if !clock.0.just_finished() {
+    // a long frame finishes the clock more than once, and pays for all of them
+    let secs = clock.0.times_finished_this_tick() as f64 * clock.0.duration().as_secs_f64();
+    if secs == 0.0 {
         return;
     }
 
-    for p in &q {
-        *inv.0.entry(p.item).or_default() += p.per_second as u32;
+    let mut stock = stock_of(&inv);
+    run_production(q.iter_mut(), secs, &mut stock);
+    restock(&mut inv, &stock, &registry);
+}
+
+// counts for run_production to work on, put back with restock
+fn stock_of(inv: &Inventory) -> BTreeMap<ItemId, u64> {
+    inv.totals().into_iter().collect()
+}
+
+fn restock(inv: &mut Inventory, stock: &BTreeMap<ItemId, u64>, registry: &ItemRegistry) {
+    for (&item, &count) in stock {
+        let have = inv.count(item);
+        let diff = count.abs_diff(have).min(u32::MAX as u64) as u32;
+        if count > have {
+            inv.add(item, diff, registry);
+        } else if count < have {
+            inv.take(item, diff);
+        }
     }
 }
 
 fn spawn_ui(mut commands: Commands) {
-    commands
-        .spawn((
-            Node {
-                position_type: PositionType::Absolute,
-                width: Val::Percent(100.0),
-                height: Val::Percent(100.0),
-                justify_content: JustifyContent::Center,
-                align_items: AlignItems::Center,
-                padding: UiRect::all(Val::Px(40.0)),
-                ..default()
-            },
-            BackgroundColor(Color::srgb(0.0, 0.0, 0.0).with_alpha(0.92)),
-        ))
-        .with_children(|parent| {
-            // Buy Basic Factory button
-            parent.spawn((
-                Button,
-                Node {
-                    width: Val::Px(320.0),
-                    height: Val::Px(60.0),
-                    flex_direction: FlexDirection::Column,
-                    justify_content: JustifyContent::Center,
-                    margin: UiRect::bottom(Val::Px(10.0)),
-                    ..default()
-                },
-                Text::new("BUY NORMAL FACTORY"),
-                TextFont {
-                    font_size: 24.0,
-                    ..default()
-                },
-                TextColor(Color::srgb(0.85, 0.30, 0.0)),
-                BtnBuyFactory(FactoryKind::Basic),
-            ));
-
-            // Buy Advanced Factory button
-            parent.spawn((
-                Button,
-                Node {
-                    width: Val::Px(320.0),
-                    height: Val::Px(60.0),
-                    flex_direction: FlexDirection::Column,
-                    justify_content: JustifyContent::Center,
-                    margin: UiRect::bottom(Val::Px(10.0)),
-                    ..default()
-                },
-                Text::new("BUY ADVANCED FACTORY"),
-                TextFont {
-                    font_size: 24.0,
-                    ..default()
-                },
-                TextColor(Color::srgb(0.30, 0.60, 0.85)),
-                BtnUpgradeFactory,
-            ));
-
-            // Upgrade Factory button
-            parent.spawn((
-                Button,
-                Node {
-                    width: Val::Px(320.0),
-                    height: Val::Px(60.0),
-                    flex_direction: FlexDirection::Column,
-                    justify_content: JustifyContent::Center,
-                    margin: UiRect::bottom(Val::Px(10.0)),
-                    ..default()
-                },
-                Text::new("UPGRADE FACTORY"),
-                TextFont {
-                    font_size: 24.0,
-                    ..default()
-                },
-                TextColor(Color::srgb(0.30, 0.85, 0.30)),
-                BtnUpgradeFactory,
-            ));
-
-            // Money display
-            parent.spawn((
-                Node {
-                    width: Val::Px(320.0),
-                    height: Val::Px(40.0),
-                    flex_direction: FlexDirection::Column,
-                    justify_content: JustifyContent::Center,
-                    margin: UiRect::top(Val::Px(20.0)),
-                    ..default()
-                },
-                Text::new("$ 0"),
-                TextFont {
-                    font_size: 28.0,
-                    ..default()
-                },
-                TextColor(Color::srgb(1.0, 1.0, 1.0)),
-                UiMoneyText,
-            ));
-
-            // Inventory display
-            parent.spawn((
-                Node {
-                    width: Val::Px(320.0),
-                    height: Val::Px(40.0),
-                    flex_direction: FlexDirection::Column,
-                    justify_content: JustifyContent::Center,
-                    ..default()
-                },
-                Text::new(""),
-                TextFont {
-                    font_size: 20.0,
-                    ..default()
-                },
-                TextColor(Color::srgb(0.8, 0.8, 0.8)),
-                UiInvText,
-            ));
+    // controls on the left, inspector and stock on the right, factories between
+    let root = Node {
+        position_type: PositionType::Absolute,
+        width: Val::Percent(100.0),
+        height: Val::Percent(100.0),
+        justify_content: JustifyContent::SpaceBetween,
+        align_items: AlignItems::FlexStart,
+        padding: UiRect::all(Val::Px(16.0)),
+        ..default()
+    };
+    commands.spawn(root).with_children(|root| {
+        root.spawn((panel(), BlocksWorldClicks))
+            .with_children(|parent| {
+                parent.spawn((
+                    themed_button("BUY NORMAL FACTORY", "accent"),
+                    Tooltip::new(""),
+                    BtnBuyFactory(FactoryKind::Basic),
+                ));
+                parent.spawn((
+                    themed_button("BUY ADVANCED FACTORY", "info"),
+                    Tooltip::new(""),
+                    BtnBuyFactory(FactoryKind::Advanced),
+                ));
+                parent.spawn((
+                    themed_button("BUY TOWER WORKSHOP", "info"),
+                    Tooltip::new(""),
+                    BtnBuyFactory(FactoryKind::Tower),
+                ));
+                parent.spawn((
+                    themed_button("BUY MODE: x1", "primary"),
+                    Tooltip::new("How many factories or levels one click buys"),
+                    BtnBuyMode,
+                ));
+                parent.spawn((
+                    themed_button("UPGRADE ALL AFFORDABLE", "success"),
+                    Tooltip::new("Buys the cheapest upgrades first until the money runs out"),
+                    BtnUpgradeAll,
+                ));
+
+                parent.spawn((
+                    themed_label("", TextRole::Body),
+                    TextBinding::resource::<Money>("$ {0}"),
+                ));
+                parent.spawn((
+                    scroll_list(ListView::list(32.0, 320.0, 160.0).sorted_by("level", true)),
+                    ListSource::computed(factory_rows),
+                    FactoryList,
+                ));
+            });
+
+        root.spawn(Node {
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(16.0),
+            ..default()
+        })
+        .with_children(|column| {
+            column.spawn(inspector_panel());
+            column.spawn(prestige_panel());
+            column
+                .spawn((panel(), BlocksWorldClicks))
+                .with_children(|parent| {
+                    parent.spawn((themed_button("SORT: COUNT", "primary"), BtnSortInventory));
+                    parent.spawn((
+                        scroll_list(ListView::list(32.0, 320.0, 160.0).sorted_by("count", true)),
+                        ListSource::computed(inventory_rows),
+                        InventoryList,
+                    ));
+                    parent.spawn((
+                        themed_button("SELL ALL", "success"),
+                        Tooltip::new(
+                            "Sells the picked item's whole stock. Big sales push its price down",
+                        ),
+                        BtnSellSelected,
+                    ));
+                    parent.spawn((
+                        themed_button("AUTO-SELL: OFF", "accent"),
+                        Tooltip::new("Sells the picked item as soon as it's made"),
+                        BtnAutoSell,
+                    ));
+                });
         });
+    });
 }
 
-fn update_ui(
-    money: Res<Money>,
-    inv: Res<Inventory>,
-    mut text_queries: ParamSet<(
-        Query<&mut Text, With<UiMoneyText>>,
-        Query<&mut Text, With<UiInvText>>,
-    )>,
-) {
-    if money.is_changed() {
-        if let Ok(mut t) = text_queries.p0().single_mut() {
-            **t = format!("$ {:.0}", money.0);
+// every stock count and how fast it moves, by item name, with what the next one
+// sells for. prices move every frame as the market recovers
+fn inventory_rows(world: &World) -> Vec<ListItem> {
+    let (Some(inv), Some(registry), Some(market), Some(config), Some(mut producers)) = (
+        world.get_resource::<Inventory>(),
+        world.get_resource::<ItemRegistry>(),
+        world.get_resource::<Market>(),
+        world.get_resource::<MarketConfig>(),
+        world.try_query::<&Produces>(),
+    ) else {
+        return Vec::new();
+    };
+    let rates = item_rates(producers.iter(world));
+    let mut totals = inv.totals();
+    // made but not in stock yet, or all used up as it comes in
+    for &item in rates.keys() {
+        if !totals.iter().any(|(id, _)| *id == item) {
+            totals.push((item, 0));
         }
     }
-    if inv.is_changed() {
-        if let Ok(mut t) = text_queries.p1().single_mut() {
-            let mut line = String::new();
-            for (id, qty) in inv.0.iter().take(3) {
-                line.push_str(&format!("I{}: {} ", id.0, qty));
+    totals
+        .into_iter()
+        .map(|(id, qty)| {
+            let price = market.price(id, base_value(registry, id), config);
+            let rate = rates.get(&id).copied().unwrap_or(0.0);
+            let auto = if market.auto_sell.contains(&id) {
+                "  AUTO"
+            } else {
+                ""
+            };
+            ListItem::new(id.0 as u64, registry.name(id))
+                .detail(format!(
+                    "{}  {}{}/s  @ ${:.2}{auto}",
+                    BigNum::from(qty),
+                    if rate < 0.0 { "-" } else { "+" },
+                    BigNum::from(rate.abs()),
+                    price
+                ))
+                .sort_key("count", qty)
+        })
+        .collect()
+}
+
+fn factory_rows(world: &World) -> Vec<ListItem> {
+    let (Some(catalog), Some(mut q)) = (
+        world.get_resource::<FactoryCatalog>(),
+        world.try_query::<(Entity, &Factory, &Produces)>(),
+    ) else {
+        return Vec::new();
+    };
+    q.iter(world)
+        .map(|(entity, factory, produces)| {
+            let name = catalog
+                .get(factory.kind)
+                .map_or_else(|| format!("{:?} factory", factory.kind), |d| d.name.clone());
+            ListItem::new(entity.to_bits(), name)
+                .detail(format!("lv {}  {}/s", factory.level, produces.per_second))
+                .sort_key("level", factory.level as u32)
+        })
+        .collect()
+}
+
+// flips the inventory between most stocked first and alphabetical
+fn click_sort_inventory(
+    mut clicks: MessageReader<ButtonClicked>,
+    q_btn: Query<&Children, With<BtnSortInventory>>,
+    mut lists: Query<&mut ListView, With<InventoryList>>,
+    mut texts: Query<&mut Text, With<ButtonText>>,
+) {
+    for children in clicks.read().filter_map(|c| q_btn.get(c.entity).ok()) {
+        let by_count = lists
+            .iter()
+            .next()
+            .and_then(|l| l.sort.as_ref())
+            .is_some_and(|s| s.key == "count");
+        for mut list in &mut lists {
+            list.sort = Some(if by_count {
+                ListSort {
+                    key: "label",
+                    descending: false,
+                }
+            } else {
+                ListSort {
+                    key: "count",
+                    descending: true,
+                }
+            });
+        }
+        for child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(child) {
+                text.0 = if by_count {
+                    "SORT: NAME"
+                } else {
+                    "SORT: COUNT"
+                }
+                .into();
             }
-            **t = line;
         }
     }
 }
-fn click_buy_factory(
-    mut commands: Commands,
-    mut money: ResMut<Money>,
-    asset_server: Res<AssetServer>,
-    mut counter: Local<u32>,
-    factories: Query<&Factory>,
-    q: Query<(&Interaction, &BtnBuyFactory), Changed<Interaction>>,
+// buy button tooltips straight from the catalog, kept current on hot reload
+fn describe_buy_buttons(
+    catalog: Res<FactoryCatalog>,
+    registry: Res<ItemRegistry>,
+    prestige: Res<Prestige>,
+    prestige_config: Res<PrestigeConfig>,
+    mut buttons: Query<(&BtnBuyFactory, &mut Tooltip)>,
 ) {
-    // Initialize counter based on existing factories (on first run after load)
-    if *counter == 0 && !factories.is_empty() {
-        *counter = factories.iter().count() as u32;
+    if !(catalog.is_changed() || registry.is_changed() || prestige.is_changed()) {
+        return;
     }
-
-    for (interaction, btn) in &q {
-        if *interaction == Interaction::Pressed {
-            let cost = match btn.0 {
-                FactoryKind::Basic => 50.0,
-                FactoryKind::Advanced => 200.0,
-            };
-
-            if money.0 >= cost {
-                money.0 -= cost;
-
-                // Spawn factory at varied position using counter
-                *counter += 1;
-                let angle = (*counter as f32) * 0.7; // Spiral-like pattern
-                let radius = (*counter as f32) * 2.0;
-                let x = angle.cos() * radius;
-                let y = angle.sin() * radius;
-
-                spawn_basic_factory(commands.reborrow(), Vec2::new(x, y), &asset_server);
-
-                println!("Bought {:?} factory for ${}", btn.0, cost);
+    let price = prestige.factory_price(&prestige_config);
+    for (btn, mut tip) in &mut buttons {
+        if let Some(def) = catalog.get(btn.0) {
+            tip.text = format!(
+                "Costs ${}, makes {} {} a second",
+                def.cost * price,
+                def.rate,
+                registry.name(def.output(&registry))
+            );
+            let inputs: Vec<_> = def
+                .inputs(&registry)
+                .into_iter()
+                .map(|(item, n)| format!("{n} {}", registry.name(item)))
+                .collect();
+            if !inputs.is_empty() {
+                tip.text
+                    .push_str(&format!(", each from {}", inputs.join(" + ")));
             }
         }
     }
 }
-fn click_upgrade_factory(
-    mut money: ResMut<Money>,
-    mut factories: Query<(&mut Factory, &mut Produces)>,
-    q_btn: Query<&Interaction, (Changed<Interaction>, With<BtnUpgradeFactory>)>,
+
+fn click_buy_factory(
+    mut builder: FactoryBuilder,
+    mut shop: Shop,
+    factories: Query<&Factory>,
+    mut clicks: MessageReader<ButtonClicked>,
+    q: Query<&BtnBuyFactory>,
+    mut toasts: MessageWriter<ToastEvent>,
 ) {
-    for interaction in &q_btn {
-        if *interaction == Interaction::Pressed {
-            // Upgrade the first factory found
-            if let Some((mut factory, mut produces)) = factories.iter_mut().next() {
-                let cost = (factory.level as f32) * 100.0;
-
-                if money.0 >= cost {
-                    money.0 -= cost;
-                    factory.level += 1;
-                    produces.per_second *= 1.5; // 50% increase per level
-
-                    println!("Upgraded factory to level {}", factory.level);
-                }
+    // spiral slot for the next factory, loaded ones included
+    let mut counter = factories.iter().count() as u32;
+
+    for btn in clicks.read().filter_map(|c| q.get(c.entity).ok()) {
+        let Some((cost, name)) = shop
+            .catalog
+            .get(btn.0)
+            .map(|d| (shop.factory_cost(d), d.name.clone()))
+        else {
+            warn!("no {:?} factory in {FACTORY_CATALOG}", btn.0);
+            continue;
+        };
+        let count = shop.mode.factories(cost, shop.money.0);
+        let total = cost * count;
+
+        if shop.money.0 >= total {
+            shop.money.0 -= total;
+
+            for _ in 0..count {
+                // spread out along a spiral so each one stays clickable
+                counter += 1;
+                let angle = (counter as f32) * 0.7;
+                let radius = 80.0 + (counter as f32) * 12.0;
+                let pos = Vec2::new(angle.cos(), angle.sin()) * radius;
+                builder.spawn(btn.0, 1, pos);
             }
+
+            toasts.write(ToastEvent::success(if count == 1 {
+                format!("Bought a {name} for ${total}")
+            } else {
+                format!("Bought {count} x {name} for ${total}")
+            }));
+        } else {
+            toasts.write(ToastEvent::warning(format!(
+                "Need ${total}, you have ${}",
+                shop.money.0
+            )));
         }
     }
 }
+// everything that goes into a save
+#[derive(SystemParam)]
+struct Snapshot<'w, 's> {
+    money: Res<'w, Money>,
+    inv: Res<'w, Inventory>,
+    market: Res<'w, Market>,
+    prestige: Res<'w, Prestige>,
+    factories: Query<'w, 's, (&'static Factory, &'static Transform)>,
+}
+
+impl Snapshot<'_, '_> {
+    fn save_file(&self) -> SaveFile {
+        let factories = self
+            .factories
+            .iter()
+            .map(|(f, tr)| FactorySave {
+                kind: f.kind,
+                level: f.level,
+                pos: (tr.translation.x, tr.translation.y),
+            })
+            .collect::<Vec<_>>();
+
+        SaveFile {
+            money: self.money.0,
+            inventory: self
+                .inv
+                .totals()
+                .into_iter()
+                .map(|(k, v)| (k, v.min(u32::MAX as u64) as u32))
+                .collect(),
+            factories,
+            auto_sell: self.market.auto_sell.iter().copied().collect(),
+            prestige: self.prestige.clone(),
+        }
+    }
+}
+
 fn autosave_every_5s_dev(
     time: Res<Time>,
     mut t: Local<Timer>,
-    money: Res<Money>,
-    inv: Res<Inventory>,
-    q: Query<(&Factory, &Transform)>,
-    path: Res<SavePath>,
+    snapshot: Snapshot,
+    store: Res<SaveStore>,
+    mut toasts: MessageWriter<ToastEvent>,
 ) {
     if t.duration().is_zero() {
         *t = Timer::from_seconds(5.0, TimerMode::Repeating)
@@ -340,65 +518,376 @@ fn autosave_every_5s_dev(
         return;
     }
 
-    use ron::ser::{PrettyConfig, to_string_pretty};
-    let factories = q
-        .iter()
-        .map(|(f, tr)| FactorySave {
-            kind: f.kind,
-            level: f.level,
-            pos: (tr.translation.x, tr.translation.y),
-        })
-        .collect::<Vec<_>>();
+    if let Err(e) = store.save(SAVE_SLOT, &snapshot.save_file()) {
+        warn!("autosave failed: {e}");
+        toasts.write(ToastEvent::error("Autosave failed"));
+    }
+}
+
+// never over the save that's still waiting to be restored, or one that couldn't be read
+fn autosave_allowed(pending: Option<Res<PendingRestore>>, locked: Option<Res<SaveLocked>>) -> bool {
+    pending.is_none() && locked.is_none()
+}
+
+// the slot on disk couldn't be loaded (damaged or from a newer build), so this
+// session never saves over it
+#[derive(Resource)]
+struct SaveLocked;
+
+// the save read at startup, applied by restore_save once both catalogs loaded
+#[derive(Resource)]
+struct PendingRestore {
+    save: SaveFile,
+    saved_at: Option<u64>,
+}
 
-    let sf = SaveFile {
-        money: money.0,
-        inventory: inv.0.iter().map(|(k, v)| (*k, *v)).collect(),
-        factories,
-        last_real_secs: time.elapsed_secs_f64(),
+fn read_save(mut commands: Commands, store: Res<SaveStore>, mut toasts: MessageWriter<ToastEvent>) {
+    let loaded = match store.load::<SaveFile>(SAVE_SLOT) {
+        Ok(Some(loaded)) => {
+            if let Some(n) = loaded.from_backup {
+                toasts.write(ToastEvent::warning(format!(
+                    "Save was damaged, restored backup {n}"
+                )));
+            }
+            loaded
+        }
+        Ok(None) => return,
+        Err(e) => {
+            error!("could not load save: {e}");
+            let why = match e {
+                SaveError::TooNew { .. } => "Save is from a newer version",
+                _ => "Save could not be loaded",
+            };
+            toasts.write(ToastEvent::error(format!(
+                "{why}, playing without saving so it stays intact"
+            )));
+            commands.insert_resource(SaveLocked);
+            return;
+        }
     };
+    commands.insert_resource(PendingRestore {
+        save: loaded.data,
+        saved_at: loaded.saved_at,
+    });
+}
+
+fn restore_save(
+    mut builder: FactoryBuilder,
+    pending: Res<PendingRestore>,
+    mut money: ResMut<Money>,
+    mut inv: ResMut<Inventory>,
+    mut market: ResMut<Market>,
+    mut prestige: ResMut<Prestige>,
+) {
+    // stack sizes and factory outputs both come from the item catalog
+    if builder.registry.is_empty() {
+        return;
+    }
+    builder.commands.remove_resource::<PendingRestore>();
+    let sf = &pending.save;
+    money.0 = sf.money;
+    market.auto_sell = sf.auto_sell.iter().copied().collect();
+    *prestige = sf.prestige.clone();
+
+    inv.clear();
+    for (id, qty) in &sf.inventory {
+        inv.add(*id, *qty, &builder.registry);
+    }
 
-    let ron = to_string_pretty(&sf, PrettyConfig::default()).expect("serialize");
-    std::fs::create_dir_all(path.0.parent().unwrap()).ok();
-    std::fs::write(&path.0, ron).expect("write save");
+    for f in &sf.factories {
+        let pos = Vec2::new(f.pos.0, f.pos.1);
+        if builder.spawn(f.kind, f.level, pos).is_none() {
+            warn!(
+                "dropping saved {:?} factory, not in {FACTORY_CATALOG}",
+                f.kind
+            );
+        }
+    }
+
+    // credited by credit_offline_progress once the factories above exist
+    if let Some(at) = pending.saved_at {
+        builder
+            .commands
+            .insert_resource(OfflineTime(unix_now().saturating_sub(at) as f64));
+    }
 }
 
-// fn init_game_or_load(mut commands: Commands, asset_time: Res<Time>) {
-//     commands.insert_resource(SavePath(std::path::PathBuf::from("saves/tycoon.ron")));
-//     // defaults
-//     commands.insert_resource(Money(100.0));
-//     commands.insert_resource(Inventory::default());
-// }
+// factories keep running while the game is closed, slower and only up to a point
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct OfflineConfig {
+    // longest absence that still pays out
+    pub cap_secs: f64,
+    // share of the normal rate earned while away
+    pub efficiency: f64,
+    // shorter breaks aren't worth a report
+    pub min_secs: f64,
+}
 
-fn try_load_then_reconstruct(
+impl Default for OfflineConfig {
+    fn default() -> Self {
+        Self {
+            cap_secs: 8.0 * 3600.0,
+            efficiency: 0.5,
+            min_secs: 60.0,
+        }
+    }
+}
+
+// wall-clock seconds between the last save and this launch
+#[derive(Resource)]
+struct OfflineTime(f64);
+
+// (seconds that count, what each item made in them). the whole stretch runs as one
+// step, so recipes can use up everything their suppliers made while away
+fn offline_earnings<P: DerefMut<Target = Produces>>(
+    away_secs: f64,
+    producers: impl IntoIterator<Item = P>,
+    stock: &mut BTreeMap<ItemId, u64>,
+    config: &OfflineConfig,
+) -> (f64, BTreeMap<ItemId, u64>) {
+    let secs = away_secs.clamp(0.0, config.cap_secs);
+    (
+        secs,
+        run_production(producers, secs * config.efficiency, stock),
+    )
+}
+
+fn credit_offline_progress(
     mut commands: Commands,
-    time: Res<Time>,
-    path: Res<SavePath>,
-    mut money: ResMut<Money>,
+    away: Res<OfflineTime>,
+    config: Res<OfflineConfig>,
     mut inv: ResMut<Inventory>,
-    asset_server: Res<AssetServer>,
+    registry: Res<ItemRegistry>,
+    mut q: Query<&mut Produces>,
+    mut dialogs: MessageWriter<OpenDialog>,
 ) {
-    if let Ok(text) = std::fs::read_to_string(&path.0) {
-        if let Ok(sf) = ron::from_str::<SaveFile>(&text) {
-            money.0 = sf.money;
-
-            inv.0 = sf.inventory.into_iter().collect();
-
-            // reconstruct factories
-            for f in sf.factories {
-                spawn_basic_factory(
-                    commands.reborrow(),
-                    Vec2::new(f.pos.0, f.pos.1),
-                    &asset_server,
-                );
-            }
+    commands.remove_resource::<OfflineTime>();
+    if away.0 < config.min_secs {
+        return;
+    }
+    let mut stock = stock_of(&inv);
+    let (secs, made) = offline_earnings(away.0, q.iter_mut(), &mut stock, &config);
+    restock(&mut inv, &stock, &registry);
+
+    let mut body = format!("You were gone for {}.", duration_text(away.0));
+    if secs < away.0 {
+        body.push_str(&format!(
+            " Factories stop after {} away.",
+            duration_text(config.cap_secs)
+        ));
+    }
+    let lines: Vec<_> = made
+        .into_iter()
+        .map(|(item, count)| format!("{} +{}", registry.name(item), BigNum::from(count)))
+        .collect();
+    if lines.is_empty() {
+        body.push_str("\nNothing was made. Factories earn while you're away.");
+    } else {
+        body.push_str(&format!(
+            "\nRunning at {:.0}% speed, your factories made:\n{}",
+            config.efficiency * 100.0,
+            lines.join("\n")
+        ));
+    }
+    dialogs.write(
+        OpenDialog::alert("offline", "WHILE YOU WERE AWAY", body).with_labels("COLLECT", ""),
+    );
+}
 
-            // offline catchup
-            let elapsed = (time.elapsed_secs_f64() - sf.last_real_secs).max(0.0);
-            let offline_ticks = (elapsed / 1.0).floor() as u32;
-            if offline_ticks > 0 {
+// "45m", "3h 12m", "2d 5h"
+fn duration_text(secs: f64) -> String {
+    let mins = (secs.max(0.0) / 60.0) as u64;
+    let (days, hours, mins) = (mins / 1440, mins / 60 % 24, mins % 60);
+    match (days, hours) {
+        (0, 0) => format!("{mins}m"),
+        (0, _) => format!("{hours}h {mins}m"),
+        _ => format!("{days}d {hours}h"),
+    }
+}
 
-                // TODO credits
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::time::TimeUpdateStrategy;
+    use std::{fs, path::Path, time::Duration};
+
+    // (money, inventory, factory kinds and levels, auto-sell) a save comes back with
+    type Contents = (
+        BigNum,
+        Vec<(ItemId, u32)>,
+        Vec<(FactoryKind, u8)>,
+        Vec<ItemId>,
+    );
+
+    fn contents(sf: &SaveFile) -> Contents {
+        (
+            sf.money,
+            sf.inventory.clone(),
+            sf.factories.iter().map(|f| (f.kind, f.level)).collect(),
+            sf.auto_sell.clone(),
+        )
+    }
+
+    // what every fixture has to migrate to. a new fixture needs an entry here
+    fn expected(slot: &str) -> Contents {
+        use FactoryKind::*;
+        let money = |s: &str| s.parse::<BigNum>().unwrap();
+        match slot {
+            // the save the game wrote before saves had a header
+            "v0_baseline" => {
+                let mut factories = vec![(Basic, 6)];
+                factories.extend([(Basic, 1); 29]);
+                (money("494100"), vec![(ItemId(1), 5146)], factories, vec![])
             }
+            "v0_headerless" => (
+                money("1250.5"),
+                vec![(ItemId(1), 340)],
+                vec![(Basic, 1), (Basic, 3)],
+                vec![],
+            ),
+            "v1" => (
+                money("494100"),
+                vec![(ItemId(1), 15200), (ItemId(2), 12)],
+                vec![(Basic, 6)],
+                vec![],
+            ),
+            "v1_big_money" => (
+                money("1.5e400"),
+                vec![(ItemId(1), u32::MAX)],
+                vec![(Advanced, 40)],
+                vec![],
+            ),
+            "v2" => (
+                money("12000.5"),
+                vec![(ItemId(1), 840), (ItemId(2), 31)],
+                vec![(Basic, 4), (Advanced, 2)],
+                vec![],
+            ),
+            "v3" => (
+                money("42500000"),
+                vec![(ItemId(1), 120000), (ItemId(2), 4100), (ItemId(3), 36)],
+                vec![(Basic, 12), (Advanced, 7), (Tower, 2)],
+                vec![ItemId(2)],
+            ),
+            _ => panic!("no expected contents for fixture {slot}"),
+        }
+    }
+
+    #[test]
+    fn old_saves_keep_loading() {
+        let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/saves");
+        let dir = std::env::temp_dir().join(format!("tycoon_saves_{}", std::process::id()));
+        let store = SaveStore::new(&dir).with_backups(0);
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut checked = 0;
+        for entry in fs::read_dir(fixtures).unwrap() {
+            let fixture = entry.unwrap().path();
+            let slot = fixture.file_stem().unwrap().to_str().unwrap();
+            fs::copy(&fixture, store.path(slot).unwrap()).unwrap();
+            let loaded = store
+                .load::<SaveFile>(slot)
+                .unwrap_or_else(|e| panic!("{e}"))
+                .unwrap();
+            assert_eq!(contents(&loaded.data), expected(slot), "{slot}");
+            // nothing was earned towards prestige before it existed
+            assert_eq!(loaded.data.prestige, Prestige::default(), "{slot}");
+
+            // and come back as the current version, unchanged, once saved
+            store.save(slot, &loaded.data).unwrap();
+            let resaved = store.load::<SaveFile>(slot).unwrap().unwrap();
+            assert_eq!(resaved.version, SaveFile::VERSION, "{slot}");
+            assert_eq!(contents(&resaved.data), expected(slot), "{slot}");
+            checked += 1;
+        }
+        assert!(checked >= 6);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn offline_earnings_are_capped_and_scaled() {
+        let config = OfflineConfig {
+            cap_secs: 3600.0,
+            efficiency: 0.5,
+            min_secs: 60.0,
+        };
+        let producer = |item, per_second: f64, inputs| Produces {
+            item: ItemId(item),
+            per_second: BigNum::from(per_second),
+            inputs,
+            carry: 0.0,
+        };
+        let mut producers = [
+            producer(1, 2.0, Vec::new()),
+            producer(1, 1.0, Vec::new()),
+            producer(2, 0.25, Vec::new()),
+        ];
+        let (secs, made) = offline_earnings(600.0, &mut producers, &mut BTreeMap::new(), &config);
+        assert_eq!(secs, 600.0);
+        assert_eq!(made, BTreeMap::from([(ItemId(1), 900), (ItemId(2), 75)]));
+
+        // a week away still only pays the first hour
+        let (secs, made) =
+            offline_earnings(7.0 * 86400.0, &mut producers, &mut BTreeMap::new(), &config);
+        assert_eq!(secs, 3600.0);
+        assert_eq!(made[&ItemId(1)], 5400);
+        assert_eq!(duration_text(7.0 * 86400.0 + 7200.0), "7d 2h");
+
+        // recipes run on what the others made while away, plus what was in stock
+        let mut chain = [
+            producer(3, 100.0, vec![(ItemId(1), 2)]),
+            producer(1, 1.0, Vec::new()),
+        ];
+        let mut stock = BTreeMap::from([(ItemId(1), 10)]);
+        let (_, made) = offline_earnings(600.0, &mut chain, &mut stock, &config);
+        assert_eq!(made[&ItemId(3)], 155);
+        assert_eq!(stock[&ItemId(1)], 0);
+    }
+
+    // runs read_save and the autosave against `dir` for about 7 seconds of play
+    fn play_on(dir: &Path) {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                250,
+            )))
+            .insert_resource(SaveStore::new(dir).with_backups(3))
+            .insert_resource(Money(BigNum::from(1.0)))
+            .init_resource::<Inventory>()
+            .init_resource::<Market>()
+            .init_resource::<Prestige>()
+            .add_message::<ToastEvent>()
+            .add_systems(Startup, read_save)
+            .add_systems(Update, autosave_every_5s_dev.run_if(autosave_allowed));
+        for _ in 0..30 {
+            app.update();
+        }
+    }
+
+    #[test]
+    fn unreadable_saves_are_never_saved_over() {
+        let dir = std::env::temp_dir().join(format!("tycoon_locked_{}", std::process::id()));
+        let store = SaveStore::new(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = store.path(SAVE_SLOT).unwrap();
+
+        // nothing there yet: autosave runs
+        play_on(&dir);
+        let fresh = fs::read_to_string(&path).unwrap();
+
+        let newer = fresh.replace(
+            &format!("version: {}", SaveFile::VERSION),
+            &format!("version: {}", SaveFile::VERSION + 1),
+        );
+        assert_ne!(newer, fresh);
+        for text in [newer.as_str(), "(money: oops"] {
+            fs::remove_dir_all(&dir).unwrap();
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(&path, text).unwrap();
+            play_on(&dir);
+            assert_eq!(fs::read_to_string(&path).unwrap(), text);
+            assert!(!dir.join(format!("{SAVE_SLOT}.ron.bak1")).exists());
         }
+        fs::remove_dir_all(&dir).unwrap();
     }
 }
@@ -0,0 +1,243 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use core_engine::prelude::*;
+use ui_common::prelude::*;
+
+use crate::{InventoryList, Money, prestige::Prestige};
+
+// prices sag as one item floods the market and climb back while it's left alone
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct MarketConfig {
+    // recent sales that halve an item's price
+    pub depth: f64,
+    // share of the glut that clears every second
+    pub recovery: f64,
+}
+
+impl Default for MarketConfig {
+    fn default() -> Self {
+        Self {
+            depth: 500.0,
+            recovery: 0.02,
+        }
+    }
+}
+
+#[derive(Resource, Default, Debug)]
+pub struct Market {
+    // recently sold and not yet absorbed, per item
+    glut: BTreeMap<ItemId, f64>,
+    // sold as soon as they're made
+    pub auto_sell: BTreeSet<ItemId>,
+}
+
+impl Market {
+    // what the next one sells for
+    pub fn price(&self, item: ItemId, base: f64, config: &MarketConfig) -> f64 {
+        base / (1.0 + self.glut(item) / config.depth)
+    }
+
+    // every unit of a batch at the price it pushes the market to, so selling
+    // in one go or one by one pays the same
+    pub fn sale_value(&self, item: ItemId, base: f64, count: u64, config: &MarketConfig) -> f64 {
+        let (d, g) = (config.depth, self.glut(item));
+        base * d * ((d + g + count as f64) / (d + g)).ln()
+    }
+
+    pub fn sell(&mut self, item: ItemId, base: f64, count: u64, config: &MarketConfig) -> BigNum {
+        let value = self.sale_value(item, base, count, config);
+        *self.glut.entry(item).or_default() += count as f64;
+        BigNum::from(value)
+    }
+
+    pub fn recover(&mut self, secs: f64, config: &MarketConfig) {
+        let keep = (-config.recovery * secs).exp();
+        for glut in self.glut.values_mut() {
+            *glut *= keep;
+        }
+        self.glut.retain(|_, glut| *glut > 0.01);
+    }
+
+    // every recent sale forgotten, for a fresh run
+    pub fn settle(&mut self) {
+        self.glut.clear();
+    }
+
+    // on or off, returns the new state
+    pub fn toggle_auto_sell(&mut self, item: ItemId) -> bool {
+        if !self.auto_sell.remove(&item) {
+            self.auto_sell.insert(item);
+        }
+        self.auto_sell.contains(&item)
+    }
+
+    fn glut(&self, item: ItemId) -> f64 {
+        self.glut.get(&item).copied().unwrap_or(0.0)
+    }
+}
+
+pub fn base_value(registry: &ItemRegistry, item: ItemId) -> f64 {
+    registry.get(item).map_or(0.0, |def| def.base_value as f64)
+}
+
+// selling out of the stock, for the sell button and auto-sell
+#[derive(SystemParam)]
+pub struct Trade<'w> {
+    pub market: ResMut<'w, Market>,
+    pub registry: Res<'w, ItemRegistry>,
+    config: Res<'w, MarketConfig>,
+    inv: ResMut<'w, Inventory>,
+    money: ResMut<'w, Money>,
+    prestige: ResMut<'w, Prestige>,
+}
+
+impl Trade<'_> {
+    // the whole stack of `item` at the going rate, None if there's nothing to sell
+    pub fn sell_all(&mut self, item: ItemId) -> Option<(u64, BigNum)> {
+        let count = self.inv.count(item).min(u32::MAX as u64) as u32;
+        // checked first, taking through ResMut marks the stock changed even when empty
+        if count == 0 {
+            return None;
+        }
+        let count = self.inv.take(item, count) as u64;
+        let base = base_value(&self.registry, item);
+        let value = self.market.sell(item, base, count, &self.config);
+        self.money.0 += value;
+        self.prestige.lifetime += value;
+        Some((count, value))
+    }
+}
+
+#[derive(Component)]
+pub struct BtnSellSelected;
+#[derive(Component)]
+pub struct BtnAutoSell;
+
+pub fn recover_market(time: Res<Time>, config: Res<MarketConfig>, mut market: ResMut<Market>) {
+    market.recover(time.delta_secs_f64(), &config);
+}
+
+pub fn auto_sell(mut trade: Trade) {
+    if !trade.inv.is_changed() || trade.market.auto_sell.is_empty() {
+        return;
+    }
+    let items: Vec<_> = trade.market.auto_sell.iter().copied().collect();
+    for item in items {
+        trade.sell_all(item);
+    }
+}
+
+// the row picked in the inventory list
+fn selected_item(lists: &Query<&ListView, With<InventoryList>>) -> Option<ItemId> {
+    let key = lists.iter().find_map(|l| l.selected)?;
+    u16::try_from(key).ok().map(ItemId)
+}
+
+pub fn click_sell_selected(
+    mut trade: Trade,
+    lists: Query<&ListView, With<InventoryList>>,
+    mut clicks: MessageReader<ButtonClicked>,
+    q_btn: Query<(), With<BtnSellSelected>>,
+    mut toasts: MessageWriter<ToastEvent>,
+) {
+    for _ in clicks.read().filter(|c| q_btn.contains(c.entity)) {
+        let Some(item) = selected_item(&lists) else {
+            toasts.write(ToastEvent::info("Pick an item to sell first"));
+            continue;
+        };
+        let name = trade.registry.name(item);
+        match trade.sell_all(item) {
+            Some((count, value)) => {
+                toasts.write(ToastEvent::success(format!(
+                    "Sold {} {name} for ${value}",
+                    BigNum::from(count)
+                )));
+            }
+            None => {
+                toasts.write(ToastEvent::info(format!("No {name} to sell")));
+            }
+        }
+    }
+}
+
+pub fn click_auto_sell(
+    mut market: ResMut<Market>,
+    registry: Res<ItemRegistry>,
+    lists: Query<&ListView, With<InventoryList>>,
+    mut clicks: MessageReader<ButtonClicked>,
+    q_btn: Query<(), With<BtnAutoSell>>,
+    mut toasts: MessageWriter<ToastEvent>,
+) {
+    for _ in clicks.read().filter(|c| q_btn.contains(c.entity)) {
+        let Some(item) = selected_item(&lists) else {
+            toasts.write(ToastEvent::info("Pick an item to auto-sell first"));
+            continue;
+        };
+        let on = market.toggle_auto_sell(item);
+        toasts.write(ToastEvent::info(format!(
+            "Auto-sell {} for {}",
+            if on { "on" } else { "off" },
+            registry.name(item)
+        )));
+    }
+}
+
+// the toggle says what it does for the row that's picked
+pub fn update_auto_sell_button(
+    market: Res<Market>,
+    lists: Query<Ref<ListView>, With<InventoryList>>,
+    buttons: Query<&Children, With<BtnAutoSell>>,
+    mut texts: Query<&mut Text, With<ButtonText>>,
+) {
+    if !(market.is_changed() || lists.iter().any(|l| l.is_changed())) {
+        return;
+    }
+    let item = lists
+        .iter()
+        .find_map(|l| l.selected)
+        .and_then(|k| u16::try_from(k).ok());
+    let label = match item {
+        Some(id) if market.auto_sell.contains(&ItemId(id)) => "AUTO-SELL: ON",
+        _ => "AUTO-SELL: OFF",
+    };
+    for children in &buttons {
+        for child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(child)
+                && text.0 != label
+            {
+                text.0 = label.into();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prices_react_to_supply() {
+        let config = MarketConfig {
+            depth: 100.0,
+            recovery: 0.1,
+        };
+        let mut market = Market::default();
+        let yarn = ItemId(1);
+        assert_eq!(market.price(yarn, 2.0, &config), 2.0);
+
+        // a glut the size of the depth halves the price
+        let whole = market.sale_value(yarn, 2.0, 100, &config);
+        market.sell(yarn, 2.0, 40, &config);
+        let rest = market.sale_value(yarn, 2.0, 60, &config);
+        market.sell(yarn, 2.0, 60, &config);
+        assert!((market.price(yarn, 2.0, &config) - 1.0).abs() < 1e-9);
+        // split batches pay what one big one would
+        let first = 2.0 * 100.0 * (140.0f64 / 100.0).ln();
+        assert!((first + rest - whole).abs() < 1e-9);
+
+        // and it drifts back while nothing is sold
+        market.recover(60.0, &config);
+        assert!(market.price(yarn, 2.0, &config) > 1.9);
+    }
+}
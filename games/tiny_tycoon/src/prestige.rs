@@ -0,0 +1,439 @@
+use std::collections::BTreeMap;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use core_engine::prelude::*;
+use serde::{Deserialize, Serialize};
+use ui_common::prelude::*;
+
+use crate::{
+    Money,
+    factories::Factory,
+    market::Market,
+    selection::{BlocksWorldClicks, SelectedFactory},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PrestigeEffect {
+    // added to the production multiplier per level
+    Production(f64),
+    // extra money every new run starts with, per level
+    StartingMoney(f64),
+    // share taken off factory prices per level
+    FactoryDiscount(f64),
+}
+
+// one node of the upgrade tree, bought with stars and kept through rebirths
+#[derive(Clone, Debug)]
+pub struct PrestigeUpgrade {
+    // what the save stores the level under
+    pub id: &'static str,
+    pub name: &'static str,
+    pub effect: PrestigeEffect,
+    // stars for the first level, every level after costs `cost_mult` times the last
+    pub cost: f64,
+    pub cost_mult: f64,
+    pub max_level: u32,
+    // upgrade and level it has to reach before this one opens up
+    pub requires: Option<(&'static str, u32)>,
+}
+
+impl PrestigeUpgrade {
+    pub fn cost_at(&self, level: u32) -> BigNum {
+        BigNum::from(self.cost) * BigNum::from(self.cost_mult).pow(level as f64)
+    }
+}
+
+// stars earned = floor((lifetime earnings / base) ^ exponent), less the ones claimed
+#[derive(Resource, Clone, Debug)]
+pub struct PrestigeConfig {
+    // lifetime earnings worth the first star
+    pub base: f64,
+    // below 1 so every star costs more than the last
+    pub exponent: f64,
+    // what a run starts with before StartingMoney upgrades
+    pub starting_money: f64,
+    pub upgrades: Vec<PrestigeUpgrade>,
+}
+
+impl Default for PrestigeConfig {
+    fn default() -> Self {
+        use PrestigeEffect::*;
+        Self {
+            base: 10_000.0,
+            exponent: 0.5,
+            starting_money: 100.0,
+            upgrades: vec![
+                PrestigeUpgrade {
+                    id: "lines",
+                    name: "Assembly Lines",
+                    effect: Production(0.25),
+                    cost: 1.0,
+                    cost_mult: 2.0,
+                    max_level: 10,
+                    requires: None,
+                },
+                PrestigeUpgrade {
+                    id: "seed",
+                    name: "Seed Money",
+                    effect: StartingMoney(250.0),
+                    cost: 1.0,
+                    cost_mult: 1.5,
+                    max_level: 10,
+                    requires: None,
+                },
+                PrestigeUpgrade {
+                    id: "bulk",
+                    name: "Bulk Deals",
+                    effect: FactoryDiscount(0.05),
+                    cost: 2.0,
+                    cost_mult: 2.0,
+                    max_level: 10,
+                    requires: Some(("seed", 2)),
+                },
+                PrestigeUpgrade {
+                    id: "overtime",
+                    name: "Overtime",
+                    effect: Production(0.5),
+                    cost: 10.0,
+                    cost_mult: 3.0,
+                    max_level: 5,
+                    requires: Some(("lines", 5)),
+                },
+            ],
+        }
+    }
+}
+
+impl PrestigeConfig {
+    pub fn stars_for(&self, lifetime: BigNum) -> BigNum {
+        (lifetime / self.base).pow(self.exponent).floor()
+    }
+
+    // lifetime earnings that make `stars` in total
+    pub fn earnings_for(&self, stars: BigNum) -> BigNum {
+        stars.pow(1.0 / self.exponent) * self.base
+    }
+
+    pub fn upgrade(&self, id: &str) -> Option<&PrestigeUpgrade> {
+        self.upgrades.iter().find(|u| u.id == id)
+    }
+}
+
+// what survives a rebirth. saved as is
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct Prestige {
+    // unspent
+    pub stars: BigNum,
+    // every star ever gained, spent or not
+    pub claimed: BigNum,
+    // money made selling over every run
+    pub lifetime: BigNum,
+    // levels bought, by upgrade id
+    pub upgrades: BTreeMap<String, u32>,
+}
+
+impl Prestige {
+    pub fn level(&self, id: &str) -> u32 {
+        self.upgrades.get(id).copied().unwrap_or(0)
+    }
+
+    // stars a rebirth right now would give
+    pub fn pending(&self, config: &PrestigeConfig) -> BigNum {
+        (config.stars_for(self.lifetime) - self.claimed).max(BigNum::ZERO)
+    }
+
+    pub fn production_mult(&self, config: &PrestigeConfig) -> f64 {
+        1.0 + self.sum(config, |e| match e {
+            PrestigeEffect::Production(x) => x,
+            _ => 0.0,
+        })
+    }
+
+    pub fn starting_money(&self, config: &PrestigeConfig) -> BigNum {
+        BigNum::from(
+            config.starting_money
+                + self.sum(config, |e| match e {
+                    PrestigeEffect::StartingMoney(x) => x,
+                    _ => 0.0,
+                }),
+        )
+    }
+
+    // factory prices get multiplied by this, never below a tenth
+    pub fn factory_price(&self, config: &PrestigeConfig) -> f64 {
+        let off = self.sum(config, |e| match e {
+            PrestigeEffect::FactoryDiscount(x) => x,
+            _ => 0.0,
+        });
+        (1.0 - off).max(0.1)
+    }
+
+    // the next level's price, or why there isn't one
+    pub fn next_cost(
+        &self,
+        upgrade: &PrestigeUpgrade,
+        config: &PrestigeConfig,
+    ) -> Result<BigNum, String> {
+        let level = self.level(upgrade.id);
+        if level >= upgrade.max_level {
+            return Err("maxed".into());
+        }
+        if let Some((id, needed)) = upgrade.requires
+            && self.level(id) < needed
+        {
+            let name = config.upgrade(id).map_or(id, |u| u.name);
+            return Err(format!("needs {name} {needed}"));
+        }
+        Ok(upgrade.cost_at(level))
+    }
+
+    // the new level
+    pub fn buy(&mut self, id: &str, config: &PrestigeConfig) -> Result<u32, String> {
+        let upgrade = config
+            .upgrade(id)
+            .ok_or_else(|| format!("no upgrade {id:?}"))?;
+        let cost = self.next_cost(upgrade, config)?;
+        if cost > self.stars {
+            return Err(format!("needs {cost} stars"));
+        }
+        self.stars -= cost;
+        let level = self.upgrades.entry(id.to_string()).or_default();
+        *level += 1;
+        Ok(*level)
+    }
+
+    fn sum(&self, config: &PrestigeConfig, value: impl Fn(PrestigeEffect) -> f64) -> f64 {
+        config
+            .upgrades
+            .iter()
+            .map(|u| value(u.effect) * self.level(u.id) as f64)
+            .sum()
+    }
+}
+
+#[derive(Component)]
+pub struct PrestigeList;
+#[derive(Component)]
+pub struct BtnRebirth;
+#[derive(Component)]
+pub struct BtnBuyPrestige;
+
+pub fn prestige_panel() -> impl Bundle {
+    (
+        panel(),
+        BlocksWorldClicks,
+        children![
+            (
+                themed_label("", TextRole::Label),
+                TextBinding::computed(prestige_summary),
+            ),
+            (
+                scroll_list(ListView::list(32.0, 320.0, 128.0)),
+                ListSource::computed(prestige_rows),
+                PrestigeList,
+            ),
+            (themed_button("BUY UPGRADE", "success"), BtnBuyPrestige),
+            (
+                themed_button("REBIRTH", "danger"),
+                Tooltip::new(
+                    "Start over with stars for what you've earned. Stars and upgrades stay"
+                ),
+                BtnRebirth,
+            ),
+        ],
+    )
+}
+
+// the gain preview: what a rebirth pays now and how far off the next star is
+fn prestige_summary(world: &World) -> String {
+    let (Some(prestige), Some(config)) = (
+        world.get_resource::<Prestige>(),
+        world.get_resource::<PrestigeConfig>(),
+    ) else {
+        return String::new();
+    };
+    let pending = prestige.pending(config);
+    let next = config.earnings_for(prestige.claimed + pending + 1.0);
+    format!(
+        "STARS {}  (x{:.2} production)\nRebirth now: +{pending} stars\nNext star at ${next} earned, ${} so far",
+        prestige.stars,
+        prestige.production_mult(config),
+        prestige.lifetime
+    )
+}
+
+fn prestige_rows(world: &World) -> Vec<ListItem> {
+    let (Some(prestige), Some(config)) = (
+        world.get_resource::<Prestige>(),
+        world.get_resource::<PrestigeConfig>(),
+    ) else {
+        return Vec::new();
+    };
+    config
+        .upgrades
+        .iter()
+        .enumerate()
+        .map(|(i, upgrade)| {
+            let level = prestige.level(upgrade.id);
+            let next = match prestige.next_cost(upgrade, config) {
+                Ok(cost) => format!("{cost} stars"),
+                Err(why) => why,
+            };
+            ListItem::new(i as u64, upgrade.name)
+                .detail(format!("lv {level}/{}  {next}", upgrade.max_level))
+        })
+        .collect()
+}
+
+// keeps every factory's ProductionRate in step with the upgrades
+pub fn apply_prestige_bonus(
+    prestige: Res<Prestige>,
+    config: Res<PrestigeConfig>,
+    mut q: Query<(Ref<Factory>, &mut Stats)>,
+) {
+    let all = prestige.is_changed() || config.is_changed();
+    let mult = prestige.production_mult(&config) as f32;
+    for (factory, mut stats) in &mut q {
+        if all || factory.is_added() {
+            stats.set_modifier(StatId::ProductionRate, StatModifier::mult("prestige", mult));
+        }
+    }
+}
+
+pub fn click_buy_prestige(
+    mut prestige: ResMut<Prestige>,
+    config: Res<PrestigeConfig>,
+    lists: Query<&ListView, With<PrestigeList>>,
+    mut clicks: MessageReader<ButtonClicked>,
+    q_btn: Query<(), With<BtnBuyPrestige>>,
+    mut toasts: MessageWriter<ToastEvent>,
+) {
+    for _ in clicks.read().filter(|c| q_btn.contains(c.entity)) {
+        let Some(upgrade) = lists
+            .iter()
+            .find_map(|l| l.selected)
+            .and_then(|i| config.upgrades.get(i as usize))
+        else {
+            toasts.write(ToastEvent::info("Pick an upgrade first"));
+            continue;
+        };
+        match prestige.buy(upgrade.id, &config) {
+            Ok(level) => {
+                toasts.write(ToastEvent::success(format!(
+                    "{} is now level {level}",
+                    upgrade.name
+                )));
+            }
+            Err(why) => {
+                toasts.write(ToastEvent::warning(format!("{}: {why}", upgrade.name)));
+            }
+        }
+    }
+}
+
+pub fn click_rebirth(
+    prestige: Res<Prestige>,
+    config: Res<PrestigeConfig>,
+    mut clicks: MessageReader<ButtonClicked>,
+    q_btn: Query<(), With<BtnRebirth>>,
+    mut dialogs: MessageWriter<OpenDialog>,
+    mut toasts: MessageWriter<ToastEvent>,
+) {
+    for _ in clicks.read().filter(|c| q_btn.contains(c.entity)) {
+        let pending = prestige.pending(&config);
+        if pending.is_zero() {
+            toasts.write(ToastEvent::info(format!(
+                "The next star takes ${} earned in total",
+                config.earnings_for(prestige.claimed + 1.0)
+            )));
+            continue;
+        }
+        dialogs.write(
+            OpenDialog::confirm(
+                "rebirth",
+                "REBIRTH?",
+                format!(
+                    "Money, stock and factories go for +{pending} stars. Stars, upgrades and auto-sell stay."
+                ),
+            )
+            .with_labels("REBIRTH", "NOT YET"),
+        );
+    }
+}
+
+// everything a rebirth clears
+#[derive(SystemParam)]
+pub struct Run<'w, 's> {
+    commands: Commands<'w, 's>,
+    money: ResMut<'w, Money>,
+    inv: ResMut<'w, Inventory>,
+    market: ResMut<'w, Market>,
+    selected: ResMut<'w, SelectedFactory>,
+    factories: Query<'w, 's, Entity, With<Factory>>,
+}
+
+pub fn answer_rebirth(
+    mut answers: MessageReader<DialogResult>,
+    mut prestige: ResMut<Prestige>,
+    config: Res<PrestigeConfig>,
+    mut run: Run,
+    mut toasts: MessageWriter<ToastEvent>,
+) {
+    for _ in answers.read().filter(|a| a.id == "rebirth" && a.confirmed) {
+        let pending = prestige.pending(&config);
+        prestige.stars += pending;
+        prestige.claimed += pending;
+
+        for entity in &run.factories {
+            run.commands.entity(entity).despawn();
+        }
+        run.money.0 = prestige.starting_money(&config);
+        run.inv.clear();
+        run.market.settle();
+        run.selected.0 = None;
+        toasts.write(ToastEvent::success(format!("Reborn with +{pending} stars")));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stars_follow_lifetime_earnings() {
+        let config = PrestigeConfig::default();
+        let mut prestige = Prestige {
+            lifetime: BigNum::from(90_000.0),
+            ..default()
+        };
+        // sqrt(90k / 10k) = 3
+        assert_eq!(prestige.pending(&config), BigNum::from(3.0));
+        assert_eq!(
+            config.earnings_for(BigNum::from(4.0)),
+            BigNum::from(160_000.0)
+        );
+
+        // claimed stars don't come back on the next rebirth
+        prestige.claimed = BigNum::from(3.0);
+        prestige.stars = BigNum::from(3.0);
+        assert!(prestige.pending(&config).is_zero());
+
+        // 1 + 2 stars for two levels, the third is out of reach
+        assert_eq!(prestige.buy("lines", &config), Ok(1));
+        assert_eq!(prestige.buy("lines", &config), Ok(2));
+        assert!(prestige.buy("lines", &config).is_err());
+        assert_eq!(prestige.production_mult(&config), 1.5);
+
+        // locked until its requirement is met
+        prestige.stars = BigNum::from(100.0);
+        assert_eq!(
+            prestige.buy("bulk", &config),
+            Err("needs Seed Money 2".into())
+        );
+        prestige.buy("seed", &config).unwrap();
+        prestige.buy("seed", &config).unwrap();
+        prestige.buy("bulk", &config).unwrap();
+        assert_eq!(prestige.starting_money(&config), BigNum::from(600.0));
+        assert!((prestige.factory_price(&config) - 0.95).abs() < 1e-9);
+    }
+}
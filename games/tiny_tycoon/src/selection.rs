@@ -0,0 +1,311 @@
+use bevy::{
+    ecs::system::SystemParam, prelude::*, ui::RelativeCursorPosition, window::PrimaryWindow,
+};
+use core_engine::prelude::*;
+use ui_common::{prelude::*, theme::ActiveTheme};
+
+use crate::{
+    Money,
+    factories::*,
+    prestige::{Prestige, PrestigeConfig},
+};
+
+// the factory the inspector shows, picked in the world or from the factory list
+#[derive(Resource, Default, PartialEq, Eq)]
+pub struct SelectedFactory(pub Option<Entity>);
+
+// a UI panel that keeps clicks from reaching the factories behind it
+#[derive(Component)]
+#[require(RelativeCursorPosition)]
+pub struct BlocksWorldClicks;
+
+#[derive(Component)]
+pub struct FactoryList;
+#[derive(Component)]
+pub struct BtnUpgradeSelected;
+#[derive(Component)]
+pub struct BtnUpgradeAll;
+#[derive(Component)]
+pub struct BtnBuyMode;
+
+// what every purchase looks at
+#[derive(SystemParam)]
+pub struct Shop<'w> {
+    pub money: ResMut<'w, Money>,
+    pub mode: Res<'w, BuyMode>,
+    pub catalog: Res<'w, FactoryCatalog>,
+    prestige: Res<'w, Prestige>,
+    prestige_config: Res<'w, PrestigeConfig>,
+}
+
+impl Shop<'_> {
+    // after prestige discounts
+    pub fn factory_cost(&self, def: &FactoryDef) -> BigNum {
+        def.cost * self.prestige.factory_price(&self.prestige_config)
+    }
+}
+
+pub fn inspector_panel() -> impl Bundle {
+    (
+        panel(),
+        BlocksWorldClicks,
+        children![
+            (
+                themed_label("", TextRole::Heading),
+                TextBinding::computed(inspector_title),
+            ),
+            (
+                themed_label("", TextRole::Label),
+                TextBinding::computed(inspector_details),
+            ),
+            (
+                themed_button("UPGRADE", "success"),
+                BtnUpgradeSelected,
+                Disabled,
+            ),
+        ],
+    )
+}
+
+fn selected(world: &World) -> Option<(&Factory, &Produces, &FactoryDef)> {
+    let entity = world.get_resource::<SelectedFactory>()?.0?;
+    let factory = world.get::<Factory>(entity)?;
+    let def = world.get_resource::<FactoryCatalog>()?.get(factory.kind)?;
+    Some((factory, world.get::<Produces>(entity)?, def))
+}
+
+fn inspector_title(world: &World) -> String {
+    selected(world).map_or_else(|| "NO FACTORY".into(), |(_, _, def)| def.name.clone())
+}
+
+fn inspector_details(world: &World) -> String {
+    let Some((factory, produces, def)) = selected(world) else {
+        return "Click a factory to inspect it".into();
+    };
+    let item = world
+        .get_resource::<ItemRegistry>()
+        .map_or_else(String::new, |r| r.name(produces.item));
+    let mut text = format!(
+        "Level {}\nMakes {}/s {item}",
+        factory.level, produces.per_second
+    );
+    if factory.level < u8::MAX {
+        // boosts from Stats carry over to the next level
+        let boost = produces.per_second / def.rate_at(factory.level);
+        text.push_str(&format!(
+            "\nNext level: {}/s for ${}",
+            def.rate_at(factory.level + 1) * boost,
+            def.upgrade_cost(factory.level)
+        ));
+    }
+    text
+}
+
+pub fn pick_factory(
+    mouse: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    panels: Query<&RelativeCursorPosition, With<BlocksWorldClicks>>,
+    factories: Query<(Entity, &GlobalTransform, &Sprite), With<Factory>>,
+    mut selected: ResMut<SelectedFactory>,
+) {
+    if !mouse.just_pressed(MouseButton::Left)
+        || panels.iter().any(RelativeCursorPosition::cursor_over)
+    {
+        return;
+    }
+    let Some(cursor) = windows.single().ok().and_then(Window::cursor_position) else {
+        return;
+    };
+    let Some(at) = cameras
+        .iter()
+        .find_map(|(camera, tr)| camera.viewport_to_world_2d(tr, cursor).ok())
+    else {
+        return;
+    };
+    // the sprite's bounds; where they overlap the one drawn on top wins
+    let hit = factories
+        .iter()
+        .filter(|(_, tr, sprite)| {
+            let half =
+                sprite.custom_size.unwrap_or(Vec2::splat(64.0)) * tr.scale().truncate() / 2.0;
+            let d = (at - tr.translation().truncate()).abs();
+            d.x <= half.x && d.y <= half.y
+        })
+        .max_by(|a, b| a.1.translation().z.total_cmp(&b.1.translation().z))
+        .map(|(e, ..)| e);
+    // clicking empty ground clears it
+    selected.set_if_neq(SelectedFactory(hit));
+}
+
+pub fn select_from_list(
+    mut events: MessageReader<ListSelected>,
+    lists: Query<(), With<FactoryList>>,
+    mut selected: ResMut<SelectedFactory>,
+) {
+    for ev in events.read().filter(|ev| lists.contains(ev.list)) {
+        selected.set_if_neq(SelectedFactory(Entity::try_from_bits(ev.key)));
+    }
+}
+
+// world picks show up as the selected row too
+pub fn sync_list_selection(
+    selected: Res<SelectedFactory>,
+    mut lists: Query<&mut ListView, With<FactoryList>>,
+) {
+    if !selected.is_changed() {
+        return;
+    }
+    let key = selected.0.map(Entity::to_bits);
+    for mut list in &mut lists {
+        if list.selected != key {
+            list.selected = key;
+        }
+    }
+}
+
+pub fn highlight_selected(
+    selected: Res<SelectedFactory>,
+    factories: Query<(&GlobalTransform, &Sprite), With<Factory>>,
+    theme: ActiveTheme,
+    mut gizmos: Gizmos,
+) {
+    let Some((tr, sprite)) = selected.0.and_then(|e| factories.get(e).ok()) else {
+        return;
+    };
+    let size = sprite.custom_size.unwrap_or(Vec2::splat(64.0)) * tr.scale().truncate();
+    gizmos.rect_2d(
+        Isometry2d::from_translation(tr.translation().truncate()),
+        size + Vec2::splat(10.0),
+        theme.color("accent", Color::srgb(0.85, 0.3, 0.0)),
+    );
+}
+
+// the inspector's button shows what a click buys in the current mode
+pub fn update_upgrade_button(
+    mut commands: Commands,
+    shop: Shop,
+    selected: Res<SelectedFactory>,
+    factories: Query<Ref<Factory>>,
+    buttons: Query<(Entity, &Children, Has<Disabled>), With<BtnUpgradeSelected>>,
+    mut texts: Query<&mut Text, With<ButtonText>>,
+) {
+    let factory = selected.0.and_then(|e| factories.get(e).ok());
+    let changed = selected.is_changed()
+        || shop.money.is_changed()
+        || shop.mode.is_changed()
+        || shop.catalog.is_changed()
+        || factory.as_ref().is_some_and(Ref::is_changed);
+    if !changed {
+        return;
+    }
+    let (label, enabled) = match factory
+        .as_ref()
+        .and_then(|f| Some((f, shop.catalog.get(f.kind)?)))
+    {
+        None => ("UPGRADE".to_string(), false),
+        Some((f, _)) if f.level == u8::MAX => ("MAX LEVEL".to_string(), false),
+        Some((f, def)) => {
+            let (levels, cost) = def.upgrade_plan(f.level, *shop.mode, shop.money.0);
+            (format!("UPGRADE x{levels}  ${cost}"), cost <= shop.money.0)
+        }
+    };
+    for (entity, children, disabled) in &buttons {
+        for child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(child)
+                && text.0 != label
+            {
+                text.0.clone_from(&label);
+            }
+        }
+        match (enabled, disabled) {
+            (true, true) => {
+                commands.entity(entity).remove::<Disabled>();
+            }
+            (false, false) => {
+                commands.entity(entity).insert(Disabled);
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn click_upgrade_selected(
+    mut shop: Shop,
+    selected: Res<SelectedFactory>,
+    mut factories: Query<&mut Factory>,
+    mut clicks: MessageReader<ButtonClicked>,
+    q_btn: Query<(), With<BtnUpgradeSelected>>,
+    mut toasts: MessageWriter<ToastEvent>,
+) {
+    for _ in clicks.read().filter(|c| q_btn.contains(c.entity)) {
+        let Some(mut factory) = selected.0.and_then(|e| factories.get_mut(e).ok()) else {
+            continue;
+        };
+        let Some(def) = shop.catalog.get(factory.kind) else {
+            continue;
+        };
+        let (levels, cost) = def.upgrade_plan(factory.level, *shop.mode, shop.money.0);
+        if levels == 0 {
+            continue;
+        }
+        if shop.money.0 >= cost {
+            shop.money.0 -= cost;
+            // sync_production_rate picks the new level's rate from the catalog
+            factory.level += levels as u8;
+            toasts.write(ToastEvent::success(format!(
+                "{} upgraded to level {}",
+                def.name, factory.level
+            )));
+        } else {
+            toasts.write(ToastEvent::warning(format!(
+                "Upgrade needs ${cost}, you have ${}",
+                shop.money.0
+            )));
+        }
+    }
+}
+
+pub fn click_upgrade_all(
+    mut shop: Shop,
+    mut factories: Query<&mut Factory>,
+    mut clicks: MessageReader<ButtonClicked>,
+    q_btn: Query<(), With<BtnUpgradeAll>>,
+    mut toasts: MessageWriter<ToastEvent>,
+) {
+    for _ in clicks.read().filter(|c| q_btn.contains(c.entity)) {
+        let mut levels: Vec<(FactoryKind, u8)> =
+            factories.iter().map(|f| (f.kind, f.level)).collect();
+        let (bought, spent) = upgrade_all_affordable(&shop.catalog, &mut levels, shop.money.0);
+        if bought == 0 {
+            toasts.write(ToastEvent::info("No upgrade is affordable yet"));
+            continue;
+        }
+        shop.money.0 -= spent;
+        // same iteration order as when the levels were collected
+        for (mut factory, (_, level)) in factories.iter_mut().zip(levels) {
+            if factory.level != level {
+                factory.level = level;
+            }
+        }
+        toasts.write(ToastEvent::success(format!(
+            "Bought {bought} upgrades for ${spent}"
+        )));
+    }
+}
+
+pub fn click_buy_mode(
+    mut mode: ResMut<BuyMode>,
+    mut clicks: MessageReader<ButtonClicked>,
+    q_btn: Query<&Children, With<BtnBuyMode>>,
+    mut texts: Query<&mut Text, With<ButtonText>>,
+) {
+    for children in clicks.read().filter_map(|c| q_btn.get(c.entity).ok()) {
+        *mode = mode.next();
+        for child in children.iter() {
+            if let Ok(mut text) = texts.get_mut(child) {
+                text.0 = format!("BUY MODE: {}", mode.label());
+            }
+        }
+    }
+}